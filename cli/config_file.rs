@@ -1,6 +1,7 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
 use crate::fs_util::canonicalize_path;
+use crate::fs_util::glob_to_regex;
 use crate::fs_util::specifier_parent;
 use crate::fs_util::specifier_to_file_path;
 
@@ -16,6 +17,7 @@ use deno_core::serde_json;
 use deno_core::serde_json::json;
 use deno_core::serde_json::Value;
 use deno_core::ModuleSpecifier;
+use regex::Regex;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::fmt;
@@ -351,6 +353,8 @@ impl FilesConfig {
 struct SerializedLintConfig {
   pub rules: LintRulesConfig,
   pub files: SerializedFilesConfig,
+  pub plugins: Vec<String>,
+  pub report: Option<LintReporterKind>,
 }
 
 impl SerializedLintConfig {
@@ -358,20 +362,41 @@ impl SerializedLintConfig {
     self,
     config_file_specifier: &ModuleSpecifier,
   ) -> Result<LintConfig, AnyError> {
+    let config_dir = specifier_parent(config_file_specifier);
     Ok(LintConfig {
       rules: self.rules,
       files: self.files.into_resolved(config_file_specifier)?,
+      plugins: self
+        .plugins
+        .into_iter()
+        .map(|p| config_dir.join(&p))
+        .collect::<Result<Vec<ModuleSpecifier>, _>>()?,
+      report: self.report,
     })
   }
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub enum LintReporterKind {
+  Pretty,
+  Json,
+  Compact,
+  Github,
+  Sarif,
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct LintConfig {
   pub rules: LintRulesConfig,
   pub files: FilesConfig,
+  /// Modules providing additional lint rules, see `tools::lint_plugins`.
+  pub plugins: Vec<ModuleSpecifier>,
+  /// The format of the lint result, unless overridden by `--reporter`.
+  pub report: Option<LintReporterKind>,
 }
 
-#[derive(Clone, Copy, Debug, Deserialize)]
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields, rename_all = "camelCase")]
 pub enum ProseWrap {
   Always,
@@ -379,7 +404,46 @@ pub enum ProseWrap {
   Preserve,
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub enum TrailingCommas {
+  Always,
+  Never,
+  OnlyMultiLine,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub enum BracePosition {
+  Maintain,
+  SameLine,
+  NextLine,
+  SameLineUnlessHanging,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub enum OperatorPosition {
+  Maintain,
+  SameLine,
+  NextLine,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub enum QuoteProps {
+  AsNeeded,
+  Preserve,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub enum JsxQuoteStyle {
+  PreferDouble,
+  PreferSingle,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(default, deny_unknown_fields, rename_all = "camelCase")]
 pub struct FmtOptionsConfig {
   pub use_tabs: Option<bool>,
@@ -387,6 +451,12 @@ pub struct FmtOptionsConfig {
   pub indent_width: Option<u8>,
   pub single_quote: Option<bool>,
   pub prose_wrap: Option<ProseWrap>,
+  pub semi_colons: Option<bool>,
+  pub trailing_commas: Option<TrailingCommas>,
+  pub brace_position: Option<BracePosition>,
+  pub operator_position: Option<OperatorPosition>,
+  pub quote_props: Option<QuoteProps>,
+  pub jsx_quote_style: Option<JsxQuoteStyle>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
@@ -414,12 +484,135 @@ pub struct FmtConfig {
   pub files: FilesConfig,
 }
 
+/// Minimum coverage percentages, from 0 to 100.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct CoverageThresholds {
+  pub lines: Option<f64>,
+  pub branches: Option<f64>,
+  pub functions: Option<f64>,
+}
+
+impl CoverageThresholds {
+  pub fn is_empty(&self) -> bool {
+    self.lines.is_none() && self.branches.is_none() && self.functions.is_none()
+  }
+
+  /// Returns a copy where the thresholds set in `other` take precedence.
+  pub fn merge(&self, other: &CoverageThresholds) -> CoverageThresholds {
+    CoverageThresholds {
+      lines: other.lines.or(self.lines),
+      branches: other.branches.or(self.branches),
+      functions: other.functions.or(self.functions),
+    }
+  }
+
+  fn validate(&self) -> Result<(), AnyError> {
+    for (name, maybe_value) in [
+      ("lines", self.lines),
+      ("branches", self.branches),
+      ("functions", self.functions),
+    ] {
+      if let Some(value) = maybe_value {
+        if !(0.0..=100.0).contains(&value) {
+          bail!(
+            "Coverage threshold for {} should be between 0 and 100, got {}",
+            name,
+            value
+          );
+        }
+      }
+    }
+    Ok(())
+  }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SerializedCoverageThresholdsConfig {
+  pub lines: Option<f64>,
+  pub branches: Option<f64>,
+  pub functions: Option<f64>,
+  pub files: BTreeMap<String, CoverageThresholds>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SerializedCoverageConfig {
+  pub thresholds: SerializedCoverageThresholdsConfig,
+}
+
+impl SerializedCoverageConfig {
+  pub fn into_resolved(
+    self,
+    config_file_specifier: &ModuleSpecifier,
+  ) -> Result<CoverageConfig, AnyError> {
+    let thresholds = CoverageThresholds {
+      lines: self.thresholds.lines,
+      branches: self.thresholds.branches,
+      functions: self.thresholds.functions,
+    };
+    thresholds.validate()?;
+
+    let mut files = Vec::new();
+    for (glob, file_thresholds) in self.thresholds.files {
+      file_thresholds.validate()?;
+      files.push(FileCoverageThresholds {
+        matcher: glob_to_regex(&glob),
+        glob,
+        thresholds: file_thresholds,
+      });
+    }
+
+    Ok(CoverageConfig {
+      thresholds,
+      files,
+      base: specifier_parent(config_file_specifier),
+    })
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct FileCoverageThresholds {
+  pub glob: String,
+  matcher: Regex,
+  pub thresholds: CoverageThresholds,
+}
+
+#[derive(Clone, Debug)]
+pub struct CoverageConfig {
+  /// Thresholds for the combined coverage of all files.
+  pub thresholds: CoverageThresholds,
+  /// Thresholds that every file matching the glob has to meet on its own.
+  pub files: Vec<FileCoverageThresholds>,
+  base: ModuleSpecifier,
+}
+
+impl CoverageConfig {
+  /// Returns the per-file thresholds that apply to the given module. Globs
+  /// are matched against the specifier relative to the configuration file.
+  pub fn file_thresholds<'a>(
+    &'a self,
+    specifier: &'a str,
+  ) -> impl Iterator<Item = &'a FileCoverageThresholds> {
+    let relative = specifier
+      .strip_prefix(self.base.as_str())
+      .unwrap_or(specifier);
+    self
+      .files
+      .iter()
+      .filter(move |file| file.matcher.is_match(relative))
+  }
+}
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ConfigFileJson {
   pub compiler_options: Option<Value>,
   pub lint: Option<Value>,
   pub fmt: Option<Value>,
+  pub coverage: Option<Value>,
+  pub files: Option<Value>,
 }
 
 #[derive(Clone, Debug)]
@@ -579,6 +772,29 @@ impl ConfigFile {
       Ok(None)
     }
   }
+
+  pub fn to_coverage_config(&self) -> Result<Option<CoverageConfig>, AnyError> {
+    if let Some(config) = self.json.coverage.clone() {
+      let coverage_config: SerializedCoverageConfig =
+        serde_json::from_value(config)
+          .context("Failed to parse \"coverage\" configuration")?;
+      Ok(Some(coverage_config.into_resolved(&self.specifier)?))
+    } else {
+      Ok(None)
+    }
+  }
+
+  /// The workspace-wide `files` configuration, used by tools which operate on
+  /// the whole project rather than on an explicit set of modules.
+  pub fn to_files_config(&self) -> Result<Option<FilesConfig>, AnyError> {
+    if let Some(config) = self.json.files.clone() {
+      let files_config: SerializedFilesConfig = serde_json::from_value(config)
+        .context("Failed to parse \"files\" configuration")?;
+      Ok(Some(files_config.into_resolved(&self.specifier)?))
+    } else {
+      Ok(None)
+    }
+  }
 }
 
 #[cfg(test)]
@@ -645,7 +861,9 @@ mod tests {
         "rules": {
           "tags": ["recommended"],
           "include": ["ban-untagged-todo"]
-        }
+        },
+        "plugins": ["./lint/house_rules.ts"],
+        "report": "compact"
       },
       "fmt": {
         "files": {
@@ -657,7 +875,13 @@ mod tests {
           "lineWidth": 80,
           "indentWidth": 4,
           "singleQuote": true,
-          "proseWrap": "preserve"
+          "proseWrap": "preserve",
+          "semiColons": false,
+          "trailingCommas": "onlyMultiLine",
+          "bracePosition": "nextLine",
+          "operatorPosition": "sameLine",
+          "quoteProps": "asNeeded",
+          "jsxQuoteStyle": "preferSingle"
         }
       }
     }"#;
@@ -699,6 +923,11 @@ mod tests {
       Some(vec!["recommended".to_string()])
     );
     assert!(lint_config.rules.exclude.is_none());
+    assert_eq!(
+      lint_config.plugins,
+      vec![config_dir.join("lint/house_rules.ts").unwrap()]
+    );
+    assert_eq!(lint_config.report, Some(LintReporterKind::Compact));
 
     let fmt_config = config_file
       .to_fmt_config()
@@ -716,6 +945,119 @@ mod tests {
     assert_eq!(fmt_config.options.line_width, Some(80));
     assert_eq!(fmt_config.options.indent_width, Some(4));
     assert_eq!(fmt_config.options.single_quote, Some(true));
+    assert_eq!(fmt_config.options.semi_colons, Some(false));
+    assert_eq!(
+      fmt_config.options.trailing_commas,
+      Some(TrailingCommas::OnlyMultiLine)
+    );
+    assert_eq!(
+      fmt_config.options.brace_position,
+      Some(BracePosition::NextLine)
+    );
+    assert_eq!(
+      fmt_config.options.operator_position,
+      Some(OperatorPosition::SameLine)
+    );
+    assert_eq!(fmt_config.options.quote_props, Some(QuoteProps::AsNeeded));
+    assert_eq!(
+      fmt_config.options.jsx_quote_style,
+      Some(JsxQuoteStyle::PreferSingle)
+    );
+  }
+
+  #[test]
+  fn test_parse_config_with_invalid_fmt_option() {
+    let config_text = r#"{
+      "fmt": {
+        "options": {
+          "trailingCommas": "sometimes"
+        }
+      }
+    }"#;
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/tsconfig.json").unwrap();
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    let err = config_file.to_fmt_config().unwrap_err();
+    assert!(format!("{:#}", err).contains("unknown variant `sometimes`"));
+  }
+
+  #[test]
+  fn test_parse_coverage_config() {
+    let config_text = r#"{
+      "coverage": {
+        "thresholds": {
+          "lines": 80,
+          "branches": 75.5,
+          "files": {
+            "src/**/*.ts": { "functions": 100 }
+          }
+        }
+      }
+    }"#;
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    let coverage_config = config_file
+      .to_coverage_config()
+      .expect("error parsing coverage object")
+      .expect("coverage object should be defined");
+    assert_eq!(
+      coverage_config.thresholds,
+      CoverageThresholds {
+        lines: Some(80.0),
+        branches: Some(75.5),
+        functions: None,
+      }
+    );
+    assert_eq!(coverage_config.files.len(), 1);
+    assert_eq!(coverage_config.files[0].glob, "src/**/*.ts");
+    assert_eq!(
+      coverage_config
+        .file_thresholds("file:///deno/src/util/mod.ts")
+        .count(),
+      1
+    );
+    assert_eq!(
+      coverage_config
+        .file_thresholds("file:///other/src/mod.ts")
+        .count(),
+      0
+    );
+
+    let config_text = r#"{ "coverage": { "thresholds": { "lines": 120 } } }"#;
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    assert!(config_file.to_coverage_config().is_err());
+  }
+
+  #[test]
+  fn test_parse_files_config() {
+    let config_text = r#"{
+      "files": {
+        "include": ["src/"],
+        "exclude": ["src/testdata/"]
+      }
+    }"#;
+    let config_dir = ModuleSpecifier::parse("file:///deno/").unwrap();
+    let config_specifier = config_dir.join("deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    let files_config = config_file
+      .to_files_config()
+      .expect("error parsing files object")
+      .expect("files object should be defined");
+    assert_eq!(files_config.include, vec![config_dir.join("src/").unwrap()]);
+    assert_eq!(
+      files_config.exclude,
+      vec![config_dir.join("src/testdata/").unwrap()]
+    );
+    assert!(
+      files_config.matches_specifier(&config_dir.join("src/mod.ts").unwrap())
+    );
+    assert!(!files_config
+      .matches_specifier(&config_dir.join("src/testdata/a.ts").unwrap()));
+
+    let config_text = r#"{ "files": { "include": "src/" } }"#;
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    assert!(config_file.to_files_config().is_err());
   }
 
   #[test]
@@ -11,6 +11,9 @@ pub struct DenoDir {
   pub root: PathBuf,
   /// Used by TsCompiler to cache compiler output.
   pub gen_cache: DiskCache,
+  /// Used by `deno lint` and `deno fmt` to skip files which are unchanged
+  /// since they were last processed.
+  pub incremental_cache: DiskCache,
 }
 
 impl DenoDir {
@@ -34,10 +37,12 @@ impl DenoDir {
     };
     assert!(root.is_absolute());
     let gen_path = root.join("gen");
+    let incremental_path = root.join("incremental");
 
     let deno_dir = Self {
       root,
       gen_cache: DiskCache::new(&gen_path),
+      incremental_cache: DiskCache::new(&incremental_path),
     };
     deno_dir.gen_cache.ensure_dir_exists(&gen_path)?;
 
@@ -1,13 +1,17 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
+use crate::version;
 use deno_runtime::colors;
 
 use deno_core::serde::Deserialize;
 use deno_core::serde::Deserializer;
 use deno_core::serde::Serialize;
 use deno_core::serde::Serializer;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
 use deno_graph::ModuleGraphError;
 use regex::Regex;
+use std::collections::BTreeSet;
 use std::error::Error;
 use std::fmt;
 
@@ -131,6 +135,25 @@ impl fmt::Display for DiagnosticCategory {
   }
 }
 
+impl DiagnosticCategory {
+  fn as_str(&self) -> &'static str {
+    match self {
+      DiagnosticCategory::Warning => "warning",
+      DiagnosticCategory::Error => "error",
+      DiagnosticCategory::Suggestion => "suggestion",
+      DiagnosticCategory::Message => "message",
+    }
+  }
+
+  fn as_sarif_level(&self) -> &'static str {
+    match self {
+      DiagnosticCategory::Warning => "warning",
+      DiagnosticCategory::Error => "error",
+      DiagnosticCategory::Suggestion | DiagnosticCategory::Message => "note",
+    }
+  }
+}
+
 impl<'de> Deserialize<'de> for DiagnosticCategory {
   fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
   where
@@ -193,6 +216,15 @@ impl DiagnosticMessageChain {
 
     s
   }
+
+  fn to_json(&self) -> Value {
+    json!({
+      "message": self.message_text,
+      "category": self.category.as_str(),
+      "code": self.code,
+      "next": self.next.iter().flatten().map(|chain| chain.to_json()).collect::<Vec<_>>(),
+    })
+  }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, Eq, PartialEq)]
@@ -334,6 +366,93 @@ impl Diagnostic {
   fn is_error(&self) -> bool {
     self.category == DiagnosticCategory::Error
   }
+
+  /// The message without colors, with any message chain flattened into
+  /// indented lines.
+  fn message(&self) -> String {
+    if let Some(message_chain) = &self.message_chain {
+      message_chain.format_message(0)
+    } else {
+      format_message(
+        self.message_text.as_deref().unwrap_or_default(),
+        &self.code,
+      )
+    }
+  }
+
+  /// Codes from 900001 up are used for diagnostics that don't come from tsc.
+  fn rule_id(&self) -> String {
+    if self.code >= 900001 {
+      "module-graph".to_string()
+    } else {
+      format!("TS{}", self.code)
+    }
+  }
+
+  fn to_json(&self) -> Value {
+    let range = match (&self.start, &self.end) {
+      (Some(start), Some(end)) => json!({ "start": start, "end": end }),
+      _ => Value::Null,
+    };
+    json!({
+      "code": self.code,
+      "category": self.category.as_str(),
+      "file": self.file_name,
+      "range": range,
+      "message": self.message(),
+      "messageChain": self.message_chain.as_ref().map(|chain| chain.to_json()),
+      "relatedInformation": self
+        .related_information
+        .iter()
+        .flatten()
+        .map(|info| info.to_json())
+        .collect::<Vec<_>>(),
+    })
+  }
+
+  /// SARIF lines and columns are one-based, where those of tsc are zero-based.
+  fn to_sarif_location(&self) -> Value {
+    let mut physical_location = json!({
+      "artifactLocation": { "uri": self.file_name },
+    });
+    if let (Some(start), Some(end)) = (&self.start, &self.end) {
+      physical_location["region"] = json!({
+        "startLine": start.line + 1,
+        "startColumn": start.character + 1,
+        "endLine": end.line + 1,
+        "endColumn": end.character + 1,
+      });
+    }
+    json!({ "physicalLocation": physical_location })
+  }
+
+  fn to_sarif_result(&self) -> Value {
+    let locations = if self.file_name.is_some() {
+      vec![self.to_sarif_location()]
+    } else {
+      vec![]
+    };
+    let related_locations = self
+      .related_information
+      .iter()
+      .flatten()
+      .filter(|info| info.file_name.is_some())
+      .enumerate()
+      .map(|(id, info)| {
+        let mut location = info.to_sarif_location();
+        location["id"] = json!(id);
+        location["message"] = json!({ "text": info.message() });
+        location
+      })
+      .collect::<Vec<_>>();
+    json!({
+      "ruleId": self.rule_id(),
+      "level": self.category.as_sarif_level(),
+      "message": { "text": self.message() },
+      "locations": locations,
+      "relatedLocations": related_locations,
+    })
+  }
 }
 
 impl fmt::Display for Diagnostic {
@@ -380,6 +499,41 @@ impl Diagnostics {
   pub fn is_empty(&self) -> bool {
     self.0.is_empty()
   }
+
+  /// Renders the diagnostics as a JSON document for other tools. Lines and
+  /// characters are zero-based, like the ones reported by tsc.
+  pub fn to_json(&self) -> Value {
+    json!({
+      "diagnostics": self.0.iter().map(|d| d.to_json()).collect::<Vec<_>>(),
+    })
+  }
+
+  /// Renders the diagnostics as a SARIF 2.1.0 log.
+  pub fn to_sarif(&self) -> Value {
+    let rule_ids = self.0.iter().map(|d| d.rule_id()).collect::<BTreeSet<_>>();
+    json!({
+      "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+      "version": "2.1.0",
+      "runs": [{
+        "tool": {
+          "driver": {
+            "name": "deno",
+            "version": version::deno(),
+            "informationUri": "https://deno.land",
+            "rules": rule_ids
+              .into_iter()
+              .map(|id| json!({ "id": id }))
+              .collect::<Vec<_>>(),
+          },
+        },
+        "results": self
+          .0
+          .iter()
+          .map(|d| d.to_sarif_result())
+          .collect::<Vec<_>>(),
+      }],
+    })
+  }
 }
 
 impl<'de> Deserialize<'de> for Diagnostics {
@@ -625,4 +779,158 @@ mod tests {
     let actual = diagnostics.to_string();
     assert_eq!(strip_ansi_codes(&actual), "TS2552 [ERROR]: Cannot find name \'foo_Bar\'. Did you mean \'foo_bar\'?\nfoo_Bar();\n~~~~~~~\n    at test.ts:8:1\n\n    \'foo_bar\' is declared here.\n    function foo_bar() {\n             ~~~~~~~\n        at test.ts:4:10");
   }
+
+  fn related_info_diagnostics() -> Diagnostics {
+    serde_json::from_value(json!([
+      {
+        "start": { "line": 7, "character": 0 },
+        "end": { "line": 7, "character": 7 },
+        "fileName": "file:///test.ts",
+        "messageText": "Cannot find name 'foo_Bar'. Did you mean 'foo_bar'?",
+        "sourceLine": "foo_Bar();",
+        "relatedInformation": [
+          {
+            "start": { "line": 3, "character": 9 },
+            "end": { "line": 3, "character": 16 },
+            "fileName": "file:///test.ts",
+            "messageText": "'foo_bar' is declared here.",
+            "sourceLine": "function foo_bar() {",
+            "category": 3,
+            "code": 2728
+          }
+        ],
+        "category": 1,
+        "code": 2552
+      },
+      {
+        "fileName": "file:///test.ts",
+        "messageChain": {
+          "messageText": "Type 'string' is not assignable to type 'number'.",
+          "category": 1,
+          "code": 2322,
+          "next": [
+            {
+              "messageText": "The types are incompatible.",
+              "category": 1,
+              "code": 2326
+            }
+          ]
+        },
+        "category": 1,
+        "code": 2322
+      }
+    ]))
+    .unwrap()
+  }
+
+  #[test]
+  fn test_diagnostics_to_json() {
+    let actual = related_info_diagnostics().to_json();
+    assert_eq!(
+      actual,
+      json!({
+        "diagnostics": [
+          {
+            "code": 2552,
+            "category": "error",
+            "file": "file:///test.ts",
+            "range": {
+              "start": { "line": 7, "character": 0 },
+              "end": { "line": 7, "character": 7 }
+            },
+            "message": "Cannot find name 'foo_Bar'. Did you mean 'foo_bar'?",
+            "messageChain": null,
+            "relatedInformation": [
+              {
+                "code": 2728,
+                "category": "message",
+                "file": "file:///test.ts",
+                "range": {
+                  "start": { "line": 3, "character": 9 },
+                  "end": { "line": 3, "character": 16 }
+                },
+                "message": "'foo_bar' is declared here.",
+                "messageChain": null,
+                "relatedInformation": []
+              }
+            ]
+          },
+          {
+            "code": 2322,
+            "category": "error",
+            "file": "file:///test.ts",
+            "range": null,
+            "message": "Type 'string' is not assignable to type 'number'.\n  The types are incompatible.",
+            "messageChain": {
+              "message": "Type 'string' is not assignable to type 'number'.",
+              "category": "error",
+              "code": 2322,
+              "next": [
+                {
+                  "message": "The types are incompatible.",
+                  "category": "error",
+                  "code": 2326,
+                  "next": []
+                }
+              ]
+            },
+            "relatedInformation": []
+          }
+        ]
+      })
+    );
+  }
+
+  #[test]
+  fn test_diagnostics_to_sarif() {
+    let actual = related_info_diagnostics().to_sarif();
+    let run = &actual["runs"][0];
+    assert_eq!(actual["version"], "2.1.0");
+    assert_eq!(
+      run["tool"]["driver"]["rules"],
+      json!([{ "id": "TS2322" }, { "id": "TS2552" }])
+    );
+    assert_eq!(
+      run["results"][0],
+      json!({
+        "ruleId": "TS2552",
+        "level": "error",
+        "message": {
+          "text": "Cannot find name 'foo_Bar'. Did you mean 'foo_bar'?"
+        },
+        "locations": [{
+          "physicalLocation": {
+            "artifactLocation": { "uri": "file:///test.ts" },
+            "region": {
+              "startLine": 8,
+              "startColumn": 1,
+              "endLine": 8,
+              "endColumn": 8
+            }
+          }
+        }],
+        "relatedLocations": [{
+          "id": 0,
+          "message": { "text": "'foo_bar' is declared here." },
+          "physicalLocation": {
+            "artifactLocation": { "uri": "file:///test.ts" },
+            "region": {
+              "startLine": 4,
+              "startColumn": 10,
+              "endLine": 4,
+              "endColumn": 17
+            }
+          }
+        }]
+      })
+    );
+    assert_eq!(
+      run["results"][1]["locations"],
+      json!([{
+        "physicalLocation": {
+          "artifactLocation": { "uri": "file:///test.ts" }
+        }
+      }])
+    );
+  }
 }
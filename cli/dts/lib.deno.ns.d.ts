@@ -134,6 +134,9 @@ declare namespace Deno {
     /** Ensure the test case does not prematurely cause the process to exit,
      * for example via a call to `Deno.exit`. Defaults to true. */
     sanitizeExit?: boolean;
+    /** Fail the test if it takes longer than the given number of
+     * milliseconds. Overrides the `--timeout` flag of `deno test`. */
+    timeout?: number;
 
     /** Specifies the permissions that should be used to run the test.
      * Set this to "inherit" to keep the calling thread's permissions.
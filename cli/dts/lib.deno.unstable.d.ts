@@ -810,6 +810,63 @@ declare namespace Deno {
       name: string,
       fn: (t: TestContext) => void | Promise<void>,
     ): Promise<boolean>;
+
+    /** Assert that `actual` matches the next snapshot of this test or step.
+     * Snapshots are stored in a `__snapshots__` directory next to the test
+     * module. Strings are stored as they are, other values as formatted by
+     * `Deno.inspect`.
+     *
+     * Run `deno test --update-snapshots` to write missing or outdated
+     * snapshots and remove the ones that are no longer used.
+     *
+     * ```ts
+     * Deno.test("render", (t) => {
+     *   t.assertSnapshot(render({ title: "Hello" }));
+     * });
+     * ```
+     */
+    assertSnapshot(actual: unknown): void;
+
+    /** The virtual clock of a test registered with the `fakeTime` option.
+     * Throws for any other test.
+     *
+     * ```ts
+     * Deno.test({ name: "debounce", fakeTime: true }, async (t) => {
+     *   let calls = 0;
+     *   const fn = debounce(() => calls++, 100);
+     *   fn();
+     *   await t.clock.tick(99);
+     *   assertEquals(calls, 0);
+     *   await t.clock.tick(1);
+     *   assertEquals(calls, 1);
+     * });
+     * ```
+     */
+    readonly clock: TestClock;
+  }
+
+  /** **UNSTABLE**: New option, yet to be vetted. */
+  export interface TestDefinition {
+    /** Run the test against a virtual clock, which only moves when the test
+     * advances it through `t.clock`. `Date` and `performance.now()` report the
+     * virtual time and timers only fire once the clock reaches them. Timers
+     * that are still pending when the test finishes are discarded.
+     *
+     * Set this to a date or a number of milliseconds since the epoch to start
+     * the clock at that time. Defaults to false. */
+    fakeTime?: boolean | number | Date;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted. */
+  export interface TestClock {
+    /** The current virtual time in milliseconds since the epoch. */
+    readonly now: number;
+    /** Advance the clock by the given number of milliseconds, running the
+     * timers that become due on the way, including the ones they schedule. */
+    tick(millis: number): Promise<void>;
+    /** Advance the clock until no timers are pending. Rejects if timers keep
+     * scheduling new ones. */
+    runAll(): Promise<void>;
   }
 
   /** **UNSTABLE**: New option, yet to be vetted. */
@@ -827,8 +884,93 @@ declare namespace Deno {
     /** Ensure the test case does not prematurely cause the process to exit,
      * for example via a call to `Deno.exit`. Defaults to true. */
     sanitizeExit?: boolean;
+    /** Fail the step if it takes longer than the given number of
+     * milliseconds. */
+    timeout?: number;
   }
 
+  /** **UNSTABLE**: New API, yet to be vetted. */
+  export interface BenchDefinition {
+    fn: () => void | Promise<void>;
+    name: string;
+    ignore?: boolean;
+    /** If at least one bench has `only` set to true, only run benches that have
+     * `only` set to true and fail the bench suite. */
+    only?: boolean;
+    /** Number of iterations run before measuring starts. Defaults to 50. */
+    warmup?: number;
+    /** Number of measured iterations. Defaults to 1000. */
+    n?: number;
+    /** Specifies the permissions that should be used to run the bench.
+     * Accepts the same values as the `permissions` option of `Deno.test`.
+     *
+     * Defaults to "inherit".
+     */
+    permissions?: TestDefinition["permissions"];
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Register a bench which will be run when `deno bench` is used on the command
+   * line and the containing module looks like a bench module.
+   * `fn` can be async if required.
+   *
+   * ```ts
+   * Deno.bench({
+   *   name: "example bench",
+   *   n: 10000,
+   *   fn() {
+   *     new URL("https://deno.land/std/testing/asserts.ts");
+   *   },
+   * });
+   * ```
+   */
+  export function bench(b: BenchDefinition): void;
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Register a bench which will be run when `deno bench` is used on the command
+   * line and the containing module looks like a bench module.
+   * `fn` can be async if required.
+   *
+   * ```ts
+   * Deno.bench("My bench description", () => {
+   *   new URL("https://deno.land/std/testing/asserts.ts");
+   * });
+   *
+   * Deno.bench(function myBenchName() {
+   *   JSON.parse(JSON.stringify({ hello: "world" }));
+   * });
+   * ```
+   */
+  export function bench(
+    name: string,
+    fn: () => void | Promise<void>,
+  ): void;
+  export function bench(fn: () => void | Promise<void>): void;
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Register a bench with the given options which will be run when `deno bench`
+   * is used on the command line and the containing module looks like a bench
+   * module.
+   *
+   * ```ts
+   * Deno.bench("read config", { permissions: { read: true } }, () => {
+   *   Deno.readTextFileSync("./deno.json");
+   * });
+   * ```
+   */
+  export function bench(
+    name: string,
+    options: Omit<BenchDefinition, "fn" | "name">,
+    fn: () => void | Promise<void>,
+  ): void;
+  export function bench(
+    options: Omit<BenchDefinition, "fn">,
+    fn: () => void | Promise<void>,
+  ): void;
+
   /** **UNSTABLE**: new API, yet to be vetted.
    *
    * A generic transport listener for message-oriented protocols. */
@@ -3,6 +3,7 @@
 use clap::App;
 use clap::AppSettings;
 use clap::Arg;
+use clap::ArgGroup;
 use clap::ArgMatches;
 use clap::ArgSettings;
 use clap::SubCommand;
@@ -14,6 +15,7 @@ use log::debug;
 use log::Level;
 use std::net::SocketAddr;
 use std::num::NonZeroU32;
+use std::num::NonZeroU64;
 use std::num::NonZeroU8;
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
@@ -34,6 +36,14 @@ lazy_static::lazy_static! {
   );
 }
 
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct BenchFlags {
+  pub ignore: Vec<PathBuf>,
+  pub include: Option<Vec<String>>,
+  pub filter: Option<String>,
+  pub json: bool,
+}
+
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct BundleFlags {
   pub source_file: String,
@@ -65,6 +75,11 @@ pub struct CoverageFlags {
   pub include: Vec<String>,
   pub exclude: Vec<String>,
   pub lcov: bool,
+  pub cobertura: bool,
+  pub html: Option<PathBuf>,
+  pub min_lines: Option<f64>,
+  pub min_branches: Option<f64>,
+  pub min_functions: Option<f64>,
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
@@ -73,6 +88,19 @@ pub struct DocFlags {
   pub json: bool,
   pub source_file: Option<String>,
   pub filter: Option<String>,
+  pub html: Option<DocHtmlFlags>,
+  pub lint: Option<DocLintFlags>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct DocHtmlFlags {
+  pub out_dir: PathBuf,
+  pub source_files: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct DocLintFlags {
+  pub source_files: Vec<String>,
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
@@ -93,6 +121,12 @@ pub struct FmtFlags {
   pub indent_width: Option<NonZeroU8>,
   pub single_quote: Option<bool>,
   pub prose_wrap: Option<String>,
+  pub semi_colons: Option<bool>,
+  pub trailing_commas: Option<String>,
+  pub brace_position: Option<String>,
+  pub operator_position: Option<String>,
+  pub quote_props: Option<String>,
+  pub jsx_quote_style: Option<String>,
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
@@ -125,6 +159,8 @@ pub struct LintFlags {
   pub maybe_rules_include: Option<Vec<String>>,
   pub maybe_rules_exclude: Option<Vec<String>>,
   pub json: bool,
+  pub fix: bool,
+  pub reporter: Option<String>,
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
@@ -137,6 +173,48 @@ pub struct RunFlags {
   pub script: String,
 }
 
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum TestReporterKind {
+  Pretty,
+  Junit,
+  Tap,
+  Json,
+}
+
+impl Default for TestReporterKind {
+  fn default() -> Self {
+    Self::Pretty
+  }
+}
+
+/// One of the shards the test modules are partitioned into, selected with
+/// `--shard=<index>/<total>`. The index is one-based.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub struct TestShard {
+  pub index: usize,
+  pub total: usize,
+}
+
+impl FromStr for TestShard {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let invalid =
+      || "shard should be in the form <index>/<total>, e.g. 1/3".to_string();
+    let (index, total) = s.split_once('/').ok_or_else(invalid)?;
+    let index = index.parse::<usize>().map_err(|_| invalid())?;
+    let total = total.parse::<usize>().map_err(|_| invalid())?;
+    if total == 0 {
+      return Err("shard total should be at least 1".to_string());
+    }
+    if index == 0 || index > total {
+      return Err(format!("shard index should be between 1 and {}", total));
+    }
+
+    Ok(TestShard { index, total })
+  }
+}
+
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct TestFlags {
   pub ignore: Vec<PathBuf>,
@@ -147,7 +225,14 @@ pub struct TestFlags {
   pub include: Option<Vec<String>>,
   pub filter: Option<String>,
   pub shuffle: Option<u64>,
+  pub shard: Option<TestShard>,
+  pub shard_by_duration: bool,
+  pub rerun_failed: bool,
+  pub timeout: Option<NonZeroU64>,
+  pub update_snapshots: bool,
   pub concurrent_jobs: NonZeroUsize,
+  pub reporter: TestReporterKind,
+  pub reporter_output: Option<PathBuf>,
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
@@ -162,6 +247,7 @@ pub struct UpgradeFlags {
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub enum DenoSubcommand {
+  Bench(BenchFlags),
   Bundle(BundleFlags),
   Cache(CacheFlags),
   Compile(CompileFlags),
@@ -206,6 +292,23 @@ impl Default for CheckFlag {
   }
 }
 
+/// How type checking diagnostics are reported.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DiagnosticsFormat {
+  /// Human readable text on stderr. The default value.
+  Pretty,
+  /// A JSON document on stdout.
+  Json,
+  /// A SARIF log on stdout, for code scanning dashboards.
+  Sarif,
+}
+
+impl Default for DiagnosticsFormat {
+  fn default() -> Self {
+    Self::Pretty
+  }
+}
+
 #[derive(Clone, Debug, PartialEq, Default)]
 pub struct Flags {
   /// Vector of CLI arguments - these are user script arguments, all Deno
@@ -230,6 +333,7 @@ pub struct Flags {
   pub check: CheckFlag,
   pub config_path: Option<String>,
   pub coverage_dir: Option<String>,
+  pub diagnostics_format: DiagnosticsFormat,
   pub enable_testing_features: bool,
   pub ignore: Vec<PathBuf>,
   pub import_map_path: Option<String>,
@@ -462,6 +566,8 @@ pub fn flags_from_vec(args: Vec<String>) -> clap::Result<Flags> {
     completions_parse(&mut flags, m);
   } else if let Some(m) = matches.subcommand_matches("test") {
     test_parse(&mut flags, m);
+  } else if let Some(m) = matches.subcommand_matches("bench") {
+    bench_parse(&mut flags, m);
   } else if let Some(m) = matches.subcommand_matches("upgrade") {
     upgrade_parse(&mut flags, m);
   } else if let Some(m) = matches.subcommand_matches("doc") {
@@ -519,6 +625,7 @@ If the flag is set, restrict these messages to errors.",
         )
         .global(true),
     )
+    .subcommand(bench_subcommand())
     .subcommand(bundle_subcommand())
     .subcommand(cache_subcommand())
     .subcommand(compile_subcommand())
@@ -541,6 +648,57 @@ If the flag is set, restrict these messages to errors.",
     .after_help(ENV_VARIABLES_HELP)
 }
 
+fn bench_subcommand<'a, 'b>() -> App<'a, 'b> {
+  runtime_args(SubCommand::with_name("bench"), true, false)
+    .setting(AppSettings::TrailingVarArg)
+    .arg(
+      Arg::with_name("ignore")
+        .long("ignore")
+        .takes_value(true)
+        .use_delimiter(true)
+        .require_equals(true)
+        .help("Ignore files"),
+    )
+    .arg(
+      Arg::with_name("filter")
+        .set(ArgSettings::AllowLeadingHyphen)
+        .long("filter")
+        .takes_value(true)
+        .help("Run benchmarks with this string or pattern in the bench name"),
+    )
+    .arg(
+      Arg::with_name("json")
+        .long("json")
+        .help("Output benchmark results in JSON format")
+        .takes_value(false),
+    )
+    .arg(
+      Arg::with_name("files")
+        .help("List of file names to run")
+        .takes_value(true)
+        .multiple(true),
+    )
+    .arg(script_arg().last(true))
+    .about("UNSTABLE: Run benchmarks")
+    .long_about(
+      "UNSTABLE: Run benchmarks using Deno's built-in bench tool.
+
+Evaluate the given modules, run all benches declared with 'Deno.bench()' and
+report results to standard output:
+
+  deno bench --unstable src/fetch_bench.ts src/signal_bench.ts
+
+Each bench is run a number of warmup iterations first, which are not measured,
+followed by the measured iterations. Both can be set per bench with the
+'warmup' and 'n' options of its definition.
+
+Directory arguments are expanded to all contained files matching the glob
+{*_,*.,}bench.{js,mjs,ts,jsx,tsx}:
+
+  deno bench --unstable src/",
+    )
+}
+
 fn bundle_subcommand<'a, 'b>() -> App<'a, 'b> {
   compile_args(SubCommand::with_name("bundle"))
     .arg(
@@ -678,9 +836,33 @@ Write a report using the lcov format:
 
   deno coverage --lcov cov_profile > cov.lcov
 
-Generate html reports from lcov:
+Write a report using the Cobertura XML format:
+
+  deno coverage --cobertura cov_profile > cobertura.xml
+
+Write an html report to the html_cov directory:
+
+  deno coverage --html cov_profile
+
+Write an html report to a custom directory:
+
+  deno coverage --html=coverage/html cov_profile
+
+Fail when less than 80% of the lines or 70% of the branches are covered:
+
+  deno coverage --min-lines=80 --min-branches=70 cov_profile
+
+Thresholds, including thresholds for files matching a glob, can also be set
+in the configuration file:
 
-  genhtml -o html_cov cov.lcov
+  {
+    \"coverage\": {
+      \"thresholds\": {
+        \"lines\": 80,
+        \"files\": { \"src/core/**\": { \"branches\": 90 } }
+      }
+    }
+  }
 ",
     )
     .arg(
@@ -715,8 +897,39 @@ Generate html reports from lcov:
       Arg::with_name("lcov")
         .long("lcov")
         .help("Output coverage report in lcov format")
-        .takes_value(false),
+        .takes_value(false)
+        .conflicts_with_all(&["cobertura", "html"]),
+    )
+    .arg(
+      Arg::with_name("cobertura")
+        .long("cobertura")
+        .help("Output coverage report in Cobertura XML format")
+        .takes_value(false)
+        .conflicts_with("html"),
     )
+    .arg(
+      Arg::with_name("html")
+        .long("html")
+        .value_name("DIR")
+        .takes_value(true)
+        .min_values(0)
+        .max_values(1)
+        .require_equals(true)
+        .help("Write an html coverage report to a directory (defaults to html_cov)"),
+    )
+    .arg(coverage_threshold_arg(
+      "min-lines",
+      "Fail if the percentage of covered lines is below PERCENT",
+    ))
+    .arg(coverage_threshold_arg(
+      "min-branches",
+      "Fail if the percentage of covered branches is below PERCENT",
+    ))
+    .arg(coverage_threshold_arg(
+      "min-functions",
+      "Fail if the percentage of covered functions is below PERCENT",
+    ))
+    .arg(config_arg())
     .arg(
       Arg::with_name("files")
         .takes_value(true)
@@ -725,6 +938,19 @@ Generate html reports from lcov:
     )
 }
 
+fn coverage_threshold_arg<'a, 'b>(name: &'a str, help: &'a str) -> Arg<'a, 'b> {
+  Arg::with_name(name)
+    .long(name)
+    .takes_value(true)
+    .require_equals(true)
+    .value_name("PERCENT")
+    .help(help)
+    .validator(|val: String| match val.parse::<f64>() {
+      Ok(percent) if (0.0..=100.0).contains(&percent) => Ok(()),
+      _ => Err("coverage threshold should be between 0 and 100".to_string()),
+    })
+}
+
 fn doc_subcommand<'a, 'b>() -> App<'a, 'b> {
   SubCommand::with_name("doc")
     .about("Show documentation for a module")
@@ -750,7 +976,15 @@ Target a specific symbol:
 Show documentation for runtime built-ins:
 
     deno doc
-    deno doc --builtin Deno.Listener",
+    deno doc --builtin Deno.Listener
+
+Generate a static HTML documentation site for one or more modules:
+
+    deno doc --html=./docs ./mod.ts ./testing.ts
+
+Check that the exported API of one or more modules is documented:
+
+    deno doc --lint ./mod.ts ./testing.ts",
     )
     .arg(import_map_arg())
     .arg(reload_arg())
@@ -760,6 +994,33 @@ Show documentation for runtime built-ins:
         .help("Output documentation in JSON format")
         .takes_value(false),
     )
+    .arg(
+      Arg::with_name("html")
+        .long("html")
+        .help(
+          "Generate a static HTML documentation site in the given directory",
+        )
+        .takes_value(true)
+        .value_name("OUT_DIR")
+        .require_equals(true)
+        .conflicts_with("json")
+        .requires("source_file"),
+    )
+    .arg(
+      Arg::with_name("lint")
+        .long("lint")
+        .help(
+          "Report exported symbols with missing or incomplete documentation",
+        )
+        .takes_value(false)
+        .conflicts_with_all(&["json", "html"])
+        .requires("source_file"),
+    )
+    .group(
+      ArgGroup::with_name("entrypoints")
+        .args(&["html", "lint"])
+        .multiple(true),
+    )
     .arg(
       Arg::with_name("private")
         .long("private")
@@ -780,6 +1041,15 @@ Show documentation for runtime built-ins:
         .conflicts_with("json")
         .conflicts_with("pretty"),
     )
+    // When generating HTML or linting all the positional arguments are
+    // entrypoints.
+    .arg(
+      Arg::with_name("source_files")
+        .takes_value(true)
+        .multiple(true)
+        .requires("entrypoints")
+        .hidden(true),
+    )
 }
 
 fn eval_subcommand<'a, 'b>() -> App<'a, 'b> {
@@ -924,6 +1194,54 @@ Ignore formatting a file by adding an ignore comment at the top of the file:
         .possible_values(&["always", "never", "preserve"])
         .help("Define how prose should be wrapped. Defaults to always."),
     )
+    .arg(
+      Arg::with_name("options-no-semicolons")
+        .long("options-no-semicolons")
+        .help("Don't use semicolons except where necessary."),
+    )
+    .arg(
+      Arg::with_name("options-trailing-commas")
+        .long("options-trailing-commas")
+        .takes_value(true)
+        .possible_values(&["always", "never", "only-multi-line"])
+        .help("Define when trailing commas are used. Defaults to only-multi-line."),
+    )
+    .arg(
+      Arg::with_name("options-brace-position")
+        .long("options-brace-position")
+        .takes_value(true)
+        .possible_values(&[
+          "maintain",
+          "same-line",
+          "next-line",
+          "same-line-unless-hanging",
+        ])
+        .help("Define where opening braces are placed. Defaults to same-line."),
+    )
+    .arg(
+      Arg::with_name("options-operator-position")
+        .long("options-operator-position")
+        .takes_value(true)
+        .possible_values(&["maintain", "same-line", "next-line"])
+        .help(
+          "Define where operators are placed when an expression spans multiple \
+lines. Defaults to next-line.",
+        ),
+    )
+    .arg(
+      Arg::with_name("options-quote-props")
+        .long("options-quote-props")
+        .takes_value(true)
+        .possible_values(&["as-needed", "preserve"])
+        .help("Define when object property names are quoted. Defaults to preserve."),
+    )
+    .arg(
+      Arg::with_name("options-jsx-quote-style")
+        .long("options-jsx-quote-style")
+        .takes_value(true)
+        .possible_values(&["prefer-double", "prefer-single"])
+        .help("Define the quotes used for JSX attributes. Defaults to prefer-double."),
+    )
 }
 
 fn info_subcommand<'a, 'b>() -> App<'a, 'b> {
@@ -1082,11 +1400,24 @@ Print result as JSON:
 
   deno lint --json
 
+Print result in another format, like one line per problem, GitHub Actions
+annotations or SARIF:
+
+  deno lint --reporter=compact
+  deno lint --reporter=github
+  deno lint --reporter=sarif
+
 Read from stdin:
 
   cat file.ts | deno lint -
   cat file.ts | deno lint --json -
 
+Automatically fix problems which have a fix available, writing the result back
+to the files (or to stdout when reading from stdin):
+
+  deno lint --fix
+  cat file.ts | deno lint --fix -
+
 List available rules:
 
   deno lint --rules
@@ -1152,6 +1483,21 @@ Ignore linting a file by adding an ignore comment at the top of the file:
         .help("Output lint result in JSON format")
         .takes_value(false),
     )
+    .arg(
+      Arg::with_name("fix")
+        .long("fix")
+        .help("Fix any problems that have an automatic fix available")
+        .takes_value(false),
+    )
+    .arg(
+      Arg::with_name("reporter")
+        .long("reporter")
+        .takes_value(true)
+        .require_equals(true)
+        .possible_values(&["pretty", "json", "compact", "github", "sarif"])
+        .conflicts_with("json")
+        .help("Select the format of the lint result. Defaults to pretty."),
+    )
     .arg(
       Arg::with_name("files")
         .takes_value(true)
@@ -1254,6 +1600,19 @@ fn test_subcommand<'a, 'b>() -> App<'a, 'b> {
         .help("Don't return error code if no test files are found")
         .takes_value(false),
     )
+    .arg(
+      Arg::with_name("rerun-failed")
+        .long("rerun-failed")
+        .alias("failed-only")
+        .help("Only run the tests that failed in the previous run")
+        .long_help(
+          "Only run the tests that failed the last time they ran. Failures are
+recorded per project in the DENO_DIR. When no failures are recorded for
+the selected modules, no tests are run. In watch mode, modules with
+failing tests are always run first.",
+        )
+        .takes_value(false),
+    )
     .arg(
       Arg::with_name("filter")
         .set(ArgSettings::AllowLeadingHyphen)
@@ -1275,6 +1634,54 @@ fn test_subcommand<'a, 'b>() -> App<'a, 'b> {
           Err(_) => Err("Shuffle seed should be a number".to_string()),
         }),
     )
+    .arg(
+      Arg::with_name("shard")
+        .long("shard")
+        .value_name("INDEX/TOTAL")
+        .help("Only run the given shard of the test modules, e.g. --shard=1/3")
+        .long_help(
+          "Only run the given shard of the test modules, e.g. --shard=1/3.
+Modules are sorted by specifier and dealt out to the shards in turn, so every
+shard of a suite gets a distinct part of it.",
+        )
+        .require_equals(true)
+        .takes_value(true)
+        .conflicts_with("watch")
+        .validator(|val: String| val.parse::<TestShard>().map(|_| ())),
+    )
+    .arg(
+      Arg::with_name("shard-by-duration")
+        .long("shard-by-duration")
+        .requires("shard")
+        .help("Balance the shards by the durations of previous test runs")
+        .long_help(
+          "Balance the shards by the durations of previous test runs, which are
+recorded in the DENO_DIR. All shards of a suite must share the same recorded
+durations for their partitions to add up to the whole suite.",
+        ),
+    )
+    .arg(
+      Arg::with_name("timeout")
+        .long("timeout")
+        .value_name("MS")
+        .help("Fail tests that take longer than the given number of milliseconds")
+        .long_help(
+          "Fail tests that take longer than the given number of milliseconds.
+A test can override this with the `timeout` option of its definition.",
+        )
+        .require_equals(true)
+        .takes_value(true)
+        .validator(|val: String| match val.parse::<NonZeroU64>() {
+          Ok(_) => Ok(()),
+          Err(_) => Err("timeout should be a non zero integer".to_string()),
+        }),
+    )
+    .arg(
+      Arg::with_name("update-snapshots")
+        .long("update-snapshots")
+        .help("Write snapshots that are missing or outdated and remove obsolete ones")
+        .takes_value(false),
+    )
     .arg(
       Arg::with_name("coverage")
         .long("coverage")
@@ -1298,6 +1705,23 @@ fn test_subcommand<'a, 'b>() -> App<'a, 'b> {
           Err(_) => Err("jobs should be a non zero unsigned integer".to_string()),
         }),
     )
+    .arg(
+      Arg::with_name("reporter")
+        .long("reporter")
+        .takes_value(true)
+        .require_equals(true)
+        .possible_values(&["pretty", "junit", "tap", "json"])
+        .help("Select the format of the test report. Defaults to 'pretty'"),
+    )
+    .arg(
+      Arg::with_name("reporter-output")
+        .long("reporter-output")
+        .takes_value(true)
+        .require_equals(true)
+        .value_name("PATH")
+        .requires("reporter")
+        .help("Write the report to PATH instead of stdout and keep the 'pretty' output on the console"),
+    )
     .arg(
       Arg::with_name("files")
         .help("List of file names to run")
@@ -1319,6 +1743,10 @@ report results to standard output:
 
   deno test src/fetch_test.ts src/signal_test.ts
 
+Write a JUnit XML report for CI while still printing results to the console:
+
+  deno test --reporter=junit --reporter-output=report.xml
+
 Directory arguments are expanded to all contained files matching the glob
 {*_,*.,}test.{js,mjs,ts,jsx,tsx}:
 
@@ -1391,6 +1819,7 @@ fn compile_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
     .arg(no_remote_arg())
     .arg(config_arg())
     .arg(no_check_arg())
+    .arg(diagnostics_format_arg())
     .arg(reload_arg())
     .arg(lock_arg())
     .arg(lock_write_arg())
@@ -1665,6 +2094,21 @@ modules will be ignored.",
     )
 }
 
+fn diagnostics_format_arg<'a, 'b>() -> Arg<'a, 'b> {
+  Arg::with_name("diagnostics-format")
+    .long("diagnostics-format")
+    .takes_value(true)
+    .require_equals(true)
+    .value_name("FORMAT")
+    .possible_values(&["pretty", "json", "sarif"])
+    .help("Format of type checking diagnostics")
+    .long_help(
+      "Format of type checking diagnostics. Defaults to 'pretty'.
+With 'json' or 'sarif', diagnostics are printed to stdout as a JSON document or
+a SARIF log respectively, for consumption by other tools.",
+    )
+}
+
 fn script_arg<'a, 'b>() -> Arg<'a, 'b> {
   Arg::with_name("script_arg")
     .multiple(true)
@@ -1731,6 +2175,48 @@ fn unsafely_ignore_ceritifcate_errors_arg<'a, 'b>() -> Arg<'a, 'b> {
     .validator(crate::flags_allow_net::validator)
 }
 
+fn bench_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
+  runtime_args_parse(flags, matches, true, false);
+
+  let ignore = match matches.values_of("ignore") {
+    Some(f) => f.map(PathBuf::from).collect(),
+    None => vec![],
+  };
+
+  let filter = matches.value_of("filter").map(String::from);
+  let json = matches.is_present("json");
+
+  if matches.is_present("script_arg") {
+    let script_arg: Vec<String> = matches
+      .values_of("script_arg")
+      .unwrap()
+      .map(String::from)
+      .collect();
+
+    for v in script_arg {
+      flags.argv.push(v);
+    }
+  }
+
+  let include = if matches.is_present("files") {
+    let files: Vec<String> = matches
+      .values_of("files")
+      .unwrap()
+      .map(String::from)
+      .collect();
+    Some(files)
+  } else {
+    None
+  };
+
+  flags.subcommand = DenoSubcommand::Bench(BenchFlags {
+    ignore,
+    include,
+    filter,
+    json,
+  });
+}
+
 fn bundle_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   compile_args_parse(flags, matches);
 
@@ -1815,12 +2301,32 @@ fn coverage_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     None => vec![],
   };
   let lcov = matches.is_present("lcov");
+  config_arg_parse(flags, matches);
+  let cobertura = matches.is_present("cobertura");
+  let html = if matches.is_present("html") {
+    Some(PathBuf::from(
+      matches.value_of("html").unwrap_or("html_cov"),
+    ))
+  } else {
+    None
+  };
+  let min_lines = matches.value_of("min-lines").map(|v| v.parse().unwrap());
+  let min_branches =
+    matches.value_of("min-branches").map(|v| v.parse().unwrap());
+  let min_functions = matches
+    .value_of("min-functions")
+    .map(|v| v.parse().unwrap());
   flags.subcommand = DenoSubcommand::Coverage(CoverageFlags {
     files,
     ignore,
     include,
     exclude,
     lcov,
+    cobertura,
+    html,
+    min_lines,
+    min_branches,
+    min_functions,
   });
 }
 
@@ -1828,15 +2334,39 @@ fn doc_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   import_map_arg_parse(flags, matches);
   reload_arg_parse(flags, matches);
 
-  let source_file = matches.value_of("source_file").map(String::from);
+  let mut source_file = matches.value_of("source_file").map(String::from);
   let private = matches.is_present("private");
   let json = matches.is_present("json");
-  let filter = matches.value_of("filter").map(String::from);
+  let mut filter = matches.value_of("filter").map(String::from);
+  let mut take_source_files = || {
+    let mut source_files: Vec<String> = source_file
+      .take()
+      .into_iter()
+      .chain(filter.take())
+      .collect();
+    if let Some(files) = matches.values_of("source_files") {
+      source_files.extend(files.map(String::from));
+    }
+    source_files
+  };
+  let html = matches.value_of("html").map(|out_dir| DocHtmlFlags {
+    out_dir: PathBuf::from(out_dir),
+    source_files: take_source_files(),
+  });
+  let lint = if matches.is_present("lint") {
+    Some(DocLintFlags {
+      source_files: take_source_files(),
+    })
+  } else {
+    None
+  };
   flags.subcommand = DenoSubcommand::Doc(DocFlags {
     source_file,
     json,
     filter,
     private,
+    html,
+    lint,
   });
 }
 
@@ -1922,6 +2452,23 @@ fn fmt_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   } else {
     None
   };
+  let semi_colons = if matches.is_present("options-no-semicolons") {
+    Some(false)
+  } else {
+    None
+  };
+  let trailing_commas = matches
+    .value_of("options-trailing-commas")
+    .map(String::from);
+  let brace_position =
+    matches.value_of("options-brace-position").map(String::from);
+  let operator_position = matches
+    .value_of("options-operator-position")
+    .map(String::from);
+  let quote_props = matches.value_of("options-quote-props").map(String::from);
+  let jsx_quote_style = matches
+    .value_of("options-jsx-quote-style")
+    .map(String::from);
 
   flags.subcommand = DenoSubcommand::Fmt(FmtFlags {
     check: matches.is_present("check"),
@@ -1933,6 +2480,12 @@ fn fmt_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     indent_width,
     single_quote,
     prose_wrap,
+    semi_colons,
+    trailing_commas,
+    brace_position,
+    operator_position,
+    quote_props,
+    jsx_quote_style,
   });
 }
 
@@ -2019,6 +2572,8 @@ fn lint_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     .map(|f| f.map(String::from).collect());
 
   let json = matches.is_present("json");
+  let fix = matches.is_present("fix");
+  let reporter = matches.value_of("reporter").map(String::from);
   flags.subcommand = DenoSubcommand::Lint(LintFlags {
     files,
     rules,
@@ -2027,6 +2582,8 @@ fn lint_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     maybe_rules_exclude,
     ignore,
     json,
+    fix,
+    reporter,
   });
 }
 
@@ -2100,6 +2657,12 @@ fn test_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     None
   };
 
+  let shard = matches.value_of("shard").map(|v| v.parse().unwrap());
+  let shard_by_duration = matches.is_present("shard-by-duration");
+  let rerun_failed = matches.is_present("rerun-failed");
+  let timeout = matches.value_of("timeout").map(|v| v.parse().unwrap());
+  let update_snapshots = matches.is_present("update-snapshots");
+
   if matches.is_present("script_arg") {
     let script_arg: Vec<String> = matches
       .values_of("script_arg")
@@ -2134,6 +2697,14 @@ fn test_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     None
   };
 
+  let reporter = match matches.value_of("reporter") {
+    Some("junit") => TestReporterKind::Junit,
+    Some("tap") => TestReporterKind::Tap,
+    Some("json") => TestReporterKind::Json,
+    _ => TestReporterKind::Pretty,
+  };
+  let reporter_output = matches.value_of("reporter-output").map(PathBuf::from);
+
   flags.coverage_dir = matches.value_of("coverage").map(String::from);
   flags.watch = matches.is_present("watch");
   flags.subcommand = DenoSubcommand::Test(TestFlags {
@@ -2144,8 +2715,15 @@ fn test_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     ignore,
     filter,
     shuffle,
+    shard,
+    shard_by_duration,
+    rerun_failed,
+    timeout,
+    update_snapshots,
     allow_none,
     concurrent_jobs,
+    reporter,
+    reporter_output,
   });
 }
 
@@ -2182,6 +2760,7 @@ fn compile_args_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   no_remote_arg_parse(flags, matches);
   config_arg_parse(flags, matches);
   no_check_arg_parse(flags, matches);
+  diagnostics_format_arg_parse(flags, matches);
   reload_arg_parse(flags, matches);
   lock_args_parse(flags, matches);
   ca_file_arg_parse(flags, matches);
@@ -2382,8 +2961,16 @@ fn no_check_arg_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   }
 }
 
-fn lock_args_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
-  if matches.is_present("lock") {
+fn diagnostics_format_arg_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
+  flags.diagnostics_format = match matches.value_of("diagnostics-format") {
+    Some("json") => DiagnosticsFormat::Json,
+    Some("sarif") => DiagnosticsFormat::Sarif,
+    _ => DiagnosticsFormat::Pretty,
+  };
+}
+
+fn lock_args_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
+  if matches.is_present("lock") {
     let lockfile = matches.value_of("lock").unwrap();
     flags.lock = Some(PathBuf::from(lockfile));
   }
@@ -2688,6 +3275,12 @@ mod tests {
           indent_width: None,
           single_quote: None,
           prose_wrap: None,
+          semi_colons: None,
+          trailing_commas: None,
+          brace_position: None,
+          operator_position: None,
+          quote_props: None,
+          jsx_quote_style: None,
         }),
         ..Flags::default()
       }
@@ -2707,6 +3300,12 @@ mod tests {
           indent_width: None,
           single_quote: None,
           prose_wrap: None,
+          semi_colons: None,
+          trailing_commas: None,
+          brace_position: None,
+          operator_position: None,
+          quote_props: None,
+          jsx_quote_style: None,
         }),
         ..Flags::default()
       }
@@ -2726,6 +3325,12 @@ mod tests {
           indent_width: None,
           single_quote: None,
           prose_wrap: None,
+          semi_colons: None,
+          trailing_commas: None,
+          brace_position: None,
+          operator_position: None,
+          quote_props: None,
+          jsx_quote_style: None,
         }),
         ..Flags::default()
       }
@@ -2745,6 +3350,12 @@ mod tests {
           indent_width: None,
           single_quote: None,
           prose_wrap: None,
+          semi_colons: None,
+          trailing_commas: None,
+          brace_position: None,
+          operator_position: None,
+          quote_props: None,
+          jsx_quote_style: None,
         }),
         watch: true,
         ..Flags::default()
@@ -2772,6 +3383,12 @@ mod tests {
           indent_width: None,
           single_quote: None,
           prose_wrap: None,
+          semi_colons: None,
+          trailing_commas: None,
+          brace_position: None,
+          operator_position: None,
+          quote_props: None,
+          jsx_quote_style: None,
         }),
         watch: true,
         ..Flags::default()
@@ -2792,6 +3409,12 @@ mod tests {
           indent_width: None,
           single_quote: None,
           prose_wrap: None,
+          semi_colons: None,
+          trailing_commas: None,
+          brace_position: None,
+          operator_position: None,
+          quote_props: None,
+          jsx_quote_style: None,
         }),
         config_path: Some("deno.jsonc".to_string()),
         ..Flags::default()
@@ -2819,6 +3442,12 @@ mod tests {
           indent_width: None,
           single_quote: None,
           prose_wrap: None,
+          semi_colons: None,
+          trailing_commas: None,
+          brace_position: None,
+          operator_position: None,
+          quote_props: None,
+          jsx_quote_style: None,
         }),
         config_path: Some("deno.jsonc".to_string()),
         watch: true,
@@ -2851,10 +3480,63 @@ mod tests {
           indent_width: Some(NonZeroU8::new(4).unwrap()),
           single_quote: Some(true),
           prose_wrap: Some("never".to_string()),
+          semi_colons: None,
+          trailing_commas: None,
+          brace_position: None,
+          operator_position: None,
+          quote_props: None,
+          jsx_quote_style: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "fmt",
+      "--options-no-semicolons",
+      "--options-trailing-commas",
+      "never",
+      "--options-brace-position",
+      "next-line",
+      "--options-operator-position",
+      "same-line",
+      "--options-quote-props",
+      "as-needed",
+      "--options-jsx-quote-style",
+      "prefer-single"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Fmt(FmtFlags {
+          ignore: vec![],
+          check: false,
+          files: vec![],
+          ext: "ts".to_string(),
+          use_tabs: None,
+          line_width: None,
+          indent_width: None,
+          single_quote: None,
+          prose_wrap: None,
+          semi_colons: Some(false),
+          trailing_commas: Some("never".to_string()),
+          brace_position: Some("next-line".to_string()),
+          operator_position: Some("same-line".to_string()),
+          quote_props: Some("as-needed".to_string()),
+          jsx_quote_style: Some("prefer-single".to_string()),
         }),
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "fmt",
+      "--options-trailing-commas",
+      "sometimes"
+    ]);
+    assert!(r.is_err());
   }
 
   #[test]
@@ -2873,6 +3555,8 @@ mod tests {
           maybe_rules_include: None,
           maybe_rules_exclude: None,
           json: false,
+          fix: false,
+          reporter: None,
           ignore: vec![],
         }),
         ..Flags::default()
@@ -2891,6 +3575,8 @@ mod tests {
           maybe_rules_include: None,
           maybe_rules_exclude: None,
           json: false,
+          fix: false,
+          reporter: None,
           ignore: vec![
             PathBuf::from("script_1.ts"),
             PathBuf::from("script_2.ts")
@@ -2911,6 +3597,8 @@ mod tests {
           maybe_rules_include: None,
           maybe_rules_exclude: None,
           json: false,
+          fix: false,
+          reporter: None,
           ignore: vec![],
         }),
         ..Flags::default()
@@ -2934,6 +3622,8 @@ mod tests {
           maybe_rules_include: Some(svec!["ban-untagged-todo", "no-undef"]),
           maybe_rules_exclude: Some(svec!["no-const-assign"]),
           json: false,
+          fix: false,
+          reporter: None,
           ignore: vec![],
         }),
         ..Flags::default()
@@ -2951,6 +3641,8 @@ mod tests {
           maybe_rules_include: None,
           maybe_rules_exclude: None,
           json: true,
+          fix: false,
+          reporter: None,
           ignore: vec![],
         }),
         ..Flags::default()
@@ -2975,12 +3667,60 @@ mod tests {
           maybe_rules_include: None,
           maybe_rules_exclude: None,
           json: true,
+          fix: false,
+          reporter: None,
           ignore: vec![],
         }),
         config_path: Some("Deno.jsonc".to_string()),
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec!["deno", "lint", "--fix", "script_1.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: vec![PathBuf::from("script_1.ts")],
+          rules: false,
+          maybe_rules_tags: None,
+          maybe_rules_include: None,
+          maybe_rules_exclude: None,
+          json: false,
+          fix: true,
+          reporter: None,
+          ignore: vec![],
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "lint",
+      "--reporter=compact",
+      "script_1.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: vec![PathBuf::from("script_1.ts")],
+          rules: false,
+          maybe_rules_tags: None,
+          maybe_rules_include: None,
+          maybe_rules_exclude: None,
+          json: false,
+          fix: false,
+          reporter: Some("compact".to_string()),
+          ignore: vec![],
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "lint", "--reporter=xml"]);
+    assert!(r.is_err());
   }
 
   #[test]
@@ -3626,6 +4366,8 @@ mod tests {
           private: false,
           json: false,
           filter: None,
+          html: None,
+          lint: None,
         }),
         import_map_path: Some("import_map.json".to_owned()),
         ..Flags::default()
@@ -3899,6 +4641,42 @@ mod tests {
     );
   }
 
+  #[test]
+  fn diagnostics_format() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--diagnostics-format=sarif",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        diagnostics_format: DiagnosticsFormat::Sarif,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--diagnostics-format=json",
+      "--no-run"
+    ]);
+    assert_eq!(r.unwrap().diagnostics_format, DiagnosticsFormat::Json);
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "cache",
+      "--diagnostics-format=xml",
+      "script.ts"
+    ]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn repl_with_unsafely_ignore_certificate_errors() {
     let r = flags_from_vec(svec![
@@ -4133,7 +4911,14 @@ mod tests {
           include: Some(svec!["dir1/", "dir2/"]),
           ignore: vec![],
           shuffle: None,
+          shard: None,
+          shard_by_duration: false,
+          rerun_failed: false,
+          timeout: None,
+          update_snapshots: false,
           concurrent_jobs: NonZeroUsize::new(1).unwrap(),
+          reporter: TestReporterKind::Pretty,
+          reporter_output: None,
         }),
         unstable: true,
         coverage_dir: Some("cov".to_string()),
@@ -4199,9 +4984,16 @@ mod tests {
           filter: None,
           allow_none: false,
           shuffle: None,
+          shard: None,
+          shard_by_duration: false,
+          rerun_failed: false,
+          timeout: None,
+          update_snapshots: false,
           include: None,
           ignore: vec![],
           concurrent_jobs: NonZeroUsize::new(4).unwrap(),
+          reporter: TestReporterKind::Pretty,
+          reporter_output: None,
         }),
         ..Flags::default()
       }
@@ -4224,9 +5016,16 @@ mod tests {
           filter: None,
           allow_none: false,
           shuffle: None,
+          shard: None,
+          shard_by_duration: false,
+          rerun_failed: false,
+          timeout: None,
+          update_snapshots: false,
           include: None,
           ignore: vec![],
           concurrent_jobs: NonZeroUsize::new(1).unwrap(),
+          reporter: TestReporterKind::Pretty,
+          reporter_output: None,
         }),
         ..Flags::default()
       }
@@ -4253,9 +5052,16 @@ mod tests {
           filter: None,
           allow_none: false,
           shuffle: None,
+          shard: None,
+          shard_by_duration: false,
+          rerun_failed: false,
+          timeout: None,
+          update_snapshots: false,
           include: None,
           ignore: vec![],
           concurrent_jobs: NonZeroUsize::new(1).unwrap(),
+          reporter: TestReporterKind::Pretty,
+          reporter_output: None,
         }),
         enable_testing_features: true,
         ..Flags::default()
@@ -4276,9 +5082,16 @@ mod tests {
           filter: None,
           allow_none: false,
           shuffle: Some(1),
+          shard: None,
+          shard_by_duration: false,
+          rerun_failed: false,
+          timeout: None,
+          update_snapshots: false,
           include: None,
           ignore: vec![],
           concurrent_jobs: NonZeroUsize::new(1).unwrap(),
+          reporter: TestReporterKind::Pretty,
+          reporter_output: None,
         }),
         watch: false,
         ..Flags::default()
@@ -4286,6 +5099,227 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_shard() {
+    let r = flags_from_vec(svec!["deno", "test", "--shard=2/3"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          no_run: false,
+          doc: false,
+          fail_fast: None,
+          filter: None,
+          allow_none: false,
+          shuffle: None,
+          shard: Some(TestShard { index: 2, total: 3 }),
+          shard_by_duration: false,
+          rerun_failed: false,
+          timeout: None,
+          update_snapshots: false,
+          include: None,
+          ignore: vec![],
+          concurrent_jobs: NonZeroUsize::new(1).unwrap(),
+          reporter: TestReporterKind::Pretty,
+          reporter_output: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--shard=1/2",
+      "--shard-by-duration"
+    ]);
+    match r.unwrap().subcommand {
+      DenoSubcommand::Test(test_flags) => {
+        assert_eq!(test_flags.shard, Some(TestShard { index: 1, total: 2 }));
+        assert!(test_flags.shard_by_duration);
+      }
+      _ => unreachable!(),
+    }
+
+    let r = flags_from_vec(svec!["deno", "test", "--shard-by-duration"]);
+    assert!(r.is_err());
+
+    for invalid in ["0/3", "4/3", "1/0", "1", "a/b", "1/3/5"] {
+      let r =
+        flags_from_vec(svec!["deno", "test", format!("--shard={}", invalid)]);
+      assert!(r.is_err(), "{}", invalid);
+    }
+  }
+
+  #[test]
+  fn test_rerun_failed() {
+    let r = flags_from_vec(svec!["deno", "test", "--rerun-failed"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          no_run: false,
+          doc: false,
+          fail_fast: None,
+          filter: None,
+          allow_none: false,
+          shuffle: None,
+          shard: None,
+          shard_by_duration: false,
+          rerun_failed: true,
+          timeout: None,
+          update_snapshots: false,
+          include: None,
+          ignore: vec![],
+          concurrent_jobs: NonZeroUsize::new(1).unwrap(),
+          reporter: TestReporterKind::Pretty,
+          reporter_output: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "test", "--failed-only"]);
+    assert!(
+      matches!(r.unwrap().subcommand, DenoSubcommand::Test(t) if t.rerun_failed)
+    );
+  }
+
+  #[test]
+  fn test_reporter() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--reporter=junit",
+      "--reporter-output=report.xml"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          no_run: false,
+          doc: false,
+          fail_fast: None,
+          filter: None,
+          allow_none: false,
+          shuffle: None,
+          shard: None,
+          shard_by_duration: false,
+          rerun_failed: false,
+          timeout: None,
+          update_snapshots: false,
+          include: None,
+          ignore: vec![],
+          concurrent_jobs: NonZeroUsize::new(1).unwrap(),
+          reporter: TestReporterKind::Junit,
+          reporter_output: Some(PathBuf::from("report.xml")),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "test", "--reporter=xml"]);
+    assert!(r.is_err());
+
+    let r =
+      flags_from_vec(svec!["deno", "test", "--reporter-output=report.xml"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_timeout() {
+    let r = flags_from_vec(svec!["deno", "test", "--timeout=500"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          no_run: false,
+          doc: false,
+          fail_fast: None,
+          filter: None,
+          allow_none: false,
+          shuffle: None,
+          shard: None,
+          shard_by_duration: false,
+          rerun_failed: false,
+          timeout: Some(NonZeroU64::new(500).unwrap()),
+          update_snapshots: false,
+          include: None,
+          ignore: vec![],
+          concurrent_jobs: NonZeroUsize::new(1).unwrap(),
+          reporter: TestReporterKind::Pretty,
+          reporter_output: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "test", "--timeout=0"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_update_snapshots() {
+    let r = flags_from_vec(svec!["deno", "test", "--update-snapshots"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          no_run: false,
+          doc: false,
+          fail_fast: None,
+          filter: None,
+          allow_none: false,
+          shuffle: None,
+          shard: None,
+          shard_by_duration: false,
+          rerun_failed: false,
+          timeout: None,
+          update_snapshots: true,
+          include: None,
+          ignore: vec![],
+          concurrent_jobs: NonZeroUsize::new(1).unwrap(),
+          reporter: TestReporterKind::Pretty,
+          reporter_output: None,
+        }),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn bench_with_flags() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "bench",
+      "--unstable",
+      "--allow-read",
+      "--filter",
+      "- foo",
+      "--json",
+      "--ignore=bar.js",
+      "dir1/",
+      "dir2/",
+      "--",
+      "arg1",
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Bench(BenchFlags {
+          ignore: vec![PathBuf::from("bar.js")],
+          include: Some(svec!["dir1/", "dir2/"]),
+          filter: Some("- foo".to_string()),
+          json: true,
+        }),
+        unstable: true,
+        allow_read: Some(vec![]),
+        argv: svec!["arg1"],
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn test_watch() {
     let r = flags_from_vec(svec!["deno", "test", "--watch"]);
@@ -4299,9 +5333,16 @@ mod tests {
           filter: None,
           allow_none: false,
           shuffle: None,
+          shard: None,
+          shard_by_duration: false,
+          rerun_failed: false,
+          timeout: None,
+          update_snapshots: false,
           include: None,
           ignore: vec![],
           concurrent_jobs: NonZeroUsize::new(1).unwrap(),
+          reporter: TestReporterKind::Pretty,
+          reporter_output: None,
         }),
         watch: true,
         ..Flags::default()
@@ -4406,6 +5447,8 @@ mod tests {
           json: true,
           source_file: Some("path/to/module.ts".to_string()),
           filter: None,
+          html: None,
+          lint: None,
         }),
         ..Flags::default()
       }
@@ -4425,6 +5468,8 @@ mod tests {
           json: false,
           source_file: Some("path/to/module.ts".to_string()),
           filter: Some("SomeClass.someField".to_string()),
+          html: None,
+          lint: None,
         }),
         ..Flags::default()
       }
@@ -4439,6 +5484,8 @@ mod tests {
           json: false,
           source_file: None,
           filter: None,
+          html: None,
+          lint: None,
         }),
         ..Flags::default()
       }
@@ -4453,11 +5500,67 @@ mod tests {
           json: false,
           source_file: Some("--builtin".to_string()),
           filter: Some("Deno.Listener".to_string()),
+          html: None,
+          lint: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "doc",
+      "--html=docs",
+      "mod.ts",
+      "testing.ts",
+      "other.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          json: false,
+          source_file: None,
+          filter: None,
+          html: Some(DocHtmlFlags {
+            out_dir: PathBuf::from("docs"),
+            source_files: svec!["mod.ts", "testing.ts", "other.ts"],
+          }),
+          lint: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "doc", "--html=docs"]);
+    assert!(r.is_err());
+
+    let r =
+      flags_from_vec(svec!["deno", "doc", "--lint", "mod.ts", "testing.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          json: false,
+          source_file: None,
+          filter: None,
+          html: None,
+          lint: Some(DocLintFlags {
+            source_files: svec!["mod.ts", "testing.ts"],
+          }),
         }),
         ..Flags::default()
       }
     );
 
+    let r = flags_from_vec(svec!["deno", "doc", "--lint", "--json", "mod.ts"]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec!["deno", "doc", "mod.ts", "Foo", "other.ts"]);
+    assert!(r.is_err());
+
     let r =
       flags_from_vec(svec!["deno", "doc", "--private", "path/to/module.js"]);
     assert_eq!(
@@ -4468,6 +5571,8 @@ mod tests {
           json: false,
           source_file: Some("path/to/module.js".to_string()),
           filter: None,
+          html: None,
+          lint: None,
         }),
         ..Flags::default()
       }
@@ -4555,12 +5660,113 @@ mod tests {
           include: vec![r"^file:".to_string()],
           exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
           lcov: false,
+          cobertura: false,
+          html: None,
+          min_lines: None,
+          min_branches: None,
+          min_functions: None,
         }),
         ..Flags::default()
       }
     );
   }
 
+  #[test]
+  fn coverage_reporters() {
+    let r = flags_from_vec(svec!["deno", "coverage", "--cobertura", "foo"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Coverage(CoverageFlags {
+          files: vec![PathBuf::from("foo")],
+          ignore: vec![],
+          include: vec![r"^file:".to_string()],
+          exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
+          lcov: false,
+          cobertura: true,
+          html: None,
+          min_lines: None,
+          min_branches: None,
+          min_functions: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "coverage", "--html", "foo"]);
+    assert_eq!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Coverage(CoverageFlags {
+        files: vec![PathBuf::from("foo")],
+        ignore: vec![],
+        include: vec![r"^file:".to_string()],
+        exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
+        lcov: false,
+        cobertura: false,
+        html: Some(PathBuf::from("html_cov")),
+        min_lines: None,
+        min_branches: None,
+        min_functions: None,
+      })
+    );
+
+    let r = flags_from_vec(svec!["deno", "coverage", "--html=out", "foo"]);
+    assert_eq!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Coverage(CoverageFlags {
+        files: vec![PathBuf::from("foo")],
+        ignore: vec![],
+        include: vec![r"^file:".to_string()],
+        exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
+        lcov: false,
+        cobertura: false,
+        html: Some(PathBuf::from("out")),
+        min_lines: None,
+        min_branches: None,
+        min_functions: None,
+      })
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "coverage", "--lcov", "--cobertura", "foo"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn coverage_thresholds() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "coverage",
+      "--min-lines=80",
+      "--min-functions=62.5",
+      "--config",
+      "deno.json",
+      "foo"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Coverage(CoverageFlags {
+          files: vec![PathBuf::from("foo")],
+          ignore: vec![],
+          include: vec![r"^file:".to_string()],
+          exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
+          lcov: false,
+          cobertura: false,
+          html: None,
+          min_lines: Some(80.0),
+          min_branches: None,
+          min_functions: Some(62.5),
+        }),
+        config_path: Some("deno.json".to_string()),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "coverage", "--min-lines=101", "foo"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn location_with_bad_scheme() {
     #[rustfmt::skip]
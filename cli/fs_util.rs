@@ -5,6 +5,7 @@ use deno_core::error::{uri_error, AnyError};
 pub use deno_core::normalize_path;
 use deno_core::ModuleSpecifier;
 use deno_runtime::deno_crypto::rand;
+use regex::Regex;
 use std::env::current_dir;
 use std::fs::OpenOptions;
 use std::io::{Error, Write};
@@ -161,6 +162,33 @@ pub fn is_supported_ext_fmt(path: &Path) -> bool {
   }
 }
 
+/// Checks if the path has a basename and extension Deno supports for benches.
+pub fn is_supported_bench_path(path: &Path) -> bool {
+  use std::path::Component;
+  if let Some(Component::Normal(basename_os_str)) =
+    path.components().next_back()
+  {
+    let basename = basename_os_str.to_string_lossy();
+    basename.ends_with("_bench.ts")
+      || basename.ends_with("_bench.tsx")
+      || basename.ends_with("_bench.js")
+      || basename.ends_with("_bench.mjs")
+      || basename.ends_with("_bench.jsx")
+      || basename.ends_with(".bench.ts")
+      || basename.ends_with(".bench.tsx")
+      || basename.ends_with(".bench.js")
+      || basename.ends_with(".bench.mjs")
+      || basename.ends_with(".bench.jsx")
+      || basename == "bench.ts"
+      || basename == "bench.tsx"
+      || basename == "bench.js"
+      || basename == "bench.mjs"
+      || basename == "bench.jsx"
+  } else {
+    false
+  }
+}
+
 /// Checks if the path has a basename and extension Deno supports for tests.
 pub fn is_supported_test_path(path: &Path) -> bool {
   use std::path::Component;
@@ -381,6 +409,31 @@ pub fn specifier_parent(specifier: &ModuleSpecifier) -> ModuleSpecifier {
   specifier
 }
 
+/// Converts a glob into an anchored regular expression. `**` matches any
+/// number of path segments, while `*` and `?` never match a `/`.
+pub fn glob_to_regex(glob: &str) -> Regex {
+  let mut pattern = String::from("^");
+  let mut chars = glob.chars().peekable();
+  while let Some(c) = chars.next() {
+    match c {
+      '*' if chars.peek() == Some(&'*') => {
+        chars.next();
+        if chars.peek() == Some(&'/') {
+          chars.next();
+          pattern.push_str("(?:.*/)?");
+        } else {
+          pattern.push_str(".*");
+        }
+      }
+      '*' => pattern.push_str("[^/]*"),
+      '?' => pattern.push_str("[^/]"),
+      c => pattern.push_str(&regex::escape(&c.to_string())),
+    }
+  }
+  pattern.push('$');
+  Regex::new(&pattern).unwrap()
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -528,6 +581,23 @@ mod tests {
     assert!(!is_supported_test_path(Path::new("NotAtest.ts")));
   }
 
+  #[test]
+  fn test_is_supported_bench_path() {
+    assert!(is_supported_bench_path(Path::new(
+      "benches/subdir/foo_bench.ts"
+    )));
+    assert!(is_supported_bench_path(Path::new(
+      "benches/subdir/foo_bench.js"
+    )));
+    assert!(is_supported_bench_path(Path::new("bar/foo.bench.tsx")));
+    assert!(is_supported_bench_path(Path::new("bar/foo.bench.mjs")));
+    assert!(is_supported_bench_path(Path::new("foo/bar/bench.ts")));
+    assert!(is_supported_bench_path(Path::new("foo/bar/bench.jsx")));
+    assert!(!is_supported_bench_path(Path::new("foo/bar_test.ts")));
+    assert!(!is_supported_bench_path(Path::new("benchmark.ts")));
+    assert!(!is_supported_bench_path(Path::new("notabench.js")));
+  }
+
   #[test]
   fn test_collect_files() {
     fn create_files(dir_path: &Path, files: &[&str]) {
@@ -750,4 +820,20 @@ mod tests {
       assert_eq!(result.to_string(), expected);
     }
   }
+
+  #[test]
+  fn test_glob_to_regex() {
+    run_test("src/*.ts", "src/mod.ts", true);
+    run_test("src/*.ts", "src/util/mod.ts", false);
+    run_test("src/**/*.ts", "src/mod.ts", true);
+    run_test("src/**/*.ts", "src/util/mod.ts", true);
+    run_test("src/**", "src/util/mod.ts", true);
+    run_test("src/?.ts", "src/a.ts", true);
+    run_test("src/?.ts", "src/ab.ts", false);
+    run_test("src/mod.ts", "src/mod_ts", false);
+
+    fn run_test(glob: &str, path: &str, expected: bool) {
+      assert_eq!(glob_to_regex(glob).is_match(path), expected, "{}", glob);
+    }
+  }
 }
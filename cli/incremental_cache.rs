@@ -0,0 +1,90 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+use crate::checksum;
+use crate::disk_cache::DiskCache;
+use crate::version;
+use deno_core::serde::Serialize;
+use deno_core::serde_json;
+use log::debug;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Remembers which files a tool (like `deno lint` or `deno fmt`) has already
+/// processed without finding any problems, so they can be skipped on the next
+/// run. An entry is only valid while the content of the file, the version of
+/// Deno and the effective configuration of the tool are all unchanged.
+pub struct IncrementalCache {
+  disk_cache: DiskCache,
+  state_hash: String,
+}
+
+impl IncrementalCache {
+  pub fn new<TState: Serialize>(
+    disk_cache: &DiskCache,
+    tool_name: &str,
+    state: &TState,
+  ) -> Self {
+    let state_json = serde_json::to_string(state).unwrap();
+    Self {
+      disk_cache: DiskCache::new(&disk_cache.location.join(tool_name)),
+      state_hash: checksum::gen(&[version::deno(), state_json]),
+    }
+  }
+
+  /// Returns `true` if the file was previously recorded with the same text.
+  pub fn is_file_same(&self, file_path: &Path, file_text: &str) -> bool {
+    match self.disk_cache.get(&Self::entry_filename(file_path)) {
+      Ok(hash) => hash == self.text_hash(file_text).as_bytes(),
+      Err(_) => false,
+    }
+  }
+
+  /// Record that the file with the provided text doesn't need to be processed
+  /// again. Failing to write the cache is not an error, the file is simply
+  /// processed again on the next run.
+  pub fn update_file(&self, file_path: &Path, file_text: &str) {
+    let filename = Self::entry_filename(file_path);
+    let hash = self.text_hash(file_text);
+    if let Err(err) = self.disk_cache.set(&filename, hash.as_bytes()) {
+      debug!(
+        "Failed to update incremental cache for {}: {}",
+        file_path.display(),
+        err
+      );
+    }
+  }
+
+  fn entry_filename(file_path: &Path) -> PathBuf {
+    PathBuf::from(checksum::gen(&[file_path.to_string_lossy().as_bytes()]))
+  }
+
+  fn text_hash(&self, file_text: &str) -> String {
+    checksum::gen(&[self.state_hash.as_bytes(), file_text.as_bytes()])
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tempfile::TempDir;
+
+  #[test]
+  fn test_incremental_cache() {
+    let temp_dir = TempDir::new().unwrap();
+    let disk_cache = DiskCache::new(temp_dir.path());
+    let file_path = temp_dir.path().join("mod.ts");
+    let cache = IncrementalCache::new(&disk_cache, "lint", &vec!["rule-a"]);
+    assert!(!cache.is_file_same(&file_path, "let a = 1;"));
+    cache.update_file(&file_path, "let a = 1;");
+    assert!(cache.is_file_same(&file_path, "let a = 1;"));
+    assert!(!cache.is_file_same(&file_path, "let a = 2;"));
+
+    // a different configuration invalidates the entries
+    let cache = IncrementalCache::new(&disk_cache, "lint", &vec!["rule-b"]);
+    assert!(!cache.is_file_same(&file_path, "let a = 1;"));
+
+    // as does a different tool
+    let cache = IncrementalCache::new(&disk_cache, "fmt", &vec!["rule-a"]);
+    assert!(!cache.is_file_same(&file_path, "let a = 1;"));
+  }
+}
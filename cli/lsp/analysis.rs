@@ -6,15 +6,20 @@ use super::tsc;
 use crate::config_file::LintConfig;
 use crate::tools::lint::create_linter;
 use crate::tools::lint::get_configured_rules;
+use crate::tools::lint::get_lint_fixes;
+use crate::tools::lint::LintFix;
 
+use deno_ast::swc::common::BytePos;
 use deno_ast::SourceTextInfo;
 use deno_core::anyhow::anyhow;
 use deno_core::error::custom_error;
 use deno_core::error::AnyError;
 use deno_core::serde::Deserialize;
+use deno_core::serde::Serialize;
 use deno_core::serde_json;
 use deno_core::serde_json::json;
 use deno_core::ModuleSpecifier;
+use import_map::ImportMap;
 use lspower::lsp;
 use lspower::lsp::Position;
 use lspower::lsp::Range;
@@ -63,6 +68,16 @@ lazy_static::lazy_static! {
   static ref TYPES_REFERENCE_RE: Regex =
     Regex::new(r#"(?i)\stypes\s*=\s*["']([^"']*)["']"#).unwrap();
 
+  /// A source action which sorts and merges the imports of a module, without
+  /// removing the unused ones.
+  pub static ref SOURCE_SORT_IMPORTS: lsp::CodeActionKind =
+    [lsp::CodeActionKind::SOURCE.as_str(), "sortImports"].join(".").into();
+
+  /// A source action which only removes the unused imports of a module.
+  pub static ref SOURCE_REMOVE_UNUSED_IMPORTS: lsp::CodeActionKind =
+    [lsp::CodeActionKind::SOURCE.as_str(), "removeUnusedImports"]
+      .join(".")
+      .into();
 }
 
 const SUPPORTED_EXTENSIONS: &[&str] = &[".ts", ".tsx", ".js", ".jsx", ".mjs"];
@@ -76,9 +91,46 @@ pub enum Category {
     message: String,
     code: String,
     hint: Option<String>,
+    fix: Option<DenoLintFixData>,
   },
 }
 
+/// The automatic fix for a lint diagnostic, which is sent to the client as
+/// the `data` of the diagnostic so it can be returned as a code action.
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DenoLintFixData {
+  pub description: String,
+  pub edits: Vec<lsp::TextEdit>,
+}
+
+impl DenoLintFixData {
+  fn from_lint_fix(text_info: &SourceTextInfo, fix: LintFix) -> Self {
+    let as_position = |pos: usize| {
+      let line_and_column =
+        text_info.line_and_column_index(BytePos(pos as u32));
+      Position {
+        line: line_and_column.line_index as u32,
+        character: line_and_column.column_index as u32,
+      }
+    };
+    DenoLintFixData {
+      description: fix.description,
+      edits: fix
+        .edits
+        .into_iter()
+        .map(|edit| lsp::TextEdit {
+          range: Range {
+            start: as_position(edit.range.start),
+            end: as_position(edit.range.end),
+          },
+          new_text: edit.new_text,
+        })
+        .collect(),
+    }
+  }
+}
+
 /// A structure to hold a reference to a diagnostic message.
 #[derive(Debug, PartialEq, Eq)]
 pub struct Reference {
@@ -93,6 +145,7 @@ impl Reference {
         message,
         code,
         hint,
+        fix,
       } => lsp::Diagnostic {
         range: self.range,
         severity: Some(lsp::DiagnosticSeverity::WARNING),
@@ -109,7 +162,7 @@ impl Reference {
         },
         related_information: None,
         tags: None, // we should tag unused code
-        data: None,
+        data: fix.as_ref().map(|fix| json!(fix)),
       },
     }
   }
@@ -135,15 +188,20 @@ pub fn get_lint_references(
   let lint_rules = get_configured_rules(maybe_lint_config, None, None, None)?;
   let linter = create_linter(parsed_source.media_type(), lint_rules);
   let lint_diagnostics = linter.lint_with_ast(parsed_source);
+  let lint_fixes = get_lint_fixes(parsed_source, &lint_diagnostics);
 
   Ok(
     lint_diagnostics
       .into_iter()
-      .map(|d| Reference {
+      .zip(lint_fixes)
+      .map(|(d, maybe_fix)| Reference {
         category: Category::Lint {
           message: d.message,
           code: d.code,
           hint: d.hint,
+          fix: maybe_fix.map(|fix| {
+            DenoLintFixData::from_lint_fix(parsed_source.source(), fix)
+          }),
         },
         range: as_lsp_range(&d.range),
       })
@@ -160,27 +218,136 @@ fn code_as_string(code: &Option<lsp::NumberOrString>) -> String {
 }
 
 /// Iterate over the supported extensions, concatenating the extension on the
-/// specifier, returning the first specifier that is resolve-able, otherwise
-/// None if none match.
+/// specifier, returning the first specifier that is resolve-able along with
+/// the module it resolves to, otherwise None if none match.
 fn check_specifier(
   specifier: &str,
   referrer: &ModuleSpecifier,
   snapshot: &language_server::StateSnapshot,
-) -> Option<String> {
+) -> Option<(String, ModuleSpecifier)> {
   for ext in SUPPORTED_EXTENSIONS {
     let specifier_with_ext = format!("{}{}", specifier, ext);
-    if snapshot
+    if let Some(resolved) = snapshot
       .documents
-      .contains_import(&specifier_with_ext, referrer)
+      .resolve_import(&specifier_with_ext, referrer)
     {
-      return Some(specifier_with_ext);
+      if snapshot.documents.contains_specifier(&resolved) {
+        return Some((specifier_with_ext, resolved));
+      }
     }
   }
   None
 }
 
+/// Find a bare specifier in the import map which resolves to the module. An
+/// exact match of a key is preferred over a match of a key which maps a
+/// directory ("prefix/"), and of those the longest prefix wins.
+pub(crate) fn get_import_map_specifier(
+  specifier: &ModuleSpecifier,
+  referrer: &ModuleSpecifier,
+  import_map: &ImportMap,
+  keys: &[String],
+) -> Option<String> {
+  let mut maybe_prefix_match: Option<(usize, String)> = None;
+  for key in keys {
+    // keys which are relative or absolute paths are only there to remap
+    // other modules, and aren't any nicer to import than the module itself
+    if key.starts_with("./") || key.starts_with("../") || key.starts_with('/') {
+      continue;
+    }
+    let resolved = match import_map.resolve(key, referrer.as_str()) {
+      Ok(resolved) => resolved,
+      Err(_) => continue,
+    };
+    if key.ends_with('/') {
+      if let Some(rest) = specifier.as_str().strip_prefix(resolved.as_str()) {
+        if maybe_prefix_match
+          .as_ref()
+          .map_or(true, |(len, _)| resolved.as_str().len() > *len)
+        {
+          maybe_prefix_match =
+            Some((resolved.as_str().len(), format!("{}{}", key, rest)));
+        }
+      }
+    } else if &resolved == specifier {
+      return Some(key.clone());
+    }
+  }
+  maybe_prefix_match.map(|(_, specifier)| specifier)
+}
+
+/// Find a remote or bare specifier which is already used in another module to
+/// import the module, so that auto-imports stay consistent with the rest of
+/// the workspace instead of pointing at the location of the cached file.
+fn get_existing_specifier(
+  specifier: &ModuleSpecifier,
+  snapshot: &language_server::StateSnapshot,
+) -> Option<String> {
+  for document in snapshot.documents.documents(false, true) {
+    for (text, dependency) in document.dependencies() {
+      // relative specifiers only make sense for the module they are used in
+      if text.starts_with("./") || text.starts_with("../") {
+        continue;
+      }
+      for resolved in [&dependency.maybe_code, &dependency.maybe_type] {
+        if let Some(Ok((resolved, _))) = resolved {
+          if resolved == specifier
+            || (resolved.scheme() != "file"
+              && snapshot
+                .documents
+                .get(resolved)
+                .map_or(false, |d| d.specifier() == specifier))
+          {
+            return Some(text);
+          }
+        }
+      }
+    }
+  }
+  None
+}
+
+/// Rewrite a module specifier generated by tsc into the one that should be
+/// used to import the module in Deno. A bare specifier from the import map is
+/// preferred, then a remote specifier already used in the workspace, and
+/// finally the specifier with its extension added.
+fn fix_specifier(
+  specifier: &str,
+  referrer: &ModuleSpecifier,
+  language_server: &language_server::Inner,
+  snapshot: &language_server::StateSnapshot,
+) -> Option<String> {
+  let (specifier_with_ext, resolved) =
+    match snapshot.documents.resolve_import(specifier, referrer) {
+      Some(resolved) if snapshot.documents.contains_specifier(&resolved) => {
+        (specifier.to_string(), resolved)
+      }
+      _ => check_specifier(specifier, referrer, snapshot)?,
+    };
+  if let Some(import_map) = &language_server.maybe_import_map {
+    if let Some(new_specifier) = get_import_map_specifier(
+      &resolved,
+      referrer,
+      import_map,
+      &language_server.import_map_keys,
+    ) {
+      return Some(new_specifier);
+    }
+  }
+  if resolved.scheme() != "file" {
+    if let Some(new_specifier) = get_existing_specifier(&resolved, snapshot) {
+      return Some(new_specifier);
+    }
+  }
+  if specifier_with_ext != specifier {
+    Some(specifier_with_ext)
+  } else {
+    None
+  }
+}
+
 /// For a set of tsc changes, can them for any that contain something that looks
-/// like an import and rewrite the import specifier to include the extension
+/// like an import and rewrite the import specifier to one Deno can resolve
 pub(crate) fn fix_ts_import_changes(
   referrer: &ModuleSpecifier,
   changes: &[tsc::FileTextChanges],
@@ -199,7 +366,7 @@ pub(crate) fn fix_ts_import_changes(
           .ok_or_else(|| anyhow!("Missing capture."))?
           .as_str();
         if let Some(new_specifier) =
-          check_specifier(specifier, referrer, &snapshot)
+          fix_specifier(specifier, referrer, language_server, &snapshot)
         {
           let new_text =
             text_change.new_text.replace(specifier, &new_specifier);
@@ -224,7 +391,8 @@ pub(crate) fn fix_ts_import_changes(
 }
 
 /// Fix tsc import code actions so that the module specifier is correct for
-/// resolution by Deno (includes the extension).
+/// resolution by Deno (includes the extension, or is a bare specifier from the
+/// import map or a remote URL).
 fn fix_ts_import_action(
   referrer: &ModuleSpecifier,
   action: &tsc::CodeFixAction,
@@ -247,7 +415,7 @@ fn fix_ts_import_action(
         .as_str();
       let snapshot = language_server.snapshot()?;
       if let Some(new_specifier) =
-        check_specifier(specifier, referrer, &snapshot)
+        fix_specifier(specifier, referrer, language_server, &snapshot)
       {
         let description = action.description.replace(specifier, &new_specifier);
         let changes = action
@@ -363,6 +531,54 @@ pub struct DenoFixData {
   pub specifier: ModuleSpecifier,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportsCodeActionData {
+  pub specifier: ModuleSpecifier,
+}
+
+/// Return the source actions which organize the imports of a module, limited
+/// to the kinds the client asked for. The edits are only calculated when the
+/// code action is resolved.
+pub fn get_imports_source_actions(
+  specifier: &ModuleSpecifier,
+  maybe_only: Option<&[lsp::CodeActionKind]>,
+) -> Vec<lsp::CodeAction> {
+  let actions = [
+    (
+      lsp::CodeActionKind::SOURCE_ORGANIZE_IMPORTS,
+      "Organize imports",
+    ),
+    (SOURCE_SORT_IMPORTS.clone(), "Sort imports"),
+    (
+      SOURCE_REMOVE_UNUSED_IMPORTS.clone(),
+      "Remove unused imports",
+    ),
+  ];
+  actions
+    .into_iter()
+    .filter(|(kind, _)| {
+      maybe_only.map_or(true, |only| {
+        only.iter().any(|o| {
+          kind == o || kind.as_str().starts_with(&format!("{}.", o.as_str()))
+        })
+      })
+    })
+    .map(|(kind, title)| lsp::CodeAction {
+      title: title.to_string(),
+      kind: Some(kind),
+      diagnostics: None,
+      edit: None,
+      command: None,
+      is_preferred: None,
+      disabled: None,
+      data: Some(json!(ImportsCodeActionData {
+        specifier: specifier.clone(),
+      })),
+    })
+    .collect()
+}
+
 #[derive(Debug, Clone)]
 enum CodeActionKind {
   Deno(lsp::CodeAction),
@@ -413,6 +629,36 @@ impl CodeActionCollection {
     Ok(())
   }
 
+  /// Add the automatic fix of a lint diagnostic, if it has one, to the code
+  /// actions collection.
+  pub(crate) fn add_deno_lint_fix_action(
+    &mut self,
+    specifier: &ModuleSpecifier,
+    diagnostic: &lsp::Diagnostic,
+  ) -> Result<(), AnyError> {
+    if let Some(data) = diagnostic.data.clone() {
+      let fix_data: DenoLintFixData = serde_json::from_value(data)?;
+      let mut changes = HashMap::new();
+      changes.insert(specifier.clone(), fix_data.edits);
+      let code_action = lsp::CodeAction {
+        title: fix_data.description,
+        kind: Some(lsp::CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diagnostic.clone()]),
+        command: None,
+        is_preferred: Some(true),
+        disabled: None,
+        data: None,
+        edit: Some(lsp::WorkspaceEdit {
+          changes: Some(changes),
+          change_annotations: None,
+          document_changes: None,
+        }),
+      };
+      self.actions.push(CodeActionKind::DenoLint(code_action));
+    }
+    Ok(())
+  }
+
   pub(crate) fn add_deno_lint_ignore_action(
     &mut self,
     specifier: &ModuleSpecifier,
@@ -759,6 +1005,7 @@ fn prepend_whitespace(content: String, line_content: Option<String>) -> String {
 #[cfg(test)]
 mod tests {
   use super::*;
+  use deno_core::resolve_url;
 
   #[test]
   fn test_reference_to_diagnostic() {
@@ -780,6 +1027,7 @@ mod tests {
             message: "message1".to_string(),
             code: "code1".to_string(),
             hint: None,
+            fix: None,
           },
           range,
         },
@@ -798,6 +1046,7 @@ mod tests {
             message: "message2".to_string(),
             code: "code2".to_string(),
             hint: Some("hint2".to_string()),
+            fix: None,
           },
           range,
         },
@@ -847,4 +1096,84 @@ mod tests {
       }
     );
   }
+
+  #[test]
+  fn test_get_import_map_specifier() {
+    let import_map = ImportMap::from_json(
+      "file:///project/import_map.json",
+      r#"{
+        "imports": {
+          "std/": "https://deno.land/std@0.117.0/",
+          "std/path": "https://deno.land/std@0.117.0/path/mod.ts",
+          "@/": "./src/",
+          "./a.ts": "./b.ts"
+        }
+      }"#,
+    )
+    .unwrap();
+    let keys: Vec<String> = ["std/", "std/path", "@/", "./a.ts"]
+      .iter()
+      .map(|k| k.to_string())
+      .collect();
+    let referrer = resolve_url("file:///project/main.ts").unwrap();
+    let fixtures = [
+      (
+        "https://deno.land/std@0.117.0/path/mod.ts",
+        Some("std/path"),
+      ),
+      (
+        "https://deno.land/std@0.117.0/fs/mod.ts",
+        Some("std/fs/mod.ts"),
+      ),
+      ("file:///project/src/util.ts", Some("@/util.ts")),
+      ("file:///project/b.ts", None),
+      ("file:///other/mod.ts", None),
+    ];
+    for (specifier, expected) in fixtures {
+      let specifier = resolve_url(specifier).unwrap();
+      assert_eq!(
+        get_import_map_specifier(&specifier, &referrer, &import_map, &keys),
+        expected.map(|s| s.to_string()),
+      );
+    }
+  }
+
+  #[test]
+  fn test_get_imports_source_actions() {
+    let specifier = resolve_url("file:///a/file.ts").unwrap();
+    let kinds = |actions: Vec<lsp::CodeAction>| -> Vec<String> {
+      actions
+        .into_iter()
+        .map(|a| a.kind.unwrap().as_str().to_string())
+        .collect()
+    };
+    assert_eq!(
+      kinds(get_imports_source_actions(&specifier, None)),
+      vec![
+        "source.organizeImports",
+        "source.sortImports",
+        "source.removeUnusedImports"
+      ]
+    );
+    assert_eq!(
+      kinds(get_imports_source_actions(
+        &specifier,
+        Some(&[lsp::CodeActionKind::SOURCE])
+      ))
+      .len(),
+      3
+    );
+    assert_eq!(
+      kinds(get_imports_source_actions(
+        &specifier,
+        Some(&[lsp::CodeActionKind::SOURCE_ORGANIZE_IMPORTS])
+      )),
+      vec!["source.organizeImports"]
+    );
+    assert!(get_imports_source_actions(
+      &specifier,
+      Some(&[lsp::CodeActionKind::QUICKFIX])
+    )
+    .is_empty());
+  }
 }
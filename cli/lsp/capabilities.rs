@@ -5,6 +5,7 @@
 ///! language server, which helps determine what messages are sent from the
 ///! client.
 ///!
+use deno_core::serde_json::json;
 use lspower::lsp::CallHierarchyServerCapability;
 use lspower::lsp::ClientCapabilities;
 use lspower::lsp::CodeActionKind;
@@ -12,7 +13,13 @@ use lspower::lsp::CodeActionOptions;
 use lspower::lsp::CodeActionProviderCapability;
 use lspower::lsp::CodeLensOptions;
 use lspower::lsp::CompletionOptions;
+use lspower::lsp::DocumentLinkOptions;
+use lspower::lsp::DocumentOnTypeFormattingOptions;
 use lspower::lsp::DocumentSymbolOptions;
+use lspower::lsp::FileOperationFilter;
+use lspower::lsp::FileOperationPattern;
+use lspower::lsp::FileOperationPatternKind;
+use lspower::lsp::FileOperationRegistrationOptions;
 use lspower::lsp::FoldingRangeProviderCapability;
 use lspower::lsp::HoverProviderCapability;
 use lspower::lsp::ImplementationProviderCapability;
@@ -29,9 +36,13 @@ use lspower::lsp::TextDocumentSyncKind;
 use lspower::lsp::TextDocumentSyncOptions;
 use lspower::lsp::TypeDefinitionProviderCapability;
 use lspower::lsp::WorkDoneProgressOptions;
+use lspower::lsp::WorkspaceFileOperationsServerCapabilities;
 use lspower::lsp::WorkspaceFoldersServerCapabilities;
 use lspower::lsp::WorkspaceServerCapabilities;
 
+use super::analysis::SOURCE_REMOVE_UNUSED_IMPORTS;
+use super::analysis::SOURCE_SORT_IMPORTS;
+use super::formatting::ON_TYPE_TRIGGER_CHARACTERS;
 use super::refactor::ALL_KNOWN_REFACTOR_ACTION_KINDS;
 use super::semantic_tokens::get_legend;
 
@@ -51,6 +62,11 @@ fn code_action_capabilities(
           .iter()
           .map(|action| action.kind.clone()),
       );
+      code_action_kinds.extend([
+        CodeActionKind::SOURCE_ORGANIZE_IMPORTS,
+        SOURCE_SORT_IMPORTS.clone(),
+        SOURCE_REMOVE_UNUSED_IMPORTS.clone(),
+      ]);
 
       CodeActionProviderCapability::Options(CodeActionOptions {
         code_action_kinds: Some(code_action_kinds),
@@ -130,14 +146,29 @@ pub fn server_capabilities(
       resolve_provider: Some(true),
     }),
     document_formatting_provider: Some(OneOf::Left(true)),
-    document_range_formatting_provider: None,
-    document_on_type_formatting_provider: None,
+    document_range_formatting_provider: Some(OneOf::Left(true)),
+    document_on_type_formatting_provider: Some(
+      DocumentOnTypeFormattingOptions {
+        first_trigger_character: ON_TYPE_TRIGGER_CHARACTERS[0].to_string(),
+        more_trigger_character: Some(
+          ON_TYPE_TRIGGER_CHARACTERS[1..]
+            .iter()
+            .map(|c| c.to_string())
+            .collect(),
+        ),
+      },
+    ),
     selection_range_provider: Some(SelectionRangeProviderCapability::Simple(
       true,
     )),
     folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
     rename_provider: Some(OneOf::Left(true)),
-    document_link_provider: None,
+    document_link_provider: Some(DocumentLinkOptions {
+      resolve_provider: Some(false),
+      work_done_progress_options: WorkDoneProgressOptions {
+        work_done_progress: None,
+      },
+    }),
     color_provider: None,
     execute_command_provider: None,
     call_hierarchy_provider: Some(CallHierarchyServerCapability::Simple(true)),
@@ -156,9 +187,40 @@ pub fn server_capabilities(
         supported: Some(true),
         change_notifications: None,
       }),
-      file_operations: None,
+      file_operations: Some(WorkspaceFileOperationsServerCapabilities {
+        did_create: None,
+        will_create: None,
+        did_rename: None,
+        will_rename: Some(FileOperationRegistrationOptions {
+          filters: vec![
+            FileOperationFilter {
+              scheme: Some("file".to_string()),
+              pattern: FileOperationPattern {
+                glob: "**/*.{ts,tsx,js,jsx,mjs}".to_string(),
+                matches: Some(FileOperationPatternKind::File),
+                options: None,
+              },
+            },
+            FileOperationFilter {
+              scheme: Some("file".to_string()),
+              pattern: FileOperationPattern {
+                glob: "**/*".to_string(),
+                matches: Some(FileOperationPatternKind::Folder),
+                options: None,
+              },
+            },
+          ],
+        }),
+        did_delete: None,
+        will_delete: None,
+      }),
     }),
-    experimental: None,
+    // `lsp-types` does not support the inlay hint capability from version 3.17
+    // of the protocol yet, so it is advertised as experimental
+    experimental: Some(json!({
+      "inlayHintProvider": true,
+      "testingApi": true,
+    })),
     linked_editing_range_provider: None,
     moniker_provider: None,
   }
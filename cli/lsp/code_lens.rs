@@ -3,14 +3,12 @@
 use super::config::Config;
 use super::config::WorkspaceSettings;
 use super::language_server;
+use super::testing;
+use super::testing::TestDefinition;
 use super::text::LineIndex;
 use super::tsc;
 use super::tsc::NavigationTree;
 
-use deno_ast::swc::ast;
-use deno_ast::swc::common::Span;
-use deno_ast::swc::visit::Visit;
-use deno_ast::swc::visit::VisitWith;
 use deno_ast::ParsedSource;
 use deno_core::error::AnyError;
 use deno_core::resolve_url;
@@ -22,7 +20,6 @@ use deno_core::ModuleSpecifier;
 use lspower::lsp;
 use regex::Regex;
 use std::cell::RefCell;
-use std::collections::HashSet;
 use std::rc::Rc;
 use std::sync::Arc;
 
@@ -46,172 +43,30 @@ pub struct CodeLensData {
   pub specifier: ModuleSpecifier,
 }
 
-fn span_to_range(span: &Span, parsed_source: &ParsedSource) -> lsp::Range {
-  let start = parsed_source.source().line_and_column_index(span.lo);
-  let end = parsed_source.source().line_and_column_index(span.hi);
-  lsp::Range {
-    start: lsp::Position {
-      line: start.line_index as u32,
-      character: start.column_index as u32,
-    },
-    end: lsp::Position {
-      line: end.line_index as u32,
-      character: end.column_index as u32,
-    },
-  }
-}
-
-struct DenoTestCollector {
-  code_lenses: Vec<lsp::CodeLens>,
-  parsed_source: ParsedSource,
-  specifier: ModuleSpecifier,
-  test_vars: HashSet<String>,
-}
-
-impl DenoTestCollector {
-  pub fn new(specifier: ModuleSpecifier, parsed_source: ParsedSource) -> Self {
-    Self {
-      code_lenses: Vec::new(),
-      parsed_source,
-      specifier,
-      test_vars: HashSet::new(),
-    }
-  }
-
-  fn add_code_lens<N: AsRef<str>>(&mut self, name: N, span: &Span) {
-    let range = span_to_range(span, &self.parsed_source);
-    self.code_lenses.push(lsp::CodeLens {
-      range,
-      command: Some(lsp::Command {
-        title: "▶\u{fe0e} Run Test".to_string(),
-        command: "deno.test".to_string(),
-        arguments: Some(vec![json!(self.specifier), json!(name.as_ref())]),
-      }),
-      data: None,
-    });
-  }
-
-  fn check_call_expr(&mut self, node: &ast::CallExpr, span: &Span) {
-    if let Some(expr) = node.args.get(0).map(|es| es.expr.as_ref()) {
-      match expr {
-        ast::Expr::Object(obj_lit) => {
-          for prop in &obj_lit.props {
-            if let ast::PropOrSpread::Prop(prop) = prop {
-              if let ast::Prop::KeyValue(key_value_prop) = prop.as_ref() {
-                if let ast::PropName::Ident(ident) = &key_value_prop.key {
-                  if ident.sym.to_string() == "name" {
-                    if let ast::Expr::Lit(ast::Lit::Str(lit_str)) =
-                      key_value_prop.value.as_ref()
-                    {
-                      let name = lit_str.value.to_string();
-                      self.add_code_lens(name, span);
-                    }
-                  }
-                }
-              }
-            }
-          }
-        }
-        ast::Expr::Lit(ast::Lit::Str(lit_str)) => {
-          let name = lit_str.value.to_string();
-          self.add_code_lens(name, span);
-        }
-        _ => (),
-      }
-    }
-  }
-
-  /// Move out the code lenses from the collector.
-  fn take(self) -> Vec<lsp::CodeLens> {
-    self.code_lenses
+fn test_code_lens(
+  specifier: &ModuleSpecifier,
+  definition: &TestDefinition,
+) -> lsp::CodeLens {
+  lsp::CodeLens {
+    range: definition.range,
+    command: Some(lsp::Command {
+      title: "▶\u{fe0e} Run Test".to_string(),
+      command: "deno.test".to_string(),
+      arguments: Some(vec![json!(specifier), json!(definition.name)]),
+    }),
+    data: None,
   }
 }
 
-impl Visit for DenoTestCollector {
-  fn visit_call_expr(&mut self, node: &ast::CallExpr) {
-    if let ast::ExprOrSuper::Expr(callee_expr) = &node.callee {
-      match callee_expr.as_ref() {
-        ast::Expr::Ident(ident) => {
-          if self.test_vars.contains(&ident.sym.to_string()) {
-            self.check_call_expr(node, &ident.span);
-          }
-        }
-        ast::Expr::Member(member_expr) => {
-          if let ast::Expr::Ident(ns_prop_ident) = member_expr.prop.as_ref() {
-            if ns_prop_ident.sym.to_string() == "test" {
-              if let ast::ExprOrSuper::Expr(obj_expr) = &member_expr.obj {
-                if let ast::Expr::Ident(ident) = obj_expr.as_ref() {
-                  if ident.sym.to_string() == "Deno" {
-                    self.check_call_expr(node, &ns_prop_ident.span);
-                  }
-                }
-              }
-            }
-          }
-        }
-        _ => (),
-      }
-    }
-  }
-
-  fn visit_var_decl(&mut self, node: &ast::VarDecl) {
-    for decl in &node.decls {
-      if let Some(init) = &decl.init {
-        match init.as_ref() {
-          // Identify destructured assignments of `test` from `Deno`
-          ast::Expr::Ident(ident) => {
-            if ident.sym.to_string() == "Deno" {
-              if let ast::Pat::Object(object_pat) = &decl.name {
-                for prop in &object_pat.props {
-                  match prop {
-                    ast::ObjectPatProp::Assign(prop) => {
-                      let name = prop.key.sym.to_string();
-                      if name == "test" {
-                        self.test_vars.insert(name);
-                      }
-                    }
-                    ast::ObjectPatProp::KeyValue(prop) => {
-                      if let ast::PropName::Ident(key_ident) = &prop.key {
-                        if key_ident.sym.to_string() == "test" {
-                          if let ast::Pat::Ident(value_ident) =
-                            &prop.value.as_ref()
-                          {
-                            self
-                              .test_vars
-                              .insert(value_ident.id.sym.to_string());
-                          }
-                        }
-                      }
-                    }
-                    _ => (),
-                  }
-                }
-              }
-            }
-          }
-          // Identify variable assignments where the init is `Deno.test`
-          ast::Expr::Member(member_expr) => {
-            if let ast::ExprOrSuper::Expr(expr) = &member_expr.obj {
-              if let ast::Expr::Ident(obj_ident) = expr.as_ref() {
-                if obj_ident.sym.to_string() == "Deno" {
-                  if let ast::Expr::Ident(prop_ident) =
-                    &member_expr.prop.as_ref()
-                  {
-                    if prop_ident.sym.to_string() == "test" {
-                      if let ast::Pat::Ident(binding_ident) = &decl.name {
-                        self.test_vars.insert(binding_ident.id.sym.to_string());
-                      }
-                    }
-                  }
-                }
-              }
-            }
-          }
-          _ => (),
-        }
-      }
-    }
-  }
+/// Return the `Deno.test` code lenses of a module.
+fn collect_test_code_lenses(
+  specifier: &ModuleSpecifier,
+  parsed_source: &ParsedSource,
+) -> Vec<lsp::CodeLens> {
+  testing::collect_tests(specifier, parsed_source)
+    .iter()
+    .map(|definition| test_code_lens(specifier, definition))
+    .collect()
 }
 
 async fn resolve_implementation_code_lens(
@@ -398,10 +253,7 @@ fn collect_test(
 ) -> Result<Vec<lsp::CodeLens>, AnyError> {
   if config.specifier_code_lens_test(specifier) {
     if let Some(parsed_source) = parsed_source {
-      let mut collector =
-        DenoTestCollector::new(specifier.clone(), parsed_source.clone());
-      parsed_source.module().visit_with(&mut collector);
-      return Ok(collector.take());
+      return Ok(collect_test_code_lenses(specifier, &parsed_source));
     }
   }
   Ok(Vec::new())
@@ -534,7 +386,7 @@ mod tests {
   use super::*;
 
   #[test]
-  fn test_deno_test_collector() {
+  fn test_collect_test_code_lenses() {
     let specifier = resolve_url("https://deno.land/x/mod.ts").unwrap();
     let source = Arc::new(
       r#"
@@ -556,11 +408,8 @@ mod tests {
       maybe_syntax: None,
     })
     .unwrap();
-    let mut collector =
-      DenoTestCollector::new(specifier, parsed_module.clone());
-    parsed_module.module().visit_with(&mut collector);
     assert_eq!(
-      collector.take(),
+      collect_test_code_lenses(&specifier, &parsed_module),
       vec![
         lsp::CodeLens {
           range: lsp::Range {
@@ -18,7 +18,7 @@ use lspower::lsp;
 
 const CURRENT_PATH: &str = ".";
 const PARENT_PATH: &str = "..";
-const LOCAL_PATHS: &[&str] = &[CURRENT_PATH, PARENT_PATH];
+pub(crate) const LOCAL_PATHS: &[&str] = &[CURRENT_PATH, PARENT_PATH];
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -172,7 +172,7 @@ pub(crate) async fn get_import_completions(
 }
 
 /// Return local completions that are relative to the base specifier.
-fn get_local_completions(
+pub(crate) fn get_local_completions(
   base: &ModuleSpecifier,
   current: &str,
   range: &lsp::Range,
@@ -330,7 +330,7 @@ fn get_workspace_completions(
 /// assert_eq!(relative_specifier(&specifier, &base), "../b.ts");
 /// ```
 ///
-fn relative_specifier(
+pub(crate) fn relative_specifier(
   specifier: &ModuleSpecifier,
   base: &ModuleSpecifier,
 ) -> String {
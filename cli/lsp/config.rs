@@ -26,6 +26,7 @@ pub struct ClientCapabilities {
   pub code_action_disabled_support: bool,
   pub line_folding_only: bool,
   pub status_notification: bool,
+  pub testing_api: bool,
   pub workspace_configuration: bool,
   pub workspace_did_change_watched_files: bool,
 }
@@ -128,6 +129,60 @@ impl Default for ImportCompletionSettings {
   }
 }
 
+/// Which arguments of a call should have an inlay hint with the name of the
+/// parameter they are passed to.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum InlayHintsParamNamesEnabled {
+  None,
+  Literals,
+  All,
+}
+
+impl Default for InlayHintsParamNamesEnabled {
+  fn default() -> Self {
+    Self::None
+  }
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct InlayHintsSettings {
+  /// Which arguments should be labelled with their parameter name.
+  #[serde(default)]
+  pub parameter_names: InlayHintsParamNamesEnabled,
+  /// Flag for labelling arguments whose text is the same as the parameter
+  /// name.  For this to have an impact, `parameter_names` needs to be enabled.
+  #[serde(default)]
+  pub parameter_names_when_argument_matches_name: bool,
+  /// Flag for providing the inferred types of implicitly typed parameters.
+  #[serde(default)]
+  pub parameter_types: bool,
+  /// Flag for providing the inferred types of implicitly typed variables.
+  #[serde(default)]
+  pub variable_types: bool,
+  /// Flag for providing the inferred types of implicitly typed class
+  /// properties.
+  #[serde(default)]
+  pub property_declaration_types: bool,
+  /// Flag for providing the inferred return types of functions and methods.
+  #[serde(default)]
+  pub function_like_return_types: bool,
+  /// Flag for providing the values of enum members.
+  #[serde(default)]
+  pub enum_member_values: bool,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TestingSettings {
+  /// The arguments of `deno test`, such as `--allow-read`, which tests that
+  /// are run from the client are run with.  Without any, tests are run
+  /// without any permissions.
+  #[serde(default)]
+  pub args: Vec<String>,
+}
+
 /// Deno language server specific settings that can be applied uniquely to a
 /// specifier.
 #[derive(Debug, Default, Clone, Deserialize)]
@@ -164,6 +219,10 @@ pub struct WorkspaceSettings {
   #[serde(default)]
   pub code_lens: CodeLensSettings,
 
+  /// Inlay hint specific settings for the workspace.
+  #[serde(default)]
+  pub inlay_hints: InlayHintsSettings,
+
   /// A flag that indicates if internal debug logging should be made available.
   #[serde(default)]
   pub internal_debug: bool,
@@ -177,8 +236,17 @@ pub struct WorkspaceSettings {
   #[serde(default)]
   pub suggest: CompletionSettings,
 
+  /// Settings for tests which are run from the client.
+  #[serde(default)]
+  pub testing: TestingSettings,
+
   #[serde(default)]
   pub unstable: bool,
+
+  /// A flag that indicates if modules in the workspace which aren't open in
+  /// the client should be diagnosed in the background as well.
+  #[serde(default)]
+  pub workspace_diagnostics: bool,
 }
 
 impl WorkspaceSettings {
@@ -187,6 +255,18 @@ impl WorkspaceSettings {
   pub fn enabled_code_lens(&self) -> bool {
     self.code_lens.implementations || self.code_lens.references
   }
+
+  /// Determine if any inlay hints are enabled at all.  This allows short
+  /// circuiting when there are no inlay hints enabled.
+  pub fn enabled_inlay_hints(&self) -> bool {
+    let hints = &self.inlay_hints;
+    hints.parameter_names != InlayHintsParamNamesEnabled::None
+      || hints.parameter_types
+      || hints.variable_types
+      || hints.property_declaration_types
+      || hints.function_like_return_types
+      || hints.enum_member_values
+  }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -377,7 +457,9 @@ impl Config {
       self.client_capabilities.status_notification = experimental
         .get("statusNotification")
         .and_then(|it| it.as_bool())
-        == Some(true)
+        == Some(true);
+      self.client_capabilities.testing_api =
+        experimental.get("testingApi").and_then(|it| it.as_bool()) == Some(true)
     }
 
     if let Some(workspace) = &capabilities.workspace {
@@ -493,6 +575,15 @@ mod tests {
           references_all_functions: false,
           test: true,
         },
+        inlay_hints: InlayHintsSettings {
+          parameter_names: InlayHintsParamNamesEnabled::None,
+          parameter_names_when_argument_matches_name: false,
+          parameter_types: false,
+          variable_types: false,
+          property_declaration_types: false,
+          function_like_return_types: false,
+          enum_member_values: false,
+        },
         internal_debug: false,
         lint: false,
         suggest: CompletionSettings {
@@ -505,8 +596,50 @@ mod tests {
             hosts: HashMap::new(),
           }
         },
+        testing: TestingSettings { args: vec![] },
         unstable: false,
+        workspace_diagnostics: false,
       }
     );
   }
+
+  #[test]
+  fn test_set_workspace_settings_inlay_hints() {
+    let config = setup();
+    let settings = config.get_workspace_settings();
+    assert!(!settings.enabled_inlay_hints());
+    config
+      .set_workspace_settings(json!({
+        "inlayHints": {
+          "parameterNames": "literals",
+          "variableTypes": true
+        }
+      }))
+      .expect("could not update");
+    let settings = config.get_workspace_settings();
+    assert!(settings.enabled_inlay_hints());
+    assert_eq!(
+      settings.inlay_hints.parameter_names,
+      InlayHintsParamNamesEnabled::Literals
+    );
+    assert!(settings.inlay_hints.variable_types);
+    assert!(!settings.inlay_hints.parameter_types);
+  }
+
+  #[test]
+  fn test_set_workspace_settings_testing_args() {
+    let config = setup();
+    config
+      .set_workspace_settings(json!({
+        "testing": {
+          "args": ["--allow-read", "--allow-env=HOME"]
+        }
+      }))
+      .expect("could not update");
+    let settings = config.get_workspace_settings();
+    assert_eq!(
+      settings.testing.args,
+      vec!["--allow-read".to_string(), "--allow-env=HOME".to_string()]
+    );
+  }
 }
@@ -0,0 +1,1304 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+//! Diagnostics, completions and hovers for Deno configuration files and import
+//! maps, which the client edits as plain JSON documents.
+//!
+//! The documents are scanned with a tolerant JSONC parser which keeps the
+//! ranges of keys and values, as the text is frequently incomplete while it is
+//! being edited. What is valid in a configuration file is described by the
+//! JSON schema in `cli/schemas`.
+
+use super::completions;
+use super::documents::Documents;
+use super::registries::ModuleRegistry;
+
+use crate::config_file::ConfigFile;
+use crate::fs_util::specifier_to_file_path;
+
+use deno_core::serde_json;
+use deno_core::serde_json::Value;
+use deno_core::url::Url;
+use deno_core::ModuleSpecifier;
+use lspower::lsp;
+use std::ops::Range;
+
+lazy_static::lazy_static! {
+  static ref CONFIG_FILE_SCHEMA: Value =
+    serde_json::from_str(include_str!("../schemas/config-file.v1.json"))
+      .unwrap();
+}
+
+const IMPORT_MAP_KEYS: &[(&str, &str)] = &[
+  (
+    "imports",
+    "Maps bare or URL-like specifiers to the URLs of the modules they resolve to. Keys which end in `/` remap every specifier that starts with them.",
+  ),
+  (
+    "scopes",
+    "Mappings which only apply to modules whose URL starts with the scope, taking precedence over `imports`.",
+  ),
+];
+
+/// The kinds of JSON documents the language server provides features for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigDocumentKind {
+  ConfigFile,
+  ImportMap,
+}
+
+/// Determine if the specifier is a configuration file or an import map, either
+/// because it is the one configured for the workspace or because it uses the
+/// conventional file name.
+pub fn get_config_document_kind(
+  specifier: &ModuleSpecifier,
+  maybe_config_uri: Option<&Url>,
+  maybe_import_map_uri: Option<&Url>,
+) -> Option<ConfigDocumentKind> {
+  if maybe_config_uri == Some(specifier) {
+    return Some(ConfigDocumentKind::ConfigFile);
+  }
+  if maybe_import_map_uri == Some(specifier) {
+    return Some(ConfigDocumentKind::ImportMap);
+  }
+  match specifier.path_segments()?.last()? {
+    "deno.json" | "deno.jsonc" => Some(ConfigDocumentKind::ConfigFile),
+    "import_map.json" => Some(ConfigDocumentKind::ImportMap),
+    _ => None,
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+  OpenBrace,
+  CloseBrace,
+  OpenBracket,
+  CloseBracket,
+  Colon,
+  Comma,
+  String(String, bool),
+  Word(String),
+}
+
+/// Split JSONC text into tokens, skipping whitespace and comments. A string
+/// which is not terminated ends at the end of its line.
+fn tokenize(text: &str) -> Vec<(Token, Range<usize>)> {
+  let bytes = text.as_bytes();
+  let mut tokens = Vec::new();
+  let mut i = 0;
+  while i < bytes.len() {
+    let start = i;
+    let token = match bytes[i] {
+      b'{' => Token::OpenBrace,
+      b'}' => Token::CloseBrace,
+      b'[' => Token::OpenBracket,
+      b']' => Token::CloseBracket,
+      b':' => Token::Colon,
+      b',' => Token::Comma,
+      b'/' if bytes.get(i + 1) == Some(&b'/') => {
+        i = text[i..].find('\n').map_or(bytes.len(), |end| i + end);
+        continue;
+      }
+      b'/' if bytes.get(i + 1) == Some(&b'*') => {
+        i = text[i + 2..]
+          .find("*/")
+          .map_or(bytes.len(), |end| i + 2 + end + 2);
+        continue;
+      }
+      b if b.is_ascii_whitespace() => {
+        i += 1;
+        continue;
+      }
+      quote @ (b'"' | b'\'') => {
+        let mut value = String::new();
+        let mut closed = false;
+        let mut chars = text[i + 1..].char_indices();
+        i = bytes.len();
+        while let Some((index, c)) = chars.next() {
+          match c {
+            '\n' => {
+              i = start + 1 + index;
+              break;
+            }
+            '\\' => match chars.next() {
+              Some((_, 'n')) => value.push('\n'),
+              Some((_, 't')) => value.push('\t'),
+              Some((_, 'r')) => value.push('\r'),
+              Some((_, 'b')) => value.push('\u{8}'),
+              Some((_, 'f')) => value.push('\u{c}'),
+              Some((index, 'u')) => {
+                let hex_start = start + 1 + index + 1;
+                if let Some(c) = text
+                  .get(hex_start..hex_start + 4)
+                  .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                  .and_then(char::from_u32)
+                {
+                  value.push(c);
+                  chars.nth(3);
+                }
+              }
+              Some((_, c)) => value.push(c),
+              None => (),
+            },
+            c if c as u32 == quote as u32 => {
+              closed = true;
+              i = start + 1 + index + 1;
+              break;
+            }
+            c => value.push(c),
+          }
+        }
+        tokens.push((Token::String(value, closed), start..i));
+        continue;
+      }
+      _ => {
+        i += 1;
+        while i < bytes.len()
+          && !bytes[i].is_ascii_whitespace()
+          && !b"{}[]:,\"'/".contains(&bytes[i])
+        {
+          i += 1;
+        }
+        tokens.push((Token::Word(text[start..i].to_string()), start..i));
+        continue;
+      }
+    };
+    i += 1;
+    tokens.push((token, start..i));
+  }
+  tokens
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum JsonValue {
+  Object(Vec<JsonProperty>),
+  Array(Vec<JsonNode>),
+  String(String),
+  Other,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct JsonNode {
+  value: JsonValue,
+  /// The byte range of the value in the text, including any quotes or
+  /// brackets.
+  range: Range<usize>,
+  /// If the string, object or array is terminated.
+  closed: bool,
+}
+
+impl JsonNode {
+  fn as_str(&self) -> Option<&str> {
+    match &self.value {
+      JsonValue::String(value) => Some(value),
+      _ => None,
+    }
+  }
+
+  fn contains(&self, offset: usize) -> bool {
+    self.range.start < offset
+      && (offset < self.range.end || !self.closed && offset == self.range.end)
+  }
+
+  fn get(&self, key: &str) -> Option<&JsonProperty> {
+    match &self.value {
+      JsonValue::Object(properties) => {
+        properties.iter().find(|p| p.key.as_str() == Some(key))
+      }
+      _ => None,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct JsonProperty {
+  /// The key of the property, which is always a string.
+  key: JsonNode,
+  value: Option<JsonNode>,
+}
+
+/// A recursive descent parser over the tokens of a JSONC document, which
+/// recovers from unexpected or missing tokens instead of failing.
+struct Parser<'a> {
+  tokens: &'a [(Token, Range<usize>)],
+  index: usize,
+  text_len: usize,
+}
+
+impl Parser<'_> {
+  fn parse_value(&mut self) -> Option<JsonNode> {
+    let tokens = self.tokens;
+    let (token, range) = tokens.get(self.index)?.clone();
+    let (value, closed) = match token {
+      Token::OpenBrace => {
+        self.index += 1;
+        return Some(self.parse_object(range.start));
+      }
+      Token::OpenBracket => {
+        self.index += 1;
+        return Some(self.parse_array(range.start));
+      }
+      Token::String(value, closed) => (JsonValue::String(value), closed),
+      Token::Word(_) => (JsonValue::Other, true),
+      _ => return None,
+    };
+    self.index += 1;
+    Some(JsonNode {
+      value,
+      range,
+      closed,
+    })
+  }
+
+  fn parse_object(&mut self, start: usize) -> JsonNode {
+    let mut properties = Vec::new();
+    loop {
+      let (token, range) = match self.tokens.get(self.index).cloned() {
+        Some(token) => token,
+        None => {
+          return JsonNode {
+            value: JsonValue::Object(properties),
+            range: start..self.text_len,
+            closed: false,
+          }
+        }
+      };
+      match token {
+        Token::CloseBrace => {
+          self.index += 1;
+          return JsonNode {
+            value: JsonValue::Object(properties),
+            range: start..range.end,
+            closed: true,
+          };
+        }
+        Token::String(_, _) | Token::Word(_) => {
+          self.index += 1;
+          let (key, closed) = match token {
+            Token::String(key, closed) => (key, closed),
+            Token::Word(key) => (key, true),
+            _ => unreachable!(),
+          };
+          let key = JsonNode {
+            value: JsonValue::String(key),
+            range,
+            closed,
+          };
+          let value =
+            if matches!(self.tokens.get(self.index), Some((Token::Colon, _))) {
+              self.index += 1;
+              self.parse_value()
+            } else {
+              None
+            };
+          properties.push(JsonProperty { key, value });
+        }
+        Token::OpenBrace | Token::OpenBracket => {
+          self.parse_value();
+        }
+        _ => self.index += 1,
+      }
+    }
+  }
+
+  fn parse_array(&mut self, start: usize) -> JsonNode {
+    let tokens = self.tokens;
+    let mut items = Vec::new();
+    loop {
+      let (token, range) = match tokens.get(self.index) {
+        Some(token) => token,
+        None => {
+          return JsonNode {
+            value: JsonValue::Array(items),
+            range: start..self.text_len,
+            closed: false,
+          }
+        }
+      };
+      match token {
+        Token::CloseBracket => {
+          let end = range.end;
+          self.index += 1;
+          return JsonNode {
+            value: JsonValue::Array(items),
+            range: start..end,
+            closed: true,
+          };
+        }
+        // a mismatched brace closes the array and is left for the object
+        Token::CloseBrace => {
+          let end = range.start;
+          return JsonNode {
+            value: JsonValue::Array(items),
+            range: start..end,
+            closed: false,
+          };
+        }
+        Token::Colon | Token::Comma => self.index += 1,
+        _ => items.extend(self.parse_value()),
+      }
+    }
+  }
+}
+
+fn parse(text: &str) -> Option<JsonNode> {
+  let tokens = tokenize(text);
+  let mut parser = Parser {
+    tokens: &tokens,
+    index: 0,
+    text_len: text.len(),
+  };
+  parser.parse_value()
+}
+
+fn to_position(text: &str, offset: usize) -> lsp::Position {
+  let before = &text[..offset];
+  let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+  lsp::Position {
+    line: before.matches('\n').count() as u32,
+    character: before[line_start..].encode_utf16().count() as u32,
+  }
+}
+
+fn to_lsp_range(text: &str, range: &Range<usize>) -> lsp::Range {
+  lsp::Range {
+    start: to_position(text, range.start),
+    end: to_position(text, range.end),
+  }
+}
+
+/// The range of the contents of a string, excluding its quotes.
+fn to_contents_range(node: &JsonNode) -> Range<usize> {
+  let end = if node.closed {
+    node.range.end - 1
+  } else {
+    node.range.end
+  };
+  (node.range.start + 1).min(end)..end
+}
+
+/// Where an offset within a document is located, as far as completions are
+/// concerned.
+#[derive(Debug, PartialEq)]
+enum JsonLocation<'a> {
+  /// The offset is where the key of a property of the object at the path is,
+  /// or could be.
+  Key {
+    path: Vec<&'a str>,
+    properties: &'a [JsonProperty],
+    maybe_key: Option<&'a JsonNode>,
+  },
+  /// The offset is within the string value of the property at the path.
+  Value {
+    path: Vec<&'a str>,
+    value: &'a JsonNode,
+  },
+}
+
+fn get_location<'a>(
+  text: &str,
+  node: &'a JsonNode,
+  offset: usize,
+  mut path: Vec<&'a str>,
+) -> Option<JsonLocation<'a>> {
+  if !node.contains(offset) {
+    return None;
+  }
+  let properties = match &node.value {
+    JsonValue::Object(properties) => properties,
+    _ => return None,
+  };
+  for property in properties {
+    if property.key.contains(offset) {
+      return Some(JsonLocation::Key {
+        path,
+        properties,
+        maybe_key: Some(&property.key),
+      });
+    }
+    if let Some(value) = &property.value {
+      if value.contains(offset) {
+        path.push(property.key.as_str()?);
+        return if let JsonValue::String(_) = &value.value {
+          Some(JsonLocation::Value { path, value })
+        } else {
+          get_location(text, value, offset, path)
+        };
+      }
+    }
+  }
+  // a property whose value hasn't been written yet
+  if text[..offset].trim_end().ends_with(':') {
+    return None;
+  }
+  Some(JsonLocation::Key {
+    path,
+    properties,
+    maybe_key: None,
+  })
+}
+
+/// Return the schema of the property at the path of a configuration file.
+fn get_schema(path: &[&str]) -> Option<&'static Value> {
+  path.iter().try_fold(&*CONFIG_FILE_SCHEMA, |schema, key| {
+    schema.get("properties")?.get(key)
+  })
+}
+
+fn get_schema_description(schema: &Value) -> Option<&str> {
+  schema
+    .get("markdownDescription")
+    .or_else(|| schema.get("description"))?
+    .as_str()
+}
+
+fn diagnostic(
+  text: &str,
+  range: &Range<usize>,
+  severity: lsp::DiagnosticSeverity,
+  code: &str,
+  message: String,
+) -> lsp::Diagnostic {
+  lsp::Diagnostic {
+    range: to_lsp_range(text, range),
+    severity: Some(severity),
+    code: Some(lsp::NumberOrString::String(code.to_string())),
+    source: Some("deno".to_string()),
+    message,
+    ..Default::default()
+  }
+}
+
+fn get_unknown_key_diagnostic(
+  text: &str,
+  key: &JsonNode,
+  path: &[&str],
+) -> lsp::Diagnostic {
+  let name = key.as_str().unwrap_or_default();
+  let message = if path.is_empty() {
+    format!("Unknown key \"{}\".", name)
+  } else {
+    format!("Unknown key \"{}\" in \"{}\".", name, path.join("."))
+  };
+  diagnostic(
+    text,
+    &key.range,
+    lsp::DiagnosticSeverity::WARNING,
+    "unknown-key",
+    message,
+  )
+}
+
+/// Check the keys and values of an object in a configuration file against the
+/// schema. The members of `"compilerOptions"` aren't checked, as the schema
+/// only describes the most common ones.
+fn check_config_object<'a>(
+  text: &str,
+  node: &'a JsonNode,
+  path: &mut Vec<&'a str>,
+  diagnostics: &mut Vec<lsp::Diagnostic>,
+) {
+  let properties = match &node.value {
+    JsonValue::Object(properties) => properties,
+    _ => return,
+  };
+  let schema = match get_schema(path) {
+    Some(schema) => schema,
+    None => return,
+  };
+  let check_keys = path.as_slice() != ["compilerOptions"]
+    && schema.get("additionalProperties").is_none();
+  for property in properties {
+    let key = match property.key.as_str() {
+      Some(key) => key,
+      None => continue,
+    };
+    let property_schema =
+      match schema.get("properties").and_then(|p| p.get(key)) {
+        Some(property_schema) => property_schema,
+        None => {
+          // a reference to the schema itself is allowed for editor support
+          if check_keys && !(path.is_empty() && key == "$schema") {
+            diagnostics.push(get_unknown_key_diagnostic(
+              text,
+              &property.key,
+              path,
+            ));
+          }
+          continue;
+        }
+      };
+    let value = match &property.value {
+      Some(value) => value,
+      None => continue,
+    };
+    if let (Some(values), Some(value_str)) = (
+      property_schema.get("enum").and_then(|e| e.as_array()),
+      value.as_str(),
+    ) {
+      if !values.iter().any(|v| v.as_str() == Some(value_str)) {
+        let expected: Vec<String> =
+          values.iter().map(|v| v.to_string()).collect();
+        diagnostics.push(diagnostic(
+          text,
+          &value.range,
+          lsp::DiagnosticSeverity::ERROR,
+          "invalid-value",
+          format!(
+            "Invalid value \"{}\" for \"{}\". Expected one of: {}.",
+            value_str,
+            key,
+            expected.join(", ")
+          ),
+        ));
+      }
+    }
+    path.push(key);
+    check_config_object(text, value, path, diagnostics);
+    path.pop();
+  }
+}
+
+fn get_config_file_diagnostics(
+  specifier: &ModuleSpecifier,
+  text: &str,
+  root: &JsonNode,
+) -> Vec<lsp::Diagnostic> {
+  let mut diagnostics = Vec::new();
+  check_config_object(text, root, &mut Vec::new(), &mut diagnostics);
+  let maybe_ignored_options = ConfigFile::new(text, specifier)
+    .and_then(|config_file| config_file.to_compiler_options());
+  match maybe_ignored_options {
+    Ok((_, Some(ignored_options))) => {
+      if let Some(compiler_options) =
+        root.get("compilerOptions").and_then(|p| p.value.as_ref())
+      {
+        for item in &ignored_options.items {
+          if let Some(property) = compiler_options.get(item) {
+            diagnostics.push(diagnostic(
+              text,
+              &property.key.range,
+              lsp::DiagnosticSeverity::WARNING,
+              "ignored-compiler-option",
+              format!(
+                "The compiler option \"{}\" is not supported by Deno and will be ignored.",
+                item
+              ),
+            ));
+          }
+        }
+      }
+    }
+    Ok(_) => (),
+    Err(err) => {
+      let range = root
+        .get("compilerOptions")
+        .map_or(0..0, |property| property.key.range.clone());
+      diagnostics.push(diagnostic(
+        text,
+        &range,
+        lsp::DiagnosticSeverity::ERROR,
+        "invalid-config",
+        format!("{:#}", err),
+      ));
+    }
+  }
+  diagnostics
+}
+
+/// Resolve the target of an import map entry, returning an error message when
+/// it isn't a valid target or doesn't exist locally. Remote targets are only
+/// checked for being valid URLs.
+fn check_import_map_target(
+  key: &str,
+  target: &str,
+  base: &ModuleSpecifier,
+  documents: &Documents,
+) -> Result<ModuleSpecifier, String> {
+  let resolved = if target.starts_with("./")
+    || target.starts_with("../")
+    || target.starts_with('/')
+  {
+    base.join(target).ok()
+  } else {
+    Url::parse(target).ok()
+  }
+  .ok_or_else(|| {
+    format!(
+      "Invalid target \"{}\" for \"{}\". Targets must be URLs or start with \"/\", \"./\" or \"../\".",
+      target, key
+    )
+  })?;
+  if key.ends_with('/') && !target.ends_with('/') {
+    return Err(format!(
+      "The target \"{}\" for \"{}\" must end with \"/\", as the key does.",
+      target, key
+    ));
+  }
+  if resolved.scheme() == "file" {
+    let exists = if key.ends_with('/') {
+      specifier_to_file_path(&resolved).map_or(false, |path| path.is_dir())
+    } else {
+      documents.contains_specifier(&resolved)
+    };
+    if !exists {
+      return Err(format!(
+        "Unable to resolve \"{}\" for \"{}\": \"{}\" does not exist.",
+        target, key, resolved
+      ));
+    }
+  }
+  Ok(resolved)
+}
+
+fn check_import_map_imports(
+  text: &str,
+  node: &JsonNode,
+  specifier: &ModuleSpecifier,
+  documents: &Documents,
+  diagnostics: &mut Vec<lsp::Diagnostic>,
+) {
+  let properties = match &node.value {
+    JsonValue::Object(properties) => properties,
+    _ => return,
+  };
+  for property in properties {
+    let (key, value) = match (property.key.as_str(), &property.value) {
+      (Some(key), Some(value)) => (key, value),
+      _ => continue,
+    };
+    let result = match value.as_str() {
+      Some(target) => {
+        check_import_map_target(key, target, specifier, documents).map(|_| ())
+      }
+      // a `null` target blocks the specifier from being resolved
+      None if &text[value.range.clone()] == "null" => Ok(()),
+      None => Err(format!("The target for \"{}\" must be a string.", key)),
+    };
+    if let Err(message) = result {
+      diagnostics.push(diagnostic(
+        text,
+        &value.range,
+        lsp::DiagnosticSeverity::ERROR,
+        "invalid-import-map-target",
+        message,
+      ));
+    }
+  }
+}
+
+fn get_import_map_diagnostics(
+  specifier: &ModuleSpecifier,
+  text: &str,
+  root: &JsonNode,
+  documents: &Documents,
+) -> Vec<lsp::Diagnostic> {
+  let mut diagnostics = Vec::new();
+  let properties = match &root.value {
+    JsonValue::Object(properties) => properties,
+    _ => return diagnostics,
+  };
+  for property in properties {
+    let value = property.value.as_ref();
+    match property.key.as_str() {
+      Some("imports") => {
+        if let Some(imports) = value {
+          check_import_map_imports(
+            text,
+            imports,
+            specifier,
+            documents,
+            &mut diagnostics,
+          );
+        }
+      }
+      Some("scopes") => {
+        if let Some(JsonValue::Object(scopes)) = value.map(|v| &v.value) {
+          for scope in scopes.iter().filter_map(|p| p.value.as_ref()) {
+            check_import_map_imports(
+              text,
+              scope,
+              specifier,
+              documents,
+              &mut diagnostics,
+            );
+          }
+        }
+      }
+      _ => {
+        diagnostics.push(get_unknown_key_diagnostic(text, &property.key, &[]))
+      }
+    }
+  }
+  diagnostics
+}
+
+/// Return the diagnostics for a configuration file or import map.
+pub fn get_diagnostics(
+  kind: ConfigDocumentKind,
+  specifier: &ModuleSpecifier,
+  text: &str,
+  documents: &Documents,
+) -> Vec<lsp::Diagnostic> {
+  let message = match jsonc_parser::parse_to_serde_value(text) {
+    Ok(Some(value)) if value.is_object() => None,
+    Ok(Some(_)) => Some("The document should be a JSON object.".to_string()),
+    Ok(None) => None,
+    Err(err) => Some(format!("Unable to parse the document: {}", err)),
+  };
+  if let Some(message) = message {
+    return vec![diagnostic(
+      text,
+      &(0..0),
+      lsp::DiagnosticSeverity::ERROR,
+      "invalid-json",
+      message,
+    )];
+  }
+  let root = match parse(text) {
+    Some(root) => root,
+    None => return Vec::new(),
+  };
+  match kind {
+    ConfigDocumentKind::ConfigFile => {
+      get_config_file_diagnostics(specifier, text, &root)
+    }
+    ConfigDocumentKind::ImportMap => {
+      get_import_map_diagnostics(specifier, text, &root, documents)
+    }
+  }
+}
+
+/// Find the key which contains the offset, along with the path to the object
+/// it is a member of.
+fn get_key_at<'a>(
+  node: &'a JsonNode,
+  offset: usize,
+  path: &mut Vec<&'a str>,
+) -> Option<&'a JsonNode> {
+  if let JsonValue::Object(properties) = &node.value {
+    for property in properties {
+      if property.key.range.contains(&offset) {
+        return Some(&property.key);
+      }
+      if let Some(value) = &property.value {
+        if value.range.contains(&offset) {
+          path.push(property.key.as_str()?);
+          return get_key_at(value, offset, path);
+        }
+      }
+    }
+  }
+  None
+}
+
+/// Describe the key under the offset in a configuration file or import map.
+/// Configuration file keys are described by the schema, while the keys of
+/// import map entries show what they resolve to.
+pub fn get_hover(
+  kind: ConfigDocumentKind,
+  specifier: &ModuleSpecifier,
+  text: &str,
+  offset: usize,
+) -> Option<lsp::Hover> {
+  let root = parse(text)?;
+  let mut path = Vec::new();
+  let key = get_key_at(&root, offset, &mut path)?;
+  let name = key.as_str()?;
+  let value = match kind {
+    ConfigDocumentKind::ConfigFile => {
+      path.push(name);
+      let description = get_schema_description(get_schema(&path)?)?;
+      format!("**{}**\n\n{}", path.join("."), description)
+    }
+    ConfigDocumentKind::ImportMap => match path.as_slice() {
+      [] => {
+        let (_, description) =
+          IMPORT_MAP_KEYS.iter().find(|(key, _)| *key == name)?;
+        format!("**{}**\n\n{}", name, description)
+      }
+      ["imports"] | ["scopes", _] => {
+        let imports = if let ["scopes", scope] = path.as_slice() {
+          root.get("scopes")?.value.as_ref()?.get(scope)?
+        } else {
+          root.get("imports")?
+        };
+        let target = imports.value.as_ref()?.get(name)?.value.as_ref()?;
+        let target = target.as_str()?;
+        let resolved = if target.starts_with("./")
+          || target.starts_with("../")
+          || target.starts_with('/')
+        {
+          specifier.join(target).ok()?
+        } else {
+          Url::parse(target).ok()?
+        };
+        format!("**Resolved Import Map Entry**\n\n{} → {}\n", name, resolved)
+      }
+      ["scopes"] => format!(
+        "**Scope**\n\nApplies to modules whose URL starts with: {}\n",
+        specifier.join(name).ok()?
+      ),
+      _ => return None,
+    },
+  };
+  Some(lsp::Hover {
+    contents: lsp::HoverContents::Markup(lsp::MarkupContent {
+      kind: lsp::MarkupKind::Markdown,
+      value,
+    }),
+    range: Some(to_lsp_range(text, &key.range)),
+  })
+}
+
+fn get_key_completions<'a>(
+  text: &str,
+  keys: impl Iterator<Item = (&'a str, Option<&'a str>)>,
+  properties: &[JsonProperty],
+  maybe_key: Option<&JsonNode>,
+) -> Vec<lsp::CompletionItem> {
+  keys
+    .filter(|(key, _)| {
+      !properties
+        .iter()
+        .any(|p| p.key.as_str() == Some(*key) && Some(&p.key) != maybe_key)
+    })
+    .map(|(key, maybe_description)| {
+      let new_text = format!("\"{}\"", key);
+      let (insert_text, text_edit) = if let Some(node) = maybe_key {
+        (
+          None,
+          Some(lsp::CompletionTextEdit::Edit(lsp::TextEdit {
+            range: to_lsp_range(text, &node.range),
+            new_text,
+          })),
+        )
+      } else {
+        (Some(new_text), None)
+      };
+      lsp::CompletionItem {
+        label: key.to_string(),
+        kind: Some(lsp::CompletionItemKind::PROPERTY),
+        documentation: maybe_description.map(|value| {
+          lsp::Documentation::MarkupContent(lsp::MarkupContent {
+            kind: lsp::MarkupKind::Markdown,
+            value: value.to_string(),
+          })
+        }),
+        filter_text: Some(format!("\"{}\"", key)),
+        insert_text,
+        text_edit,
+        ..Default::default()
+      }
+    })
+    .collect()
+}
+
+/// Complete the target of an import map entry, using the same sources as the
+/// completions of import specifiers in modules.
+async fn get_target_completions(
+  specifier: &ModuleSpecifier,
+  text: &str,
+  value: &JsonNode,
+  offset: usize,
+  module_registries: &ModuleRegistry,
+  documents: &Documents,
+) -> Vec<lsp::CompletionItem> {
+  let contents_range = to_contents_range(value);
+  let current = &text[contents_range.clone()];
+  let range = to_lsp_range(text, &contents_range);
+  if current.starts_with("./") || current.starts_with("../") {
+    completions::get_local_completions(specifier, current, &range)
+      .unwrap_or_default()
+  } else if !current.is_empty() {
+    let offset = offset.saturating_sub(contents_range.start);
+    module_registries
+      .get_completions(current, offset, &range, |specifier| {
+        documents.contains_specifier(specifier)
+      })
+      .await
+      .unwrap_or_default()
+  } else {
+    let mut items: Vec<lsp::CompletionItem> = completions::LOCAL_PATHS
+      .iter()
+      .map(|s| lsp::CompletionItem {
+        label: s.to_string(),
+        kind: Some(lsp::CompletionItemKind::FOLDER),
+        detail: Some("(local)".to_string()),
+        sort_text: Some("1".to_string()),
+        insert_text: Some(s.to_string()),
+        ..Default::default()
+      })
+      .collect();
+    if let Some(origin_items) =
+      module_registries.get_origin_completions(current, &range)
+    {
+      items.extend(origin_items);
+    }
+    items
+  }
+}
+
+/// Return the completions at the offset of a configuration file or import
+/// map. Keys of configuration files and the values of their enumerated options
+/// come from the schema, while import map targets are completed from local
+/// files and the module registries.
+pub async fn get_completions(
+  kind: ConfigDocumentKind,
+  specifier: &ModuleSpecifier,
+  text: &str,
+  offset: usize,
+  module_registries: &ModuleRegistry,
+  documents: &Documents,
+) -> Option<lsp::CompletionResponse> {
+  let root = parse(text)?;
+  let location = get_location(text, &root, offset, Vec::new())?;
+  let items = match (kind, location) {
+    (
+      ConfigDocumentKind::ConfigFile,
+      JsonLocation::Key {
+        path,
+        properties,
+        maybe_key,
+      },
+    ) => {
+      let schema_properties = get_schema(&path)?.get("properties")?;
+      let keys = schema_properties
+        .as_object()?
+        .iter()
+        .map(|(key, schema)| (key.as_str(), get_schema_description(schema)));
+      get_key_completions(text, keys, properties, maybe_key)
+    }
+    (ConfigDocumentKind::ConfigFile, JsonLocation::Value { path, value }) => {
+      let range = to_lsp_range(text, &value.range);
+      get_schema(&path)?
+        .get("enum")?
+        .as_array()?
+        .iter()
+        .filter_map(|v| v.as_str())
+        .map(|v| lsp::CompletionItem {
+          label: v.to_string(),
+          kind: Some(lsp::CompletionItemKind::ENUM_MEMBER),
+          filter_text: Some(format!("\"{}\"", v)),
+          text_edit: Some(lsp::CompletionTextEdit::Edit(lsp::TextEdit {
+            range,
+            new_text: format!("\"{}\"", v),
+          })),
+          ..Default::default()
+        })
+        .collect()
+    }
+    (
+      ConfigDocumentKind::ImportMap,
+      JsonLocation::Key {
+        path,
+        properties,
+        maybe_key,
+      },
+    ) if path.is_empty() => {
+      let keys = IMPORT_MAP_KEYS
+        .iter()
+        .map(|(key, description)| (*key, Some(*description)));
+      get_key_completions(text, keys, properties, maybe_key)
+    }
+    (ConfigDocumentKind::ImportMap, JsonLocation::Value { path, value })
+      if matches!(path.as_slice(), ["imports", _] | ["scopes", _, _]) =>
+    {
+      get_target_completions(
+        specifier,
+        text,
+        value,
+        offset,
+        module_registries,
+        documents,
+      )
+      .await
+    }
+    _ => return None,
+  };
+  Some(lsp::CompletionResponse::List(lsp::CompletionList {
+    is_incomplete: false,
+    items,
+  }))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use deno_core::resolve_url;
+  use tempfile::TempDir;
+
+  fn get_key_location(
+    text: &str,
+    offset: usize,
+  ) -> Option<(Vec<String>, Option<String>)> {
+    let root = parse(text)?;
+    match get_location(text, &root, offset, Vec::new())? {
+      JsonLocation::Key {
+        path, maybe_key, ..
+      } => Some((
+        path.iter().map(|s| s.to_string()).collect(),
+        maybe_key.and_then(|k| k.as_str()).map(|s| s.to_string()),
+      )),
+      JsonLocation::Value { .. } => None,
+    }
+  }
+
+  #[test]
+  fn test_get_config_document_kind() {
+    let config_uri = resolve_url("file:///a/config.json").unwrap();
+    let fixtures = [
+      (
+        "file:///a/config.json",
+        Some(ConfigDocumentKind::ConfigFile),
+      ),
+      (
+        "file:///a/b/deno.jsonc",
+        Some(ConfigDocumentKind::ConfigFile),
+      ),
+      (
+        "file:///a/import_map.json",
+        Some(ConfigDocumentKind::ImportMap),
+      ),
+      ("file:///a/file.json", None),
+      ("file:///a/deno.ts", None),
+    ];
+    for (specifier, expected) in fixtures {
+      let specifier = resolve_url(specifier).unwrap();
+      assert_eq!(
+        get_config_document_kind(&specifier, Some(&config_uri), None),
+        expected
+      );
+    }
+  }
+
+  #[test]
+  fn test_parse() {
+    let text = r#"{
+  // a comment
+  "a": { "b": [1, "c"], /* another */ d: 'e\'f' },
+  "g": "hi"
+}"#;
+    let root = parse(text).unwrap();
+    assert!(root.closed);
+    assert_eq!(root.range, 0..text.len());
+    let a = root.get("a").unwrap();
+    assert_eq!(&text[a.key.range.clone()], "\"a\"");
+    let a_value = a.value.as_ref().unwrap();
+    assert!(matches!(
+      &a_value.get("b").unwrap().value.as_ref().unwrap().value,
+      JsonValue::Array(items) if items.len() == 2
+    ));
+    let d = a_value.get("d").unwrap().value.as_ref().unwrap();
+    assert_eq!(d.as_str(), Some("e'f"));
+    let g = root.get("g").unwrap().value.as_ref().unwrap();
+    assert_eq!(g.as_str(), Some("hi"));
+
+    // incomplete documents are still parsed as far as possible
+    let text = "{\n  \"a\": {\n    \"b\n";
+    let root = parse(text).unwrap();
+    assert!(!root.closed);
+    let a_value = root.get("a").unwrap().value.as_ref().unwrap();
+    let b = &a_value.get("b").unwrap().key;
+    assert!(!b.closed);
+    assert_eq!(&text[b.range.clone()], "\"b");
+  }
+
+  #[test]
+  fn test_get_location() {
+    let text = "{\n  \"lint\": {\n    \"fi\n  },\n  \"fmt\": \n}";
+    assert_eq!(get_key_location(text, 1), Some((Vec::new(), None)));
+    assert_eq!(
+      get_key_location(text, 20),
+      Some((vec!["lint".to_string()], Some("fi".to_string())))
+    );
+    let offset = text.find("\"fmt\": ").unwrap() + 7;
+    assert_eq!(get_key_location(text, offset), None);
+
+    let text = r#"{ "imports": { "std/": "https://deno.land/std" } }"#;
+    let root = parse(text).unwrap();
+    let offset = text.find("https").unwrap();
+    match get_location(text, &root, offset, Vec::new()) {
+      Some(JsonLocation::Value { path, value }) => {
+        assert_eq!(path, vec!["imports", "std/"]);
+        assert_eq!(value.as_str(), Some("https://deno.land/std"));
+      }
+      location => panic!("unexpected location: {:?}", location),
+    }
+  }
+
+  #[test]
+  fn test_get_config_file_diagnostics() {
+    let temp_dir = TempDir::new().expect("could not create temp dir");
+    let documents = Documents::new(&temp_dir.path().join("deps"));
+    let specifier = resolve_url("file:///a/deno.json").unwrap();
+    let text = r#"{
+  "compilerOptions": {
+    "strict": true,
+    "target": "es5",
+    "jsx": "reactt"
+  },
+  "fmt": {
+    "options": { "lineWidth": 80, "indent": 2 }
+  },
+  "tasks": {}
+}"#;
+    let diagnostics = get_diagnostics(
+      ConfigDocumentKind::ConfigFile,
+      &specifier,
+      text,
+      &documents,
+    );
+    let actual: Vec<(u32, String)> = diagnostics
+      .iter()
+      .map(|d| {
+        let code = match &d.code {
+          Some(lsp::NumberOrString::String(code)) => code.clone(),
+          _ => String::new(),
+        };
+        (d.range.start.line, code)
+      })
+      .collect();
+    assert_eq!(
+      actual,
+      vec![
+        (4, "invalid-value".to_string()),
+        (7, "unknown-key".to_string()),
+        (9, "unknown-key".to_string()),
+        (3, "ignored-compiler-option".to_string()),
+      ]
+    );
+    assert_eq!(
+      diagnostics[1].message,
+      "Unknown key \"indent\" in \"fmt.options\"."
+    );
+
+    let diagnostics = get_diagnostics(
+      ConfigDocumentKind::ConfigFile,
+      &specifier,
+      "{ \"fmt\": ",
+      &documents,
+    );
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+      diagnostics[0].code,
+      Some(lsp::NumberOrString::String("invalid-json".to_string()))
+    );
+  }
+
+  #[test]
+  fn test_get_import_map_diagnostics() {
+    let temp_dir = TempDir::new().expect("could not create temp dir");
+    std::fs::create_dir(temp_dir.path().join("lib")).unwrap();
+    std::fs::write(temp_dir.path().join("lib/mod.ts"), "").unwrap();
+    let documents = Documents::new(&temp_dir.path().join("deps"));
+    let specifier =
+      ModuleSpecifier::from_file_path(temp_dir.path().join("import_map.json"))
+        .unwrap();
+    let text = r#"{
+  "imports": {
+    "lib": "./lib/mod.ts",
+    "lib/": "./lib/",
+    "missing": "./missing.ts",
+    "std/": "https://deno.land/std",
+    "bare": "lodash"
+  },
+  "scopes": {
+    "https://deno.land/": { "fmt": "./fmt.ts" }
+  },
+  "other": {}
+}"#;
+    let diagnostics = get_diagnostics(
+      ConfigDocumentKind::ImportMap,
+      &specifier,
+      text,
+      &documents,
+    );
+    let lines: Vec<u32> =
+      diagnostics.iter().map(|d| d.range.start.line).collect();
+    assert_eq!(lines, vec![4, 5, 6, 9, 11]);
+    assert_eq!(
+      diagnostics[1].message,
+      "The target \"https://deno.land/std\" for \"std/\" must end with \"/\", as the key does."
+    );
+    assert_eq!(diagnostics[4].message, "Unknown key \"other\".");
+  }
+
+  #[test]
+  fn test_get_hover() {
+    let specifier = resolve_url("file:///a/deno.json").unwrap();
+    let text = r#"{ "fmt": { "options": { "useTabs": true } } }"#;
+    let offset = text.find("useTabs").unwrap();
+    let hover =
+      get_hover(ConfigDocumentKind::ConfigFile, &specifier, text, offset)
+        .unwrap();
+    match hover.contents {
+      lsp::HoverContents::Markup(content) => {
+        assert!(content.value.starts_with("**fmt.options.useTabs**\n\n"));
+      }
+      _ => unreachable!(),
+    }
+
+    let specifier = resolve_url("file:///a/import_map.json").unwrap();
+    let text = r#"{ "imports": { "lib/": "./src/lib/" } }"#;
+    let offset = text.find("lib/").unwrap();
+    let hover =
+      get_hover(ConfigDocumentKind::ImportMap, &specifier, text, offset)
+        .unwrap();
+    assert_eq!(
+      hover.contents,
+      lsp::HoverContents::Markup(lsp::MarkupContent {
+        kind: lsp::MarkupKind::Markdown,
+        value: "**Resolved Import Map Entry**\n\nlib/ → file:///a/src/lib/\n"
+          .to_string(),
+      })
+    );
+  }
+
+  #[tokio::test]
+  async fn test_get_completions() {
+    let temp_dir = TempDir::new().expect("could not create temp dir");
+    let documents = Documents::new(&temp_dir.path().join("deps"));
+    let module_registries =
+      ModuleRegistry::new(&temp_dir.path().join("registries"));
+    let specifier = resolve_url("file:///a/deno.json").unwrap();
+    let text =
+      "{\n  \"fmt\": {\n    \"\n  },\n  \"lint\": { \"report\": \"\" }\n}";
+
+    let offset = text.find("    \"").unwrap() + 5;
+    let response = get_completions(
+      ConfigDocumentKind::ConfigFile,
+      &specifier,
+      text,
+      offset,
+      &module_registries,
+      &documents,
+    )
+    .await;
+    let labels = match response {
+      Some(lsp::CompletionResponse::List(list)) => {
+        list.items.into_iter().map(|i| i.label).collect::<Vec<_>>()
+      }
+      _ => panic!("expected a completion list"),
+    };
+    assert!(labels.contains(&"options".to_string()));
+    assert!(labels.contains(&"files".to_string()));
+
+    let offset = text.find("\"\" }").unwrap() + 1;
+    let response = get_completions(
+      ConfigDocumentKind::ConfigFile,
+      &specifier,
+      text,
+      offset,
+      &module_registries,
+      &documents,
+    )
+    .await;
+    let labels = match response {
+      Some(lsp::CompletionResponse::List(list)) => {
+        list.items.into_iter().map(|i| i.label).collect::<Vec<_>>()
+      }
+      _ => panic!("expected a completion list"),
+    };
+    assert_eq!(labels, vec!["pretty", "json", "compact", "github", "sarif"]);
+  }
+}
@@ -2,6 +2,7 @@
 
 use super::analysis;
 use super::documents;
+use super::documents::Document;
 use super::documents::Documents;
 use super::language_server;
 use super::tsc;
@@ -28,10 +29,14 @@ use tokio::time::Duration;
 use tokio::time::Instant;
 
 pub type DiagnosticRecord =
-  (ModuleSpecifier, Option<i32>, Vec<lsp::Diagnostic>);
+  (ModuleSpecifier, Option<String>, Vec<lsp::Diagnostic>);
 pub type DiagnosticVec = Vec<DiagnosticRecord>;
 type TsDiagnosticsMap = HashMap<String, Vec<diagnostics::Diagnostic>>;
 
+/// The number of closed workspace modules which are diagnosed at a time, so
+/// that new changes to open documents are picked up between batches.
+const WORKSPACE_DIAGNOSTICS_BATCH_SIZE: usize = 20;
+
 #[derive(Debug, Hash, Clone, PartialEq, Eq)]
 pub(crate) enum DiagnosticSource {
   Deno,
@@ -42,7 +47,7 @@ pub(crate) enum DiagnosticSource {
 #[derive(Debug, Default)]
 struct DiagnosticCollection {
   map: HashMap<(ModuleSpecifier, DiagnosticSource), Vec<lsp::Diagnostic>>,
-  versions: HashMap<ModuleSpecifier, HashMap<DiagnosticSource, i32>>,
+  versions: HashMap<ModuleSpecifier, HashMap<DiagnosticSource, String>>,
   changes: HashSet<ModuleSpecifier>,
 }
 
@@ -63,7 +68,7 @@ impl DiagnosticCollection {
     &self,
     specifier: &ModuleSpecifier,
     source: &DiagnosticSource,
-  ) -> Option<i32> {
+  ) -> Option<String> {
     let source_version = self.versions.get(specifier)?;
     source_version.get(source).cloned()
   }
@@ -175,9 +180,32 @@ impl DiagnosticsServer {
               update_diagnostics(
                 &client,
                 collection.clone(),
-                snapshot,
+                snapshot.clone(),
                 &ts_server
               ).await;
+
+              if snapshot.config.settings.workspace.workspace_diagnostics {
+                // Diagnosing the whole workspace can take a while, so it is
+                // abandoned as soon as another update is requested and then
+                // resumed from the modules which are still out of date.
+                tokio::select! {
+                  maybe_request = rx.recv() => {
+                    match maybe_request {
+                      None => break,
+                      Some(_) => {
+                        dirty = true;
+                        debounce_timer.as_mut().reset(Instant::now() + DELAY);
+                      }
+                    }
+                  }
+                  _ = update_workspace_diagnostics(
+                    &client,
+                    collection.clone(),
+                    snapshot,
+                    &ts_server
+                  ) => {}
+                }
+              }
             }
           }
         }
@@ -303,8 +331,8 @@ fn ts_json_to_diagnostics(
 async fn generate_lint_diagnostics(
   snapshot: &language_server::StateSnapshot,
   collection: Arc<Mutex<DiagnosticCollection>>,
+  documents: Vec<Document>,
 ) -> Result<DiagnosticVec, AnyError> {
-  let documents = snapshot.documents.documents(true, true);
   let workspace_settings = snapshot.config.settings.workspace.clone();
   let maybe_lint_config = snapshot.maybe_lint_config.clone();
 
@@ -312,7 +340,7 @@ async fn generate_lint_diagnostics(
     let mut diagnostics_vec = Vec::new();
     if workspace_settings.lint {
       for document in documents {
-        let version = document.maybe_lsp_version();
+        let version = Some(document.script_version());
         let current_version = collection
           .lock()
           .await
@@ -366,16 +394,15 @@ async fn generate_ts_diagnostics(
   snapshot: Arc<language_server::StateSnapshot>,
   collection: Arc<Mutex<DiagnosticCollection>>,
   ts_server: &tsc::TsServer,
+  documents: Vec<Document>,
 ) -> Result<DiagnosticVec, AnyError> {
   let mut diagnostics_vec = Vec::new();
   let specifiers: Vec<ModuleSpecifier> = {
     let collection = collection.lock().await;
-    snapshot
-      .documents
-      .documents(true, true)
+    documents
       .iter()
       .filter_map(|d| {
-        let version = d.maybe_lsp_version();
+        let version = Some(d.script_version());
         let current_version =
           collection.get_version(d.specifier(), &DiagnosticSource::TypeScript);
         if version != current_version {
@@ -395,8 +422,7 @@ async fn generate_ts_diagnostics(
       let version = snapshot
         .documents
         .get(&specifier)
-        .map(|d| d.maybe_lsp_version())
-        .flatten();
+        .map(|d| d.script_version());
       diagnostics_vec.push((
         specifier,
         version,
@@ -487,15 +513,16 @@ fn diagnose_dependency(
 async fn generate_deps_diagnostics(
   snapshot: Arc<language_server::StateSnapshot>,
   collection: Arc<Mutex<DiagnosticCollection>>,
+  documents: Vec<Document>,
 ) -> Result<DiagnosticVec, AnyError> {
   tokio::task::spawn(async move {
     let mut diagnostics_vec = Vec::new();
 
-    for document in snapshot.documents.documents(true, true) {
+    for document in documents {
       if !snapshot.config.specifier_enabled(document.specifier()) {
         continue;
       }
-      let version = document.maybe_lsp_version();
+      let version = Some(document.script_version());
       let current_version = collection
         .lock()
         .await
@@ -566,8 +593,8 @@ async fn publish_diagnostics(
   }
 }
 
-/// Updates diagnostics for any specifiers that don't have the correct version
-/// generated and publishes the diagnostics to the client.
+/// Updates diagnostics for any open documents that don't have the correct
+/// version generated and publishes the diagnostics to the client.
 async fn update_diagnostics(
   client: &lspower::Client,
   collection: Arc<Mutex<DiagnosticCollection>>,
@@ -575,18 +602,74 @@ async fn update_diagnostics(
   ts_server: &tsc::TsServer,
 ) {
   let mark = snapshot.performance.mark("update_diagnostics", None::<()>);
+  let documents = snapshot.documents.documents(true, true);
+  update_documents_diagnostics(
+    client,
+    collection,
+    snapshot.clone(),
+    ts_server,
+    documents,
+  )
+  .await;
+  snapshot.performance.measure(mark);
+}
 
+/// Updates diagnostics for the modules in the workspace which aren't open in
+/// the client. The modules are diagnosed in batches, and any which are
+/// already up to date are skipped, so an interrupted update can be resumed
+/// cheaply.
+async fn update_workspace_diagnostics(
+  client: &lspower::Client,
+  collection: Arc<Mutex<DiagnosticCollection>>,
+  snapshot: Arc<language_server::StateSnapshot>,
+  ts_server: &tsc::TsServer,
+) {
+  let mark = snapshot
+    .performance
+    .mark("update_workspace_diagnostics", None::<()>);
+  let documents: Vec<Document> = snapshot
+    .workspace_files
+    .iter()
+    .filter_map(|specifier| snapshot.documents.get(specifier))
+    .filter(|d| !d.is_open() && d.is_diagnosable())
+    .collect();
+  for batch in documents.chunks(WORKSPACE_DIAGNOSTICS_BATCH_SIZE) {
+    update_documents_diagnostics(
+      client,
+      collection.clone(),
+      snapshot.clone(),
+      ts_server,
+      batch.to_vec(),
+    )
+    .await;
+  }
+  snapshot.performance.measure(mark);
+}
+
+/// Updates diagnostics for the supplied documents which don't have the
+/// correct version generated and publishes the diagnostics to the client.
+async fn update_documents_diagnostics(
+  client: &lspower::Client,
+  collection: Arc<Mutex<DiagnosticCollection>>,
+  snapshot: Arc<language_server::StateSnapshot>,
+  ts_server: &tsc::TsServer,
+  documents: Vec<Document>,
+) {
   let lint = async {
     let mark = snapshot
       .performance
       .mark("update_diagnostics_lint", None::<()>);
     let collection = collection.clone();
-    let diagnostics = generate_lint_diagnostics(&snapshot, collection.clone())
-      .await
-      .map_err(|err| {
-        error!("Error generating lint diagnostics: {}", err);
-      })
-      .unwrap_or_default();
+    let diagnostics = generate_lint_diagnostics(
+      &snapshot,
+      collection.clone(),
+      documents.clone(),
+    )
+    .await
+    .map_err(|err| {
+      error!("Error generating lint diagnostics: {}", err);
+    })
+    .unwrap_or_default();
 
     let mut collection = collection.lock().await;
     for diagnostic_record in diagnostics {
@@ -601,13 +684,17 @@ async fn update_diagnostics(
       .performance
       .mark("update_diagnostics_ts", None::<()>);
     let collection = collection.clone();
-    let diagnostics =
-      generate_ts_diagnostics(snapshot.clone(), collection.clone(), ts_server)
-        .await
-        .map_err(|err| {
-          error!("Error generating TypeScript diagnostics: {}", err);
-        })
-        .unwrap_or_default();
+    let diagnostics = generate_ts_diagnostics(
+      snapshot.clone(),
+      collection.clone(),
+      ts_server,
+      documents.clone(),
+    )
+    .await
+    .map_err(|err| {
+      error!("Error generating TypeScript diagnostics: {}", err);
+    })
+    .unwrap_or_default();
     let mut collection = collection.lock().await;
     for diagnostic_record in diagnostics {
       collection.set(DiagnosticSource::TypeScript, diagnostic_record);
@@ -621,13 +708,16 @@ async fn update_diagnostics(
       .performance
       .mark("update_diagnostics_deps", None::<()>);
     let collection = collection.clone();
-    let diagnostics =
-      generate_deps_diagnostics(snapshot.clone(), collection.clone())
-        .await
-        .map_err(|err| {
-          error!("Error generating Deno diagnostics: {}", err);
-        })
-        .unwrap_or_default();
+    let diagnostics = generate_deps_diagnostics(
+      snapshot.clone(),
+      collection.clone(),
+      documents.clone(),
+    )
+    .await
+    .map_err(|err| {
+      error!("Error generating Deno diagnostics: {}", err);
+    })
+    .unwrap_or_default();
     let mut collection = collection.lock().await;
     for diagnostic_record in diagnostics {
       collection.set(DiagnosticSource::Deno, diagnostic_record);
@@ -637,7 +727,6 @@ async fn update_diagnostics(
   };
 
   tokio::join!(lint, ts, deps);
-  snapshot.performance.measure(mark);
 }
 
 #[cfg(test)]
@@ -707,7 +796,9 @@ console.log(a);
       1,
       LanguageId::TypeScript,
     )]);
-    let result = generate_lint_diagnostics(&snapshot, collection).await;
+    let documents = snapshot.documents.documents(true, true);
+    let result =
+      generate_lint_diagnostics(&snapshot, collection, documents).await;
     assert!(result.is_ok());
     let diagnostics = result.unwrap();
     assert_eq!(diagnostics.len(), 1);
@@ -15,7 +15,9 @@ use crate::resolver::ImportMapResolver;
 use crate::resolver::JsxResolver;
 use crate::text_encoding;
 
+use deno_ast::swc::common::Span;
 use deno_ast::MediaType;
+use deno_ast::ParsedSource;
 use deno_ast::SourceTextInfo;
 use deno_core::error::custom_error;
 use deno_core::error::AnyError;
@@ -579,6 +581,24 @@ pub(crate) fn to_lsp_range(range: &deno_graph::Range) -> lsp::Range {
   }
 }
 
+pub(crate) fn span_to_range(
+  span: &Span,
+  parsed_source: &ParsedSource,
+) -> lsp::Range {
+  let start = parsed_source.source().line_and_column_index(span.lo);
+  let end = parsed_source.source().line_and_column_index(span.hi);
+  lsp::Range {
+    start: lsp::Position {
+      line: start.line_index as u32,
+      character: start.column_index as u32,
+    },
+    end: lsp::Position {
+      line: end.line_index as u32,
+      character: end.column_index as u32,
+    },
+  }
+}
+
 fn to_deno_graph_range(
   specifier: &ModuleSpecifier,
   maybe_range: Option<&lsp::Range>,
@@ -883,19 +903,27 @@ impl Documents {
     specifier: &str,
     referrer: &ModuleSpecifier,
   ) -> bool {
-    let maybe_resolver = self.get_maybe_resolver();
-    let maybe_specifier = if let Some(resolver) = maybe_resolver {
-      resolver.resolve(specifier, referrer).ok()
-    } else {
-      deno_core::resolve_import(specifier, referrer.as_str()).ok()
-    };
-    if let Some(import_specifier) = maybe_specifier {
+    if let Some(import_specifier) = self.resolve_import(specifier, referrer) {
       self.contains_specifier(&import_specifier)
     } else {
       false
     }
   }
 
+  /// Resolve an import specifier for the referrer, using the configured
+  /// import map when there is one.
+  pub fn resolve_import(
+    &self,
+    specifier: &str,
+    referrer: &ModuleSpecifier,
+  ) -> Option<ModuleSpecifier> {
+    if let Some(resolver) = self.get_maybe_resolver() {
+      resolver.resolve(specifier, referrer).ok()
+    } else {
+      deno_core::resolve_import(specifier, referrer.as_str()).ok()
+    }
+  }
+
   /// Return `true` if the specifier can be resolved to a document.
   pub fn contains_specifier(&self, specifier: &ModuleSpecifier) -> bool {
     self.get(specifier).is_some()
@@ -0,0 +1,317 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+//! Updates the import specifiers of modules when files or directories in the
+//! workspace are renamed or moved by the client.
+
+use super::analysis;
+use super::completions;
+use super::documents::to_lsp_range;
+use super::documents::Document;
+
+use deno_core::resolve_url;
+use deno_core::ModuleSpecifier;
+use import_map::ImportMap;
+use lspower::lsp;
+use std::collections::HashSet;
+
+/// A module or a directory of modules which is being renamed, where the first
+/// element is the current specifier and the second element is the new one.
+pub type FileRename = (ModuleSpecifier, ModuleSpecifier);
+
+/// Return the specifier a module will have after the renames are applied, or
+/// `None` if the module is not affected. A rename of a directory applies to
+/// every module within it.
+pub fn get_renamed_specifier(
+  specifier: &ModuleSpecifier,
+  renames: &[FileRename],
+) -> Option<ModuleSpecifier> {
+  for (old_specifier, new_specifier) in renames {
+    if specifier == old_specifier {
+      return Some(new_specifier.clone());
+    }
+    let old_dir = old_specifier.as_str().trim_end_matches('/');
+    if let Some(rest) = specifier
+      .as_str()
+      .strip_prefix(old_dir)
+      .and_then(|rest| rest.strip_prefix('/'))
+    {
+      let new_dir = new_specifier.as_str().trim_end_matches('/');
+      return resolve_url(&format!("{}/{}", new_dir, rest)).ok();
+    }
+  }
+  None
+}
+
+fn is_relative(specifier: &str) -> bool {
+  specifier.starts_with("./") || specifier.starts_with("../")
+}
+
+/// Determine the text of an import specifier once the referrer and the module
+/// it points to have been renamed. Relative specifiers stay relative, import
+/// map specifiers are remapped through the import map where possible, and
+/// absolute ones are replaced. Remote specifiers are never changed.
+fn get_new_specifier_text(
+  text: &str,
+  referrer: &ModuleSpecifier,
+  target: &ModuleSpecifier,
+  maybe_import_map: Option<&ImportMap>,
+  import_map_keys: &[String],
+) -> Option<String> {
+  if is_relative(text) {
+    Some(completions::relative_specifier(target, referrer))
+  } else if text.starts_with('/') {
+    Some(target.path().to_string())
+  } else if text.starts_with("file:") {
+    Some(target.to_string())
+  } else if resolve_url(text).is_err() {
+    // a bare specifier, which can only be resolved through the import map
+    maybe_import_map
+      .and_then(|import_map| {
+        analysis::get_import_map_specifier(
+          target,
+          referrer,
+          import_map,
+          import_map_keys,
+        )
+      })
+      .or_else(|| Some(completions::relative_specifier(target, referrer)))
+  } else {
+    None
+  }
+}
+
+/// Calculate the edits to the import specifiers of the supplied documents
+/// which are required for them to keep resolving to the same modules after the
+/// renames are applied. This covers both modules which import a renamed
+/// module and the relative imports of the renamed modules themselves.
+pub fn get_edits_for_file_renames(
+  renames: &[FileRename],
+  documents: &[Document],
+  maybe_import_map: Option<&ImportMap>,
+  import_map_keys: &[String],
+) -> Vec<lsp::TextDocumentEdit> {
+  let mut document_edits = Vec::new();
+  for document in documents {
+    let referrer = document.specifier();
+    if referrer.scheme() != "file" {
+      continue;
+    }
+    let maybe_new_referrer = get_renamed_specifier(referrer, renames);
+    let mut seen_ranges = HashSet::new();
+    let mut edits = Vec::new();
+    for (text, dependency) in document.dependencies() {
+      for resolved in [&dependency.maybe_code, &dependency.maybe_type] {
+        let (target, range) = match resolved {
+          Some(Ok((target, range))) => (target, range),
+          _ => continue,
+        };
+        let maybe_new_target = get_renamed_specifier(target, renames);
+        if maybe_new_target.is_none()
+          && (maybe_new_referrer.is_none() || !is_relative(&text))
+        {
+          continue;
+        }
+        let new_referrer = maybe_new_referrer.as_ref().unwrap_or(referrer);
+        let new_target = maybe_new_target.as_ref().unwrap_or(target);
+        let new_text = match get_new_specifier_text(
+          &text,
+          new_referrer,
+          new_target,
+          maybe_import_map,
+          import_map_keys,
+        ) {
+          Some(new_text) if new_text != text => new_text,
+          _ => continue,
+        };
+        // the range of a dependency includes the quotes around the specifier,
+        // which are kept as they are
+        let mut range = to_lsp_range(range);
+        range.start.character += 1;
+        range.end.character -= 1;
+        if seen_ranges.insert((
+          range.start.line,
+          range.start.character,
+          range.end.line,
+          range.end.character,
+        )) {
+          edits.push(lsp::OneOf::Left(lsp::TextEdit { range, new_text }));
+        }
+      }
+    }
+    if !edits.is_empty() {
+      document_edits.push(lsp::TextDocumentEdit {
+        text_document: lsp::OptionalVersionedTextDocumentIdentifier {
+          uri: referrer.clone(),
+          version: document.maybe_lsp_version(),
+        },
+        edits,
+      });
+    }
+  }
+  document_edits
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lsp::documents::Documents;
+  use crate::lsp::documents::LanguageId;
+  use std::sync::Arc;
+  use tempfile::TempDir;
+
+  fn rename(old: &str, new: &str) -> FileRename {
+    (resolve_url(old).unwrap(), resolve_url(new).unwrap())
+  }
+
+  fn edit_texts(edits: &lsp::TextDocumentEdit) -> Vec<(u32, u32, String)> {
+    edits
+      .edits
+      .iter()
+      .map(|edit| match edit {
+        lsp::OneOf::Left(edit) => (
+          edit.range.start.line,
+          edit.range.start.character,
+          edit.new_text.clone(),
+        ),
+        lsp::OneOf::Right(edit) => (
+          edit.text_edit.range.start.line,
+          edit.text_edit.range.start.character,
+          edit.text_edit.new_text.clone(),
+        ),
+      })
+      .collect()
+  }
+
+  #[test]
+  fn test_get_renamed_specifier() {
+    let renames = vec![
+      rename("file:///a/b.ts", "file:///a/c.ts"),
+      rename("file:///a/lib", "file:///a/src/lib"),
+    ];
+    let fixtures = [
+      ("file:///a/b.ts", Some("file:///a/c.ts")),
+      ("file:///a/lib/mod.ts", Some("file:///a/src/lib/mod.ts")),
+      (
+        "file:///a/lib/util/fs.ts",
+        Some("file:///a/src/lib/util/fs.ts"),
+      ),
+      ("file:///a/library.ts", None),
+      ("file:///a/d.ts", None),
+    ];
+    for (specifier, expected) in fixtures {
+      let specifier = resolve_url(specifier).unwrap();
+      assert_eq!(
+        get_renamed_specifier(&specifier, &renames),
+        expected.map(|s| resolve_url(s).unwrap())
+      );
+    }
+  }
+
+  #[test]
+  fn test_get_edits_for_file_renames() {
+    let temp_dir = TempDir::new().expect("could not create temp dir");
+    let mut documents = Documents::new(&temp_dir.path().join("deps"));
+    let fixtures = [
+      ("file:///a/b.ts", "export const b = \"b\";\n"),
+      (
+        "file:///a/c.ts",
+        "import { b } from \"./b.ts\";\nimport { d } from './lib/d.ts';\n",
+      ),
+      (
+        "file:///a/lib/d.ts",
+        "export { b as d } from \"../b.ts\";\n",
+      ),
+    ];
+    for (specifier, source) in fixtures {
+      documents.open(
+        resolve_url(specifier).unwrap(),
+        1,
+        LanguageId::TypeScript,
+        Arc::new(source.to_string()),
+      );
+    }
+    let documents = documents.documents(true, true);
+
+    let renames = vec![rename("file:///a/b.ts", "file:///a/src/b.ts")];
+    let mut edits = get_edits_for_file_renames(&renames, &documents, None, &[]);
+    edits.sort_by(|a, b| a.text_document.uri.cmp(&b.text_document.uri));
+    assert_eq!(edits.len(), 2);
+    assert_eq!(edits[0].text_document.uri.as_str(), "file:///a/c.ts");
+    assert_eq!(edits[0].text_document.version, Some(1));
+    assert_eq!(
+      edit_texts(&edits[0]),
+      vec![(0, 19, "./src/b.ts".to_string())]
+    );
+    assert_eq!(edits[1].text_document.uri.as_str(), "file:///a/lib/d.ts");
+    assert_eq!(
+      edit_texts(&edits[1]),
+      vec![(0, 24, "../src/b.ts".to_string())]
+    );
+
+    // moving a directory updates the modules which import modules within it,
+    // as well as the relative imports of the modules within it
+    let renames = vec![rename("file:///a/lib", "file:///a/src/lib")];
+    let mut edits = get_edits_for_file_renames(&renames, &documents, None, &[]);
+    edits.sort_by(|a, b| a.text_document.uri.cmp(&b.text_document.uri));
+    assert_eq!(edits.len(), 2);
+    assert_eq!(
+      edit_texts(&edits[0]),
+      vec![(1, 19, "./src/lib/d.ts".to_string())]
+    );
+    assert_eq!(
+      edit_texts(&edits[1]),
+      vec![(0, 24, "../../b.ts".to_string())]
+    );
+  }
+
+  #[test]
+  fn test_get_edits_for_file_renames_import_map() {
+    let temp_dir = TempDir::new().expect("could not create temp dir");
+    let mut documents = Documents::new(&temp_dir.path().join("deps"));
+    let import_map = Arc::new(
+      ImportMap::from_json(
+        "file:///a/import_map.json",
+        r#"{ "imports": { "@/": "./src/" } }"#,
+      )
+      .unwrap(),
+    );
+    documents.update_config(Some(import_map.clone()), None);
+    documents.open(
+      resolve_url("file:///a/src/b.ts").unwrap(),
+      1,
+      LanguageId::TypeScript,
+      Arc::new("export const b = \"b\";\n".to_string()),
+    );
+    documents.open(
+      resolve_url("file:///a/c.ts").unwrap(),
+      1,
+      LanguageId::TypeScript,
+      Arc::new("import { b } from \"@/b.ts\";\n".to_string()),
+    );
+    let documents = documents.documents(true, true);
+
+    let renames = vec![rename("file:///a/src/b.ts", "file:///a/src/e/b.ts")];
+    let edits = get_edits_for_file_renames(
+      &renames,
+      &documents,
+      Some(&import_map),
+      &["@/".to_string()],
+    );
+    assert_eq!(edits.len(), 1);
+    assert_eq!(edit_texts(&edits[0]), vec![(0, 19, "@/e/b.ts".to_string())]);
+
+    // when the module moves out of the mapped directory, the import falls
+    // back to a relative specifier
+    let renames = vec![rename("file:///a/src/b.ts", "file:///a/lib/b.ts")];
+    let edits = get_edits_for_file_renames(
+      &renames,
+      &documents,
+      Some(&import_map),
+      &["@/".to_string()],
+    );
+    assert_eq!(
+      edit_texts(&edits[0]),
+      vec![(0, 19, "./lib/b.ts".to_string())]
+    );
+  }
+}
@@ -0,0 +1,218 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+//! Helpers for partial formatting (range and on-type formatting).
+//!
+//! dprint can only format whole files, so partial formatting works by
+//! formatting the entire document and then only keeping the edits that fall
+//! within the statements the requested range touches.
+
+use super::documents::span_to_range;
+
+use deno_ast::swc::ast;
+use deno_ast::swc::common::Span;
+use deno_ast::swc::common::Spanned;
+use deno_ast::swc::visit::Visit;
+use deno_ast::swc::visit::VisitWith;
+use deno_ast::ParsedSource;
+use lspower::lsp;
+
+/// The characters which trigger on-type formatting.
+pub const ON_TYPE_TRIGGER_CHARACTERS: &[&str] = &[";", "}", "\n"];
+
+fn contains(outer: &lsp::Range, inner: &lsp::Range) -> bool {
+  outer.start <= inner.start && outer.end >= inner.end
+}
+
+fn intersects(a: &lsp::Range, b: &lsp::Range) -> bool {
+  a.start < b.end && a.end > b.start
+}
+
+struct StatementRangeCollector<'a> {
+  parsed_source: &'a ParsedSource,
+  range: lsp::Range,
+  expanded: Option<lsp::Range>,
+  smallest_containing: Option<lsp::Range>,
+}
+
+impl<'a> StatementRangeCollector<'a> {
+  fn check_span(&mut self, span: Span) {
+    let stmt_range = span_to_range(&span, self.parsed_source);
+    if contains(&stmt_range, &self.range) {
+      // statements are visited outside in, so the last one wins
+      self.smallest_containing = Some(stmt_range);
+    } else if intersects(&stmt_range, &self.range) {
+      let expanded = self.expanded.get_or_insert(self.range);
+      expanded.start = expanded.start.min(stmt_range.start);
+      expanded.end = expanded.end.max(stmt_range.end);
+    }
+  }
+}
+
+impl Visit for StatementRangeCollector<'_> {
+  fn visit_module_decl(&mut self, node: &ast::ModuleDecl) {
+    self.check_span(node.span());
+    node.visit_children_with(self);
+  }
+
+  fn visit_stmt(&mut self, node: &ast::Stmt) {
+    self.check_span(node.span());
+    node.visit_children_with(self);
+  }
+}
+
+/// Expand a range so it covers whole statements. Statements which partially
+/// overlap the range are included completely, and when the range falls
+/// entirely within a single statement, that statement is used.
+pub fn get_statements_range(
+  parsed_source: &ParsedSource,
+  range: lsp::Range,
+) -> lsp::Range {
+  let mut collector = StatementRangeCollector {
+    parsed_source,
+    range,
+    expanded: None,
+    smallest_containing: None,
+  };
+  parsed_source.module().visit_with(&mut collector);
+  collector
+    .expanded
+    .or(collector.smallest_containing)
+    .unwrap_or(range)
+}
+
+/// Expand a range so it covers whole lines, used for documents which aren't
+/// parsed as JavaScript or TypeScript.
+pub fn get_lines_range(range: lsp::Range) -> lsp::Range {
+  lsp::Range {
+    start: lsp::Position {
+      line: range.start.line,
+      character: 0,
+    },
+    end: lsp::Position {
+      line: range.end.line,
+      character: u32::MAX,
+    },
+  }
+}
+
+/// Returns the range that should be formatted after the user typed one of the
+/// `ON_TYPE_TRIGGER_CHARACTERS` at the given position. For a newline this is
+/// the line which was just finished, otherwise it is the typed character.
+pub fn get_on_type_range(position: lsp::Position, ch: &str) -> lsp::Range {
+  if ch == "\n" {
+    let line = position.line.saturating_sub(1);
+    get_lines_range(lsp::Range {
+      start: lsp::Position { line, character: 0 },
+      end: lsp::Position { line, character: 0 },
+    })
+  } else {
+    lsp::Range {
+      start: lsp::Position {
+        line: position.line,
+        character: position.character.saturating_sub(1),
+      },
+      end: position,
+    }
+  }
+}
+
+/// Only keep the edits which fall completely within the range.
+pub fn filter_edits(
+  text_edits: Vec<lsp::TextEdit>,
+  range: &lsp::Range,
+) -> Vec<lsp::TextEdit> {
+  text_edits
+    .into_iter()
+    .filter(|edit| contains(range, &edit.range))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lsp::text;
+  use crate::lsp::text::LineIndex;
+  use crate::tools::fmt::format_parsed_source;
+  use deno_ast::MediaType;
+  use deno_ast::SourceTextInfo;
+  use std::sync::Arc;
+
+  fn parse(source: &str) -> ParsedSource {
+    deno_ast::parse_module(deno_ast::ParseParams {
+      specifier: "file:///a/mod.ts".to_string(),
+      source: SourceTextInfo::new(Arc::new(source.to_string())),
+      media_type: MediaType::TypeScript,
+      capture_tokens: true,
+      scope_analysis: false,
+      maybe_syntax: None,
+    })
+    .unwrap()
+  }
+
+  fn range(
+    start_line: u32,
+    start_character: u32,
+    end_line: u32,
+    end_character: u32,
+  ) -> lsp::Range {
+    lsp::Range {
+      start: lsp::Position {
+        line: start_line,
+        character: start_character,
+      },
+      end: lsp::Position {
+        line: end_line,
+        character: end_character,
+      },
+    }
+  }
+
+  #[test]
+  fn test_get_statements_range() {
+    let parsed_source =
+      parse("const a  =  1;\nfunction b() {\n  let c=2;\n  return c;\n}\n");
+    // a range within a single statement expands to that statement
+    assert_eq!(
+      get_statements_range(&parsed_source, range(0, 2, 0, 4)),
+      range(0, 0, 0, 14)
+    );
+    // a range within a function body only expands to the inner statements
+    assert_eq!(
+      get_statements_range(&parsed_source, range(2, 0, 2, u32::MAX)),
+      range(2, 0, 2, u32::MAX)
+    );
+    assert_eq!(
+      get_statements_range(&parsed_source, range(2, 4, 3, 3)),
+      range(2, 2, 3, 11)
+    );
+    // a range spanning several top level statements covers all of them
+    assert_eq!(
+      get_statements_range(&parsed_source, range(0, 3, 1, 3)),
+      range(0, 0, 4, 1)
+    );
+  }
+
+  #[test]
+  fn test_get_on_type_range() {
+    let position = lsp::Position {
+      line: 2,
+      character: 10,
+    };
+    assert_eq!(get_on_type_range(position, ";"), range(2, 9, 2, 10));
+    assert_eq!(get_on_type_range(position, "\n"), range(1, 0, 1, u32::MAX));
+  }
+
+  #[test]
+  fn test_filter_edits() {
+    let source = "const a  =  1;\nconst b  =  2;\n";
+    let parsed_source = parse(source);
+    let formatted =
+      format_parsed_source(&parsed_source, Default::default()).unwrap();
+    let line_index = LineIndex::new(source);
+    let edits = text::get_edits(source, &formatted, &line_index);
+    let range = get_statements_range(&parsed_source, range(1, 0, 1, 0));
+    let edits = filter_edits(edits, &range);
+    assert!(!edits.is_empty());
+    assert!(edits.iter().all(|edit| edit.range.start.line == 1));
+  }
+}
@@ -18,16 +18,19 @@ use lspower::lsp::request::*;
 use lspower::lsp::*;
 use lspower::Client;
 use serde_json::from_value;
+use std::collections::HashSet;
 use std::env;
 use std::path::PathBuf;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use tokio::fs;
 
+use super::analysis;
 use super::analysis::fix_ts_import_changes;
 use super::analysis::ts_changes_to_edit;
 use super::analysis::CodeActionCollection;
 use super::analysis::CodeActionData;
+use super::analysis::ImportsCodeActionData;
 use super::cache::CacheServer;
 use super::capabilities;
 use super::code_lens;
@@ -35,18 +38,26 @@ use super::completions;
 use super::config::Config;
 use super::config::ConfigSnapshot;
 use super::config::SETTINGS_SECTION;
+use super::config_documents;
+use super::config_documents::ConfigDocumentKind;
 use super::diagnostics;
 use super::diagnostics::DiagnosticSource;
 use super::documents::to_hover_text;
 use super::documents::to_lsp_range;
 use super::documents::AssetOrDocument;
+use super::documents::Document;
 use super::documents::Documents;
 use super::documents::LanguageId;
+use super::file_rename;
+use super::formatting;
 use super::lsp_custom;
 use super::parent_process_checker;
 use super::performance::Performance;
 use super::refactor;
 use super::registries;
+use super::testing;
+use super::testing::TestRun;
+use super::testing::TestServer;
 use super::text;
 use super::tsc;
 use super::tsc::AssetDocument;
@@ -54,11 +65,14 @@ use super::tsc::Assets;
 use super::tsc::TsServer;
 use super::urls;
 use crate::config_file::ConfigFile;
+use crate::config_file::FilesConfig;
 use crate::config_file::FmtConfig;
 use crate::config_file::LintConfig;
 use crate::config_file::TsConfig;
 use crate::deno_dir;
 use crate::file_fetcher::get_source_from_data_url;
+use crate::flags;
+use crate::flags::Flags;
 use crate::fs_util;
 use crate::logger;
 use crate::tools::fmt::format_file;
@@ -81,6 +95,7 @@ pub(crate) struct StateSnapshot {
   pub module_registries: registries::ModuleRegistry,
   pub performance: Performance,
   pub url_map: urls::LspUrlMap,
+  pub workspace_files: Arc<Vec<ModuleSpecifier>>,
 }
 
 #[derive(Debug)]
@@ -96,6 +111,9 @@ pub(crate) struct Inner {
   /// The collection of documents that the server is currently handling, either
   /// on disk or "open" within the client.
   documents: Documents,
+  /// The bare specifiers mapped by the `imports` of the import map, which are
+  /// preferred when rewriting the specifiers of auto-imports.
+  pub(crate) import_map_keys: Vec<String>,
   /// Handles module registries, which allow discovery of modules
   module_registries: registries::ModuleRegistry,
   /// The path to the module registries cache
@@ -112,6 +130,9 @@ pub(crate) struct Inner {
   maybe_lint_config: Option<LintConfig>,
   /// An optional configuration for formatter which has been taken from specified config file.
   maybe_fmt_config: Option<FmtConfig>,
+  /// An optional configuration of the files which make up the workspace, which
+  /// has been taken from specified config file.
+  maybe_files_config: Option<FilesConfig>,
   /// An optional URL which provides the location of a TypeScript configuration
   /// file which will be used by the Deno LSP.
   maybe_config_uri: Option<Url>,
@@ -119,6 +140,8 @@ pub(crate) struct Inner {
   pub(crate) maybe_import_map: Option<Arc<ImportMap>>,
   /// The URL for the import map which is used to determine relative imports.
   maybe_import_map_uri: Option<Url>,
+  /// A lazily created "server" for handling test run requests.
+  maybe_test_server: Option<TestServer>,
   /// A collection of measurements which instrument that performance of the LSP.
   performance: Performance,
   /// A memoized version of fixable diagnostic codes retrieved from TypeScript.
@@ -127,6 +150,10 @@ pub(crate) struct Inner {
   pub(crate) ts_server: Arc<TsServer>,
   /// A map of specifiers and URLs used to translate over the LSP.
   pub(crate) url_map: urls::LspUrlMap,
+  /// The modules in the workspace which are diagnosed even when they aren't
+  /// open in the client. This is only populated when workspace diagnostics
+  /// are enabled.
+  workspace_files: Arc<Vec<ModuleSpecifier>>,
 }
 
 impl LanguageServer {
@@ -154,20 +181,24 @@ impl Inner {
       config,
       diagnostics_server: Default::default(),
       documents,
+      import_map_keys: Vec::new(),
       maybe_cache_path: None,
       maybe_lint_config: None,
       maybe_fmt_config: None,
+      maybe_files_config: None,
       maybe_cache_server: None,
       maybe_config_file: None,
       maybe_config_uri: None,
       maybe_import_map: None,
       maybe_import_map_uri: None,
+      maybe_test_server: None,
       module_registries,
       module_registries_location,
       performance: Default::default(),
       ts_fixable_diagnostics: Default::default(),
       ts_server,
       url_map: Default::default(),
+      workspace_files: Default::default(),
     }
   }
 
@@ -378,6 +409,7 @@ impl Inner {
       module_registries: self.module_registries.clone(),
       performance: self.performance.clone(),
       url_map: self.url_map.clone(),
+      workspace_files: self.workspace_files.clone(),
     }))
   }
 
@@ -485,9 +517,19 @@ impl Inner {
         &import_map_url.to_string(),
         &import_map_json,
       )?);
+      self.import_map_keys = serde_json::from_str::<Value>(&import_map_json)
+        .ok()
+        .and_then(|value| {
+          value
+            .get("imports")
+            .and_then(|imports| imports.as_object())
+            .map(|imports| imports.keys().cloned().collect())
+        })
+        .unwrap_or_default();
       self.maybe_import_map_uri = Some(import_map_url);
       self.maybe_import_map = Some(import_map);
     } else {
+      self.import_map_keys = Vec::new();
       self.maybe_import_map = None;
     }
     self.performance.measure(mark);
@@ -531,6 +573,7 @@ impl Inner {
     self.maybe_config_file = None;
     self.maybe_config_uri = None;
     self.maybe_fmt_config = None;
+    self.maybe_files_config = None;
     self.maybe_lint_config = None;
 
     let maybe_file_and_url = self.get_config_file_and_url()?;
@@ -548,11 +591,18 @@ impl Inner {
           anyhow!("Unable to update formatter configuration: {:?}", err)
         })?
         .unwrap_or_default();
+      let files_config = config_file
+        .to_files_config()
+        .map_err(|err| {
+          anyhow!("Unable to update files configuration: {:?}", err)
+        })?
+        .unwrap_or_default();
 
       self.maybe_config_file = Some(config_file);
       self.maybe_config_uri = Some(config_url);
       self.maybe_lint_config = Some(lint_config);
       self.maybe_fmt_config = Some(fmt_config);
+      self.maybe_files_config = Some(files_config);
     }
 
     Ok(())
@@ -594,6 +644,147 @@ impl Inner {
     Ok(())
   }
 
+  /// Publish the tests defined in a document to clients which support the
+  /// testing API.
+  async fn publish_tests(&self, document: &Document) {
+    let specifier = document.specifier();
+    if !self.config.client_capabilities.testing_api
+      || specifier.scheme() != "file"
+      || !self.config.specifier_enabled(specifier)
+    {
+      return;
+    }
+    if let Some(Ok(parsed_source)) = document.maybe_parsed_source() {
+      let tests = testing::collect_tests(specifier, &parsed_source);
+      self
+        .client
+        .send_custom_notification::<lsp_custom::TestModuleNotification>(
+          lsp_custom::TestModuleParams {
+            text_document: TextDocumentIdentifier {
+              uri: specifier.clone(),
+            },
+            tests,
+          },
+        )
+        .await;
+    }
+  }
+
+  fn get_config_document_kind(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> Option<ConfigDocumentKind> {
+    if !self.config.specifier_enabled(specifier) {
+      return None;
+    }
+    config_documents::get_config_document_kind(
+      specifier,
+      self.maybe_config_uri.as_ref(),
+      self.maybe_import_map_uri.as_ref(),
+    )
+  }
+
+  /// Configuration files and import maps aren't handled by the diagnostics
+  /// server, so their diagnostics are published directly when they change.
+  async fn publish_config_document_diagnostics(&self, document: &Document) {
+    let specifier = document.specifier();
+    if let Some(kind) = self.get_config_document_kind(specifier) {
+      let diagnostics = config_documents::get_diagnostics(
+        kind,
+        specifier,
+        &document.content(),
+        &self.documents,
+      );
+      self
+        .client
+        .publish_diagnostics(
+          specifier.clone(),
+          diagnostics,
+          document.maybe_lsp_version(),
+        )
+        .await;
+    }
+  }
+
+  /// Publish the tests of the test modules in the workspace, so that clients
+  /// can show them before the modules are opened.
+  async fn publish_workspace_tests(&self) {
+    for specifier in self.collect_workspace_files() {
+      let is_test_module = fs_util::specifier_to_file_path(&specifier)
+        .map_or(false, |path| fs_util::is_supported_test_path(&path));
+      if !is_test_module {
+        continue;
+      }
+      if let Some(document) = self.documents.get(&specifier) {
+        self.publish_tests(&document).await;
+      }
+    }
+  }
+
+  /// Walk the workspace for the modules which should be diagnosed when
+  /// workspace diagnostics are enabled, taking the `files` configuration of
+  /// the configuration file into account. Diagnostics are cleared for any
+  /// closed modules which are no longer part of the workspace.
+  async fn update_workspace_files(&mut self) {
+    let mark = self.performance.mark("update_workspace_files", None::<()>);
+    let workspace_files =
+      if self.config.get_workspace_settings().workspace_diagnostics {
+        self.collect_workspace_files()
+      } else {
+        Vec::new()
+      };
+
+    let current: HashSet<&ModuleSpecifier> = workspace_files.iter().collect();
+    let removed: Vec<ModuleSpecifier> = self
+      .workspace_files
+      .iter()
+      .filter(|s| !current.contains(s))
+      .filter(|s| !self.documents.get(s).map_or(false, |d| d.is_open()))
+      .cloned()
+      .collect();
+    for specifier in &removed {
+      self
+        .client
+        .publish_diagnostics(specifier.clone(), Vec::new(), None)
+        .await;
+    }
+    self.diagnostics_server.invalidate(removed).await;
+
+    self.workspace_files = Arc::new(workspace_files);
+    self.performance.measure(mark);
+  }
+
+  /// Collect the modules under the root of the workspace which are included
+  /// by the `files` configuration of the configuration file.
+  fn collect_workspace_files(&self) -> Vec<ModuleSpecifier> {
+    let root_path = match self
+      .config
+      .root_uri
+      .as_ref()
+      .map(fs_util::specifier_to_file_path)
+    {
+      Some(Ok(root_path)) => root_path,
+      _ => return Vec::new(),
+    };
+    match fs_util::collect_specifiers(
+      vec![root_path.to_string_lossy().to_string()],
+      &[],
+      fs_util::is_supported_ext,
+    ) {
+      Ok(specifiers) => specifiers
+        .into_iter()
+        .filter(|s| match &self.maybe_files_config {
+          Some(files_config) => files_config.matches_specifier(s),
+          None => true,
+        })
+        .collect(),
+      Err(err) => {
+        error!("Unable to collect workspace modules: {}", err);
+        Vec::new()
+      }
+    }
+  }
+
   async fn get_asset(
     &mut self,
     specifier: &ModuleSpecifier,
@@ -707,6 +898,7 @@ impl Inner {
       self.maybe_import_map.clone(),
       self.maybe_config_file.as_ref(),
     );
+    self.update_workspace_files().await;
 
     self.performance.measure(mark);
     Ok(InitializeResult {
@@ -724,13 +916,20 @@ impl Inner {
       // we are going to watch all the JSON files in the workspace, and the
       // notification handler will pick up any of the changes of those files we
       // are interested in.
+      let mut watchers = vec![FileSystemWatcher {
+        glob_pattern: "**/*.json".to_string(),
+        kind: Some(WatchKind::Change),
+      }];
+      // when workspace diagnostics are enabled, modules which aren't open in
+      // the client are diagnosed too, so we need to know when they change
+      if self.config.get_workspace_settings().workspace_diagnostics {
+        watchers.push(FileSystemWatcher {
+          glob_pattern: "**/*.{ts,tsx,js,jsx,mjs}".to_string(),
+          kind: Some(WatchKind::all()),
+        });
+      }
       let watch_registration_options =
-        DidChangeWatchedFilesRegistrationOptions {
-          watchers: vec![FileSystemWatcher {
-            glob_pattern: "**/*.json".to_string(),
-            kind: Some(WatchKind::Change),
-          }],
-        };
+        DidChangeWatchedFilesRegistrationOptions { watchers };
       let registration = Registration {
         id: "workspace/didChangeWatchedFiles".to_string(),
         method: "workspace/didChangeWatchedFiles".to_string(),
@@ -745,6 +944,16 @@ impl Inner {
       }
     }
 
+    if self.config.client_capabilities.testing_api {
+      self.publish_workspace_tests().await;
+    }
+
+    if self.config.get_workspace_settings().workspace_diagnostics {
+      if let Err(err) = self.diagnostics_server.update() {
+        error!("{}", err);
+      }
+    }
+
     info!("Server ready.");
   }
 
@@ -801,6 +1010,9 @@ impl Inner {
       if let Err(err) = self.diagnostics_server.update() {
         error!("{}", err);
       }
+      self.publish_tests(&document).await;
+    } else {
+      self.publish_config_document_diagnostics(&document).await;
     }
 
     self.performance.measure(mark);
@@ -823,6 +1035,9 @@ impl Inner {
           if let Err(err) = self.diagnostics_server.update() {
             error!("{}", err);
           }
+          self.publish_tests(&document).await;
+        } else {
+          self.publish_config_document_diagnostics(&document).await;
         }
       }
       Err(err) => error!("{}", err),
@@ -843,6 +1058,12 @@ impl Inner {
     if let Err(err) = self.documents.close(&specifier) {
       error!("{}", err);
     }
+    if self.get_config_document_kind(&specifier).is_some() {
+      self
+        .client
+        .publish_diagnostics(specifier.clone(), Vec::new(), None)
+        .await;
+    }
     if self.is_diagnosable(&specifier) {
       let mut specifiers = self.documents.dependents(&specifier);
       specifiers.push(specifier.clone());
@@ -912,6 +1133,7 @@ impl Inner {
     if let Err(err) = self.update_tsconfig().await {
       self.client.show_message(MessageType::WARNING, err).await;
     }
+    self.update_workspace_files().await;
     if let Err(err) = self.diagnostics_server.update() {
       error!("{}", err);
     }
@@ -963,10 +1185,33 @@ impl Inner {
         self.maybe_import_map.clone(),
         self.maybe_config_file.as_ref(),
       );
+      self.update_workspace_files().await;
       self.diagnostics_server.invalidate_all().await;
       if let Err(err) = self.diagnostics_server.update() {
         error!("Cannot update diagnostics: {}", err);
       }
+    } else if self.config.get_workspace_settings().workspace_diagnostics {
+      // modules which aren't open in the client may have been created, changed
+      // or removed on disk, which the workspace diagnostics need to reflect
+      let changed_modules: Vec<ModuleSpecifier> = changes
+        .into_iter()
+        .filter(|uri| {
+          fs_util::specifier_to_file_path(uri)
+            .map_or(false, |path| fs_util::is_supported_ext(&path))
+        })
+        .collect();
+      if !changed_modules.is_empty() {
+        self.update_workspace_files().await;
+        let mut specifiers = Vec::new();
+        for specifier in changed_modules {
+          specifiers.extend(self.documents.dependents(&specifier));
+          specifiers.push(specifier);
+        }
+        self.diagnostics_server.invalidate(specifiers).await;
+        if let Err(err) = self.diagnostics_server.update() {
+          error!("Cannot update diagnostics: {}", err);
+        }
+      }
     }
     self.performance.measure(mark);
   }
@@ -1014,11 +1259,56 @@ impl Inner {
     params: DocumentFormattingParams,
   ) -> LspResult<Option<Vec<TextEdit>>> {
     let specifier = self.url_map.normalize_url(&params.text_document.uri);
+    let mark = self.performance.mark("formatting", Some(&params));
+    let result = self.format_document(specifier, None, false).await;
+    self.performance.measure(mark);
+    result
+  }
+
+  async fn range_formatting(
+    &self,
+    params: DocumentRangeFormattingParams,
+  ) -> LspResult<Option<Vec<TextEdit>>> {
+    let specifier = self.url_map.normalize_url(&params.text_document.uri);
+    let mark = self.performance.mark("range_formatting", Some(&params));
+    let result = self
+      .format_document(specifier, Some(params.range), false)
+      .await;
+    self.performance.measure(mark);
+    result
+  }
+
+  async fn on_type_formatting(
+    &self,
+    params: DocumentOnTypeFormattingParams,
+  ) -> LspResult<Option<Vec<TextEdit>>> {
+    let specifier = self
+      .url_map
+      .normalize_url(&params.text_document_position.text_document.uri);
+    let mark = self.performance.mark("on_type_formatting", Some(&params));
+    let range = formatting::get_on_type_range(
+      params.text_document_position.position,
+      &params.ch,
+    );
+    // the document is likely incomplete while typing, so don't bother the
+    // user when it can't be formatted
+    let result = self.format_document(specifier, Some(range), true).await;
+    self.performance.measure(mark);
+    result
+  }
+
+  /// Format a document, or only the statements which are touched by the
+  /// range when one is supplied.
+  async fn format_document(
+    &self,
+    specifier: ModuleSpecifier,
+    maybe_range: Option<Range>,
+    quiet: bool,
+  ) -> LspResult<Option<Vec<TextEdit>>> {
     let document = match self.documents.get(&specifier) {
       Some(doc) if doc.is_open() => doc,
       _ => return Ok(None),
     };
-    let mark = self.performance.mark("formatting", Some(&params));
     let file_path =
       fs_util::specifier_to_file_path(&specifier).map_err(|err| {
         error!("{}", err);
@@ -1036,9 +1326,10 @@ impl Inner {
     };
 
     let text_edits = tokio::task::spawn_blocking(move || {
-      let format_result = match document.maybe_parsed_source() {
+      let maybe_parsed_source = document.maybe_parsed_source();
+      let format_result = match &maybe_parsed_source {
         Some(Ok(parsed_source)) => {
-          format_parsed_source(&parsed_source, fmt_options)
+          format_parsed_source(parsed_source, fmt_options)
         }
         Some(Err(err)) => Err(err.to_string()),
         None => {
@@ -1048,11 +1339,24 @@ impl Inner {
       };
 
       match format_result {
-        Ok(new_text) => Some(text::get_edits(
-          document.content().as_str(),
-          &new_text,
-          document.line_index().as_ref(),
-        )),
+        Ok(new_text) => {
+          let text_edits = text::get_edits(
+            document.content().as_str(),
+            &new_text,
+            document.line_index().as_ref(),
+          );
+          if let Some(range) = maybe_range {
+            let range = match &maybe_parsed_source {
+              Some(Ok(parsed_source)) => {
+                formatting::get_statements_range(parsed_source, range)
+              }
+              _ => formatting::get_lines_range(range),
+            };
+            Some(formatting::filter_edits(text_edits, &range))
+          } else {
+            Some(text_edits)
+          }
+        }
         Err(err) => {
           // TODO(lucacasonato): handle error properly
           warn!("Format error: {}", err);
@@ -1063,13 +1367,14 @@ impl Inner {
     .await
     .unwrap();
 
-    self.performance.measure(mark);
     if let Some(text_edits) = text_edits {
       if text_edits.is_empty() {
         Ok(None)
       } else {
         Ok(Some(text_edits))
       }
+    } else if quiet {
+      Ok(None)
     } else {
       self.client.show_message(MessageType::WARNING, format!("Unable to format \"{}\". Likely due to unrecoverable syntax errors in the file.", specifier)).await;
       Ok(None)
@@ -1080,6 +1385,9 @@ impl Inner {
     let specifier = self
       .url_map
       .normalize_url(&params.text_document_position_params.text_document.uri);
+    if let Some(kind) = self.get_config_document_kind(&specifier) {
+      return self.config_document_hover(kind, &specifier, &params);
+    }
     if !self.is_diagnosable(&specifier)
       || !self.config.specifier_enabled(&specifier)
     {
@@ -1252,12 +1560,15 @@ impl Inner {
               LspError::internal_error()
             })?,
           Some("deno-lint") => code_actions
-            .add_deno_lint_ignore_action(
-              &specifier,
-              diagnostic,
-              asset_or_doc.document().map(|d| d.text_info()),
-              asset_or_doc.maybe_parsed_source().map(|r| r.ok()).flatten(),
-            )
+            .add_deno_lint_fix_action(&specifier, diagnostic)
+            .and_then(|_| {
+              code_actions.add_deno_lint_ignore_action(
+                &specifier,
+                diagnostic,
+                asset_or_doc.document().map(|d| d.text_info()),
+                asset_or_doc.maybe_parsed_source().map(|r| r.ok()).flatten(),
+              )
+            })
             .map_err(|err| {
               error!("Unable to fix lint error: {}", err);
               LspError::internal_error()
@@ -1306,6 +1617,18 @@ impl Inner {
         .map(CodeActionOrCommand::CodeAction),
     );
 
+    // Source
+    if asset_or_doc.document().is_some() {
+      all_actions.extend(
+        analysis::get_imports_source_actions(
+          &specifier,
+          params.context.only.as_deref(),
+        )
+        .into_iter()
+        .map(CodeActionOrCommand::CodeAction),
+      );
+    }
+
     let code_action_disabled_support =
       self.config.client_capabilities.code_action_disabled_support;
     let actions: Vec<CodeActionOrCommand> = all_actions.into_iter().filter(|ca| {
@@ -1412,6 +1735,47 @@ impl Inner {
           LspError::internal_error()
         })?;
       code_action
+    } else if kind.as_str().starts_with(CodeActionKind::SOURCE.as_str()) {
+      let mut code_action = params.clone();
+      let action_data: ImportsCodeActionData =
+        from_value(data).map_err(|err| {
+          error!("Unable to decode code action data: {}", err);
+          LspError::invalid_params("The CodeAction's data is invalid.")
+        })?;
+      let changes = if kind == *analysis::SOURCE_REMOVE_UNUSED_IMPORTS {
+        let req = tsc::RequestMethod::GetCombinedCodeFix((
+          action_data.specifier.clone(),
+          json!("unusedIdentifier_deleteImports"),
+        ));
+        let combined_code_actions: tsc::CombinedCodeActions = self
+          .ts_server
+          .request(self.snapshot()?, req)
+          .await
+          .map_err(|err| {
+            error!("Unable to get combined fix from TypeScript: {}", err);
+            LspError::internal_error()
+          })?;
+        combined_code_actions.changes
+      } else {
+        let req = tsc::RequestMethod::OrganizeImports((
+          action_data.specifier.clone(),
+          kind == *analysis::SOURCE_SORT_IMPORTS,
+        ));
+        self
+          .ts_server
+          .request(self.snapshot()?, req)
+          .await
+          .map_err(|err| {
+            error!("Unable to organize imports with TypeScript: {}", err);
+            LspError::internal_error()
+          })?
+      };
+      code_action.edit =
+        ts_changes_to_edit(&changes, self).await.map_err(|err| {
+          error!("Unable to convert changes to edits: {}", err);
+          LspError::internal_error()
+        })?;
+      code_action
     } else {
       // The code action doesn't need to be resolved
       params
@@ -1527,6 +1891,62 @@ impl Inner {
     }
   }
 
+  async fn document_link(
+    &mut self,
+    params: DocumentLinkParams,
+  ) -> LspResult<Option<Vec<DocumentLink>>> {
+    let specifier = self.url_map.normalize_url(&params.text_document.uri);
+    if !self.is_diagnosable(&specifier)
+      || !self.config.specifier_enabled(&specifier)
+    {
+      return Ok(None);
+    }
+
+    let mark = self.performance.mark("document_link", Some(&params));
+    let asset_or_doc = self.get_cached_asset_or_document(&specifier)?;
+    let document = match asset_or_doc.document() {
+      Some(document) => document,
+      None => return Ok(None),
+    };
+    let mut links: Vec<DocumentLink> = Vec::new();
+    for (_, dep) in document.dependencies() {
+      for resolved in [&dep.maybe_code, &dep.maybe_type].into_iter().flatten() {
+        let (dep_specifier, range) = match resolved {
+          Ok(resolved) => resolved,
+          Err(_) => continue,
+        };
+        let is_remote = match dep_specifier.scheme() {
+          "file" => false,
+          "http" | "https" => true,
+          _ => continue,
+        };
+        // remote modules can only be opened once they are in the cache
+        if is_remote && !self.documents.contains_specifier(dep_specifier) {
+          continue;
+        }
+        let range = to_lsp_range(range);
+        if links.iter().any(|link| link.range == range) {
+          continue;
+        }
+        let target = match self.url_map.normalize_specifier(dep_specifier) {
+          Ok(target) => target,
+          Err(err) => {
+            error!("Unable to normalize \"{}\": {}", dep_specifier, err);
+            continue;
+          }
+        };
+        links.push(DocumentLink {
+          range,
+          target: Some(target),
+          tooltip: Some(dep_specifier.to_string()),
+          data: None,
+        });
+      }
+    }
+    self.performance.measure(mark);
+    Ok(Some(links))
+  }
+
   async fn references(
     &mut self,
     params: ReferenceParams,
@@ -1676,6 +2096,11 @@ impl Inner {
     let specifier = self
       .url_map
       .normalize_url(&params.text_document_position.text_document.uri);
+    if let Some(kind) = self.get_config_document_kind(&specifier) {
+      return self
+        .config_document_completion(kind, &specifier, &params)
+        .await;
+    }
     if !self.is_diagnosable(&specifier)
       || !self.config.specifier_enabled(&specifier)
     {
@@ -1747,6 +2172,81 @@ impl Inner {
     Ok(response)
   }
 
+  /// Return the document and the byte offset of a position within it.
+  fn get_config_document_offset(
+    &self,
+    specifier: &ModuleSpecifier,
+    position: Position,
+  ) -> LspResult<Option<(Document, usize)>> {
+    let document = match self.documents.get(specifier) {
+      Some(document) => document,
+      None => return Ok(None),
+    };
+    let offset = document
+      .line_index()
+      .offset(position)
+      .map_err(|err| LspError::invalid_params(err.to_string()))?;
+    let offset = u32::from(offset) as usize;
+    if !document.content().is_char_boundary(offset) {
+      return Ok(None);
+    }
+    Ok(Some((document, offset)))
+  }
+
+  fn config_document_hover(
+    &self,
+    kind: ConfigDocumentKind,
+    specifier: &ModuleSpecifier,
+    params: &HoverParams,
+  ) -> LspResult<Option<Hover>> {
+    let mark = self.performance.mark("config_document_hover", Some(params));
+    let maybe_hover = self
+      .get_config_document_offset(
+        specifier,
+        params.text_document_position_params.position,
+      )?
+      .and_then(|(document, offset)| {
+        config_documents::get_hover(
+          kind,
+          specifier,
+          &document.content(),
+          offset,
+        )
+      });
+    self.performance.measure(mark);
+    Ok(maybe_hover)
+  }
+
+  async fn config_document_completion(
+    &self,
+    kind: ConfigDocumentKind,
+    specifier: &ModuleSpecifier,
+    params: &CompletionParams,
+  ) -> LspResult<Option<CompletionResponse>> {
+    let mark = self
+      .performance
+      .mark("config_document_completion", Some(params));
+    let maybe_response = match self.get_config_document_offset(
+      specifier,
+      params.text_document_position.position,
+    )? {
+      Some((document, offset)) => {
+        config_documents::get_completions(
+          kind,
+          specifier,
+          &document.content(),
+          offset,
+          &self.module_registries,
+          &self.documents,
+        )
+        .await
+      }
+      None => None,
+    };
+    self.performance.measure(mark);
+    Ok(maybe_response)
+  }
+
   async fn completion_resolve(
     &mut self,
     params: CompletionItem,
@@ -2116,10 +2616,31 @@ impl Inner {
         Some(Err(err)) => Err(LspError::invalid_params(err.to_string())),
         None => Err(LspError::invalid_params("Missing parameters")),
       },
+      lsp_custom::INLAY_HINT_REQUEST => {
+        match params.map(serde_json::from_value) {
+          Some(Ok(params)) => Ok(Some(
+            serde_json::to_value(self.inlay_hint(params).await?).map_err(
+              |err| {
+                error!("Failed to serialize inlay_hint response: {}", err);
+                LspError::internal_error()
+              },
+            )?,
+          )),
+          Some(Err(err)) => Err(LspError::invalid_params(err.to_string())),
+          None => Err(LspError::invalid_params("Missing parameters")),
+        }
+      }
       lsp_custom::PERFORMANCE_REQUEST => Ok(Some(self.get_performance())),
       lsp_custom::RELOAD_IMPORT_REGISTRIES_REQUEST => {
         self.reload_import_registries().await
       }
+      lsp_custom::TEST_RUN_REQUEST => {
+        match params.map(serde_json::from_value) {
+          Some(Ok(params)) => self.test_run(params).await,
+          Some(Err(err)) => Err(LspError::invalid_params(err.to_string())),
+          None => Err(LspError::invalid_params("Missing parameters")),
+        }
+      }
       lsp_custom::VIRTUAL_TEXT_DOCUMENT => {
         match params.map(serde_json::from_value) {
           Some(Ok(params)) => Ok(Some(
@@ -2355,6 +2876,48 @@ impl Inner {
     self.performance.measure(mark);
     Ok(maybe_symbol_information)
   }
+
+  async fn will_rename_files(
+    &mut self,
+    params: RenameFilesParams,
+  ) -> LspResult<Option<WorkspaceEdit>> {
+    let mark = self.performance.mark("will_rename_files", Some(&params));
+    let renames: Vec<file_rename::FileRename> = params
+      .files
+      .iter()
+      .filter_map(|rename| {
+        let old_specifier = resolve_url(&rename.old_uri).ok()?;
+        let new_specifier = resolve_url(&rename.new_uri).ok()?;
+        Some((
+          self.url_map.normalize_url(&old_specifier),
+          self.url_map.normalize_url(&new_specifier),
+        ))
+      })
+      .collect();
+
+    // modules which aren't open in the client might import the renamed
+    // modules as well, so they need to be loaded into the documents first
+    for specifier in self.collect_workspace_files() {
+      self.documents.get(&specifier);
+    }
+
+    let document_edits = file_rename::get_edits_for_file_renames(
+      &renames,
+      &self.documents.documents(false, true),
+      self.maybe_import_map.as_deref(),
+      &self.import_map_keys,
+    );
+    self.performance.measure(mark);
+    if document_edits.is_empty() {
+      Ok(None)
+    } else {
+      Ok(Some(WorkspaceEdit {
+        changes: None,
+        document_changes: Some(DocumentChanges::Edits(document_edits)),
+        change_annotations: None,
+      }))
+    }
+  }
 }
 
 #[lspower::async_trait]
@@ -2425,6 +2988,20 @@ impl lspower::LanguageServer for LanguageServer {
     self.0.lock().await.formatting(params).await
   }
 
+  async fn range_formatting(
+    &self,
+    params: DocumentRangeFormattingParams,
+  ) -> LspResult<Option<Vec<TextEdit>>> {
+    self.0.lock().await.range_formatting(params).await
+  }
+
+  async fn on_type_formatting(
+    &self,
+    params: DocumentOnTypeFormattingParams,
+  ) -> LspResult<Option<Vec<TextEdit>>> {
+    self.0.lock().await.on_type_formatting(params).await
+  }
+
   async fn hover(&self, params: HoverParams) -> LspResult<Option<Hover>> {
     self.0.lock().await.hover(params).await
   }
@@ -2461,6 +3038,13 @@ impl lspower::LanguageServer for LanguageServer {
     self.0.lock().await.document_highlight(params).await
   }
 
+  async fn document_link(
+    &self,
+    params: DocumentLinkParams,
+  ) -> LspResult<Option<Vec<DocumentLink>>> {
+    self.0.lock().await.document_link(params).await
+  }
+
   async fn references(
     &self,
     params: ReferenceParams,
@@ -2580,6 +3164,13 @@ impl lspower::LanguageServer for LanguageServer {
   ) -> LspResult<Option<Vec<SymbolInformation>>> {
     self.0.lock().await.symbol(params).await
   }
+
+  async fn will_rename_files(
+    &self,
+    params: RenameFilesParams,
+  ) -> LspResult<Option<WorkspaceEdit>> {
+    self.0.lock().await.will_rename_files(params).await
+  }
 }
 
 // These are implementations of custom commands supported by the LSP
@@ -2638,6 +3229,50 @@ impl Inner {
     json!({ "averages": averages })
   }
 
+  async fn inlay_hint(
+    &mut self,
+    params: lsp_custom::InlayHintParams,
+  ) -> LspResult<Option<Vec<lsp_custom::InlayHint>>> {
+    let specifier = self.url_map.normalize_url(&params.text_document.uri);
+    let workspace_settings = self.config.get_workspace_settings();
+    if !self.is_diagnosable(&specifier)
+      || !self.config.specifier_enabled(&specifier)
+      || !workspace_settings.enabled_inlay_hints()
+    {
+      return Ok(None);
+    }
+
+    let mark = self.performance.mark("inlay_hint", Some(&params));
+    let asset_or_doc = self.get_cached_asset_or_document(&specifier)?;
+    let line_index = asset_or_doc.line_index();
+    let start = line_index.offset_tsc(params.range.start)?;
+    let end = line_index.offset_tsc(params.range.end)?;
+    let req = tsc::RequestMethod::ProvideInlayHints((
+      specifier,
+      tsc::TextSpan {
+        start,
+        length: end.saturating_sub(start),
+      },
+      (&workspace_settings.inlay_hints).into(),
+    ));
+    let maybe_inlay_hints: Option<Vec<tsc::InlayHint>> = self
+      .ts_server
+      .request(self.snapshot()?, req)
+      .await
+      .map_err(|err| {
+        error!("Unable to get inlay hints: {}", err);
+        LspError::internal_error()
+      })?;
+    let maybe_inlay_hints = maybe_inlay_hints.map(|hints| {
+      hints
+        .iter()
+        .map(|hint| hint.to_lsp(line_index.clone()))
+        .collect()
+    });
+    self.performance.measure(mark);
+    Ok(maybe_inlay_hints)
+  }
+
   async fn reload_import_registries(&mut self) -> LspResult<Option<Value>> {
     fs_util::remove_dir_all_if_exists(&self.module_registries_location)
       .await
@@ -2654,6 +3289,102 @@ impl Inner {
     Ok(Some(json!(true)))
   }
 
+  /// Run the tests the client has asked for in the test server, which reports
+  /// the progress of the run with `deno/testRunProgress` notifications.
+  async fn test_run(
+    &mut self,
+    params: lsp_custom::TestRunRequestParams,
+  ) -> LspResult<Option<Value>> {
+    let mark = self.performance.mark("test_run", Some(&params));
+    let mut modules: Vec<(ModuleSpecifier, Option<Vec<String>>)> = Vec::new();
+    for test_identifier in &params.include {
+      let specifier = self
+        .url_map
+        .normalize_url(&test_identifier.text_document.uri);
+      let maybe_name = if let Some(id) = &test_identifier.id {
+        let maybe_parsed_source = self
+          .documents
+          .get(&specifier)
+          .map(|d| d.maybe_parsed_source())
+          .flatten();
+        let maybe_definition = match maybe_parsed_source {
+          Some(Ok(parsed_source)) => {
+            testing::collect_tests(&specifier, &parsed_source)
+              .into_iter()
+              .find(|definition| definition.contains_id(id))
+          }
+          _ => None,
+        };
+        match maybe_definition {
+          Some(definition) => Some(definition.name),
+          None => {
+            warn!("Unable to find test \"{}\" in \"{}\".", id, specifier);
+            continue;
+          }
+        }
+      } else {
+        None
+      };
+      if let Some((_, maybe_names)) =
+        modules.iter_mut().find(|(s, _)| *s == specifier)
+      {
+        match maybe_name {
+          Some(name) => {
+            if let Some(names) = maybe_names {
+              names.push(name);
+            }
+          }
+          None => *maybe_names = None,
+        }
+      } else {
+        modules.push((specifier, maybe_name.map(|name| vec![name])));
+      }
+    }
+
+    // the arguments are parsed like the ones of `deno test`, so tests run from
+    // the client don't have any permissions unless they are granted there
+    let workspace_settings = self.config.get_workspace_settings();
+    let mut args = vec!["deno".to_string(), "test".to_string()];
+    args.extend(workspace_settings.testing.args);
+    let test_flags = flags::flags_from_vec(args).map_err(|err| {
+      LspError::invalid_params(format!(
+        "Invalid \"deno.testing.args\": {}",
+        err.message
+      ))
+    })?;
+    let flags = Flags {
+      cache_path: self.maybe_cache_path.clone(),
+      config_path: self
+        .maybe_config_file
+        .as_ref()
+        .map(|cf| fs_util::specifier_to_file_path(&cf.specifier).ok())
+        .flatten()
+        .map(|path| path.to_string_lossy().to_string()),
+      import_map_path: self
+        .maybe_import_map_uri
+        .as_ref()
+        .map(|uri| uri.to_string()),
+      unstable: workspace_settings.unstable || test_flags.unstable,
+      ..test_flags
+    };
+    if self.maybe_test_server.is_none() {
+      self.maybe_test_server = Some(TestServer::new(self.client.clone()));
+    }
+    let test_server = self.maybe_test_server.as_ref().unwrap();
+    test_server
+      .run(TestRun {
+        id: params.id,
+        flags,
+        modules,
+      })
+      .map_err(|err| {
+        error!("{}", err);
+        LspError::internal_error()
+      })?;
+    self.performance.measure(mark);
+    Ok(Some(json!(true)))
+  }
+
   async fn virtual_text_document(
     &mut self,
     params: lsp_custom::VirtualTextDocumentParams,
@@ -1,13 +1,20 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
+use super::testing::TestDefinition;
+use crate::tools::test::TestEvent;
+
 use deno_core::serde::Deserialize;
 use deno_core::serde::Serialize;
 use lspower::lsp;
 
 pub const CACHE_REQUEST: &str = "deno/cache";
+/// The inlay hint request from version 3.17 of the protocol, which isn't
+/// supported by the version of `lsp-types` we use yet.
+pub const INLAY_HINT_REQUEST: &str = "textDocument/inlayHint";
 pub const PERFORMANCE_REQUEST: &str = "deno/performance";
 pub const RELOAD_IMPORT_REGISTRIES_REQUEST: &str =
   "deno/reloadImportRegistries";
+pub const TEST_RUN_REQUEST: &str = "deno/testRun";
 pub const VIRTUAL_TEXT_DOCUMENT: &str = "deno/virtualTextDocument";
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -21,6 +28,36 @@ pub struct CacheParams {
   pub uris: Vec<lsp::TextDocumentIdentifier>,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlayHintParams {
+  pub text_document: lsp::TextDocumentIdentifier,
+  /// The visible range of the document that hints should be provided for.
+  pub range: lsp::Range,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct InlayHintKind(i32);
+
+impl InlayHintKind {
+  pub const TYPE: InlayHintKind = InlayHintKind(1);
+  pub const PARAMETER: InlayHintKind = InlayHintKind(2);
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlayHint {
+  pub position: lsp::Position,
+  pub label: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub kind: Option<InlayHintKind>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub padding_left: Option<bool>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub padding_right: Option<bool>,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct RegistryStateNotificationParams {
   pub origin: String,
@@ -40,3 +77,68 @@ impl lsp::notification::Notification for RegistryStateNotification {
 pub struct VirtualTextDocumentParams {
   pub text_document: lsp::TextDocumentIdentifier,
 }
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestModuleParams {
+  pub text_document: lsp::TextDocumentIdentifier,
+  /// All of the tests defined in the module, replacing any tests previously
+  /// sent for it.
+  pub tests: Vec<TestDefinition>,
+}
+
+pub enum TestModuleNotification {}
+
+impl lsp::notification::Notification for TestModuleNotification {
+  type Params = TestModuleParams;
+
+  const METHOD: &'static str = "deno/testModule";
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestIdentifier {
+  pub text_document: lsp::TextDocumentIdentifier,
+  /// The id of a test or test step of the module.  When there is no id, all of
+  /// the tests of the module are run.  Steps can't be run on their own, so the
+  /// test the step belongs to is run instead.
+  pub id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestRunRequestParams {
+  /// An id chosen by the client, which is used to identify the progress
+  /// notifications of the run.
+  pub id: u32,
+  pub include: Vec<TestIdentifier>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+pub enum TestRunProgressMessage {
+  /// An event reported by the test runner.
+  Event(TestEvent),
+  /// A module failed to load or run.
+  Error(String),
+  /// The run has finished and no more notifications will be sent for it.
+  End,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestRunProgressParams {
+  pub id: u32,
+  /// The id of the test or test step the message is about, if any.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub test_id: Option<String>,
+  pub message: TestRunProgressMessage,
+}
+
+pub enum TestRunProgressNotification {}
+
+impl lsp::notification::Notification for TestRunProgressNotification {
+  type Params = TestRunProgressParams;
+
+  const METHOD: &'static str = "deno/testRunProgress";
+}
@@ -13,8 +13,11 @@ mod capabilities;
 mod code_lens;
 mod completions;
 mod config;
+mod config_documents;
 mod diagnostics;
 mod documents;
+mod file_rename;
+mod formatting;
 pub(crate) mod language_server;
 mod lsp_custom;
 mod parent_process_checker;
@@ -23,6 +26,7 @@ mod performance;
 mod refactor;
 mod registries;
 mod semantic_tokens;
+mod testing;
 mod text;
 mod tsc;
 mod urls;
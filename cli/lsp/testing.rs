@@ -0,0 +1,669 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+//! Support for editors which integrate tests into their UI. Test definitions
+//! are discovered from the AST of documents and published to the client, and
+//! tests the client asks for are run in-process by the [TestServer], which
+//! streams the test events back to the client as they happen.
+
+use super::documents::span_to_range;
+use super::lsp_custom;
+
+use crate::checksum;
+use crate::emit;
+use crate::flags::Flags;
+use crate::proc_state::ProcState;
+use crate::tools::test;
+use crate::tools::test::TestEvent;
+
+use deno_ast::swc::ast;
+use deno_ast::swc::common::Span;
+use deno_ast::swc::visit::Visit;
+use deno_ast::swc::visit::VisitWith;
+use deno_ast::ParsedSource;
+use deno_core::anyhow::anyhow;
+use deno_core::error::AnyError;
+use deno_core::serde::Deserialize;
+use deno_core::serde::Serialize;
+use deno_core::ModuleSpecifier;
+use deno_runtime::permissions::Permissions;
+use deno_runtime::tokio_util::create_basic_runtime;
+use deno_runtime::tokio_util::run_basic;
+use log::error;
+use lspower::lsp;
+use std::collections::HashSet;
+use std::sync::mpsc::channel;
+use std::thread;
+use tokio::sync::mpsc;
+
+/// A test or test step which was discovered in a module.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestDefinition {
+  /// An identifier of the test which is stable across changes to the module,
+  /// as long as the test isn't renamed.
+  pub id: String,
+  pub name: String,
+  /// The range of the `test` or `step` identifier of the call which defines
+  /// the test.
+  pub range: lsp::Range,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub steps: Vec<TestDefinition>,
+}
+
+impl TestDefinition {
+  /// Determine if the supplied id is the id of this test or of one of its
+  /// steps.
+  pub fn contains_id(&self, id: &str) -> bool {
+    self.id == id || self.steps.iter().any(|step| step.contains_id(id))
+  }
+}
+
+/// The id of a test, where the origin is the specifier of the test module.
+pub fn test_id(origin: &str, name: &str) -> String {
+  checksum::gen(&[origin.as_bytes(), name.as_bytes()])
+}
+
+/// The id of a test step.  The runtime only reports the level of a step and
+/// not its parent steps, so the level is used to tell nested steps apart.
+pub fn step_id(test_id: &str, level: usize, name: &str) -> String {
+  checksum::gen(&[
+    test_id.as_bytes(),
+    level.to_string().as_bytes(),
+    name.as_bytes(),
+  ])
+}
+
+/// The function which implements a test or a test step.
+enum TestFn<'a> {
+  Arrow(&'a ast::ArrowExpr),
+  Function(&'a ast::Function),
+}
+
+impl TestFn<'_> {
+  /// The name of the parameter which receives the test context.
+  fn context_name(&self) -> Option<String> {
+    let maybe_pat = match self {
+      TestFn::Arrow(arrow_expr) => arrow_expr.params.get(0),
+      TestFn::Function(function) => function.params.get(0).map(|p| &p.pat),
+    };
+    if let Some(ast::Pat::Ident(binding_ident)) = maybe_pat {
+      Some(binding_ident.id.sym.to_string())
+    } else {
+      None
+    }
+  }
+
+  fn visit_with<V: Visit>(&self, visitor: &mut V) {
+    match self {
+      TestFn::Arrow(arrow_expr) => arrow_expr.visit_with(visitor),
+      TestFn::Function(function) => function.visit_with(visitor),
+    }
+  }
+}
+
+fn as_test_fn(expr: &ast::Expr) -> Option<TestFn> {
+  match expr {
+    ast::Expr::Arrow(arrow_expr) => Some(TestFn::Arrow(arrow_expr)),
+    ast::Expr::Fn(fn_expr) => Some(TestFn::Function(&fn_expr.function)),
+    ast::Expr::Paren(paren_expr) => as_test_fn(&paren_expr.expr),
+    _ => None,
+  }
+}
+
+/// Get the name and the function of a test from the arguments of a
+/// `Deno.test()` or `t.step()` call, which can either be a name and a
+/// function, a named function or a definition object.
+fn get_test_args(
+  args: &[ast::ExprOrSpread],
+) -> Option<(String, Option<TestFn>)> {
+  match args.get(0).map(|es| es.expr.as_ref())? {
+    ast::Expr::Lit(ast::Lit::Str(lit_str)) => Some((
+      lit_str.value.to_string(),
+      args.get(1).map(|es| as_test_fn(&es.expr)).flatten(),
+    )),
+    ast::Expr::Fn(fn_expr) => fn_expr.ident.as_ref().map(|ident| {
+      (
+        ident.sym.to_string(),
+        Some(TestFn::Function(&fn_expr.function)),
+      )
+    }),
+    ast::Expr::Object(obj_lit) => {
+      let mut maybe_name = None;
+      let mut maybe_test_fn = None;
+      for prop in &obj_lit.props {
+        if let ast::PropOrSpread::Prop(prop) = prop {
+          match prop.as_ref() {
+            ast::Prop::KeyValue(key_value_prop) => {
+              if let ast::PropName::Ident(ident) = &key_value_prop.key {
+                match ident.sym.to_string().as_str() {
+                  "name" => {
+                    if let ast::Expr::Lit(ast::Lit::Str(lit_str)) =
+                      key_value_prop.value.as_ref()
+                    {
+                      maybe_name = Some(lit_str.value.to_string());
+                    }
+                  }
+                  "fn" => {
+                    maybe_test_fn = as_test_fn(&key_value_prop.value);
+                  }
+                  _ => (),
+                }
+              }
+            }
+            ast::Prop::Method(method_prop) => {
+              if let ast::PropName::Ident(ident) = &method_prop.key {
+                if ident.sym.to_string() == "fn" {
+                  maybe_test_fn = Some(TestFn::Function(&method_prop.function));
+                }
+              }
+            }
+            _ => (),
+          }
+        }
+      }
+      maybe_name.map(|name| (name, maybe_test_fn))
+    }
+    _ => None,
+  }
+}
+
+/// Collects the `step()` calls made on the test context of a test or step.
+struct StepCollector<'a> {
+  context_name: String,
+  level: usize,
+  parsed_source: &'a ParsedSource,
+  steps: Vec<TestDefinition>,
+  test_id: &'a str,
+}
+
+impl<'a> StepCollector<'a> {
+  fn collect(
+    test_fn: &TestFn,
+    level: usize,
+    test_id: &'a str,
+    parsed_source: &'a ParsedSource,
+  ) -> Vec<TestDefinition> {
+    if let Some(context_name) = test_fn.context_name() {
+      let mut collector = Self {
+        context_name,
+        level,
+        parsed_source,
+        steps: Vec::new(),
+        test_id,
+      };
+      test_fn.visit_with(&mut collector);
+      collector.steps
+    } else {
+      Vec::new()
+    }
+  }
+}
+
+impl Visit for StepCollector<'_> {
+  fn visit_call_expr(&mut self, node: &ast::CallExpr) {
+    if let ast::ExprOrSuper::Expr(callee_expr) = &node.callee {
+      if let ast::Expr::Member(member_expr) = callee_expr.as_ref() {
+        if let ast::Expr::Ident(prop_ident) = member_expr.prop.as_ref() {
+          if prop_ident.sym.to_string() == "step" {
+            if let ast::ExprOrSuper::Expr(obj_expr) = &member_expr.obj {
+              if let ast::Expr::Ident(obj_ident) = obj_expr.as_ref() {
+                if obj_ident.sym.to_string() == self.context_name {
+                  if let Some((name, maybe_test_fn)) = get_test_args(&node.args)
+                  {
+                    let steps = maybe_test_fn
+                      .map(|test_fn| {
+                        Self::collect(
+                          &test_fn,
+                          self.level + 1,
+                          self.test_id,
+                          self.parsed_source,
+                        )
+                      })
+                      .unwrap_or_default();
+                    self.steps.push(TestDefinition {
+                      id: step_id(self.test_id, self.level, &name),
+                      name,
+                      range: span_to_range(
+                        &prop_ident.span,
+                        self.parsed_source,
+                      ),
+                      steps,
+                    });
+                    // the steps of the step have already been collected
+                    return;
+                  }
+                }
+              }
+            }
+          }
+        }
+      }
+    }
+    node.visit_children_with(self);
+  }
+}
+
+/// Collects the `Deno.test()` calls of a module, including calls via
+/// variables `Deno.test` has been assigned or destructured to.
+struct TestCollector {
+  definitions: Vec<TestDefinition>,
+  parsed_source: ParsedSource,
+  specifier: ModuleSpecifier,
+  test_vars: HashSet<String>,
+}
+
+impl TestCollector {
+  fn new(specifier: ModuleSpecifier, parsed_source: ParsedSource) -> Self {
+    Self {
+      definitions: Vec::new(),
+      parsed_source,
+      specifier,
+      test_vars: HashSet::new(),
+    }
+  }
+
+  fn check_call_expr(&mut self, node: &ast::CallExpr, span: &Span) {
+    if let Some((name, maybe_test_fn)) = get_test_args(&node.args) {
+      let id = test_id(self.specifier.as_str(), &name);
+      let steps = maybe_test_fn
+        .map(|test_fn| {
+          StepCollector::collect(&test_fn, 1, &id, &self.parsed_source)
+        })
+        .unwrap_or_default();
+      self.definitions.push(TestDefinition {
+        id,
+        name,
+        range: span_to_range(span, &self.parsed_source),
+        steps,
+      });
+    }
+  }
+
+  /// Move out the test definitions from the collector.
+  fn take(self) -> Vec<TestDefinition> {
+    self.definitions
+  }
+}
+
+impl Visit for TestCollector {
+  fn visit_call_expr(&mut self, node: &ast::CallExpr) {
+    if let ast::ExprOrSuper::Expr(callee_expr) = &node.callee {
+      match callee_expr.as_ref() {
+        ast::Expr::Ident(ident) => {
+          if self.test_vars.contains(&ident.sym.to_string()) {
+            self.check_call_expr(node, &ident.span);
+          }
+        }
+        ast::Expr::Member(member_expr) => {
+          if let ast::Expr::Ident(ns_prop_ident) = member_expr.prop.as_ref() {
+            if ns_prop_ident.sym.to_string() == "test" {
+              if let ast::ExprOrSuper::Expr(obj_expr) = &member_expr.obj {
+                if let ast::Expr::Ident(ident) = obj_expr.as_ref() {
+                  if ident.sym.to_string() == "Deno" {
+                    self.check_call_expr(node, &ns_prop_ident.span);
+                  }
+                }
+              }
+            }
+          }
+        }
+        _ => (),
+      }
+    }
+  }
+
+  fn visit_var_decl(&mut self, node: &ast::VarDecl) {
+    for decl in &node.decls {
+      if let Some(init) = &decl.init {
+        match init.as_ref() {
+          // Identify destructured assignments of `test` from `Deno`
+          ast::Expr::Ident(ident) => {
+            if ident.sym.to_string() == "Deno" {
+              if let ast::Pat::Object(object_pat) = &decl.name {
+                for prop in &object_pat.props {
+                  match prop {
+                    ast::ObjectPatProp::Assign(prop) => {
+                      let name = prop.key.sym.to_string();
+                      if name == "test" {
+                        self.test_vars.insert(name);
+                      }
+                    }
+                    ast::ObjectPatProp::KeyValue(prop) => {
+                      if let ast::PropName::Ident(key_ident) = &prop.key {
+                        if key_ident.sym.to_string() == "test" {
+                          if let ast::Pat::Ident(value_ident) =
+                            &prop.value.as_ref()
+                          {
+                            self
+                              .test_vars
+                              .insert(value_ident.id.sym.to_string());
+                          }
+                        }
+                      }
+                    }
+                    _ => (),
+                  }
+                }
+              }
+            }
+          }
+          // Identify variable assignments where the init is `Deno.test`
+          ast::Expr::Member(member_expr) => {
+            if let ast::ExprOrSuper::Expr(expr) = &member_expr.obj {
+              if let ast::Expr::Ident(obj_ident) = expr.as_ref() {
+                if obj_ident.sym.to_string() == "Deno" {
+                  if let ast::Expr::Ident(prop_ident) =
+                    &member_expr.prop.as_ref()
+                  {
+                    if prop_ident.sym.to_string() == "test" {
+                      if let ast::Pat::Ident(binding_ident) = &decl.name {
+                        self.test_vars.insert(binding_ident.id.sym.to_string());
+                      }
+                    }
+                  }
+                }
+              }
+            }
+          }
+          _ => (),
+        }
+      }
+    }
+  }
+}
+
+/// Collect the tests, and their steps, defined in a module.
+pub fn collect_tests(
+  specifier: &ModuleSpecifier,
+  parsed_source: &ParsedSource,
+) -> Vec<TestDefinition> {
+  let mut collector =
+    TestCollector::new(specifier.clone(), parsed_source.clone());
+  parsed_source.module().visit_with(&mut collector);
+  collector.take()
+}
+
+/// A request to run tests, identified by an id chosen by the client.
+#[derive(Debug)]
+pub(crate) struct TestRun {
+  pub id: u32,
+  pub flags: Flags,
+  /// The modules to test, along with the names of the tests to run, where
+  /// `None` runs all the tests of the module.
+  pub modules: Vec<(ModuleSpecifier, Option<Vec<String>>)>,
+}
+
+/// Determine which test or step a test event is about.
+fn get_event_test_id(event: &TestEvent) -> Option<String> {
+  match event {
+    TestEvent::Wait(description) | TestEvent::Result(description, _, _) => {
+      Some(test_id(&description.origin, &description.name))
+    }
+    TestEvent::StepWait(description)
+    | TestEvent::StepResult(description, _, _) => Some(step_id(
+      &test_id(&description.test.origin, &description.test.name),
+      description.level,
+      &description.name,
+    )),
+    _ => None,
+  }
+}
+
+async fn send_progress(
+  client: &lspower::Client,
+  id: u32,
+  test_id: Option<String>,
+  message: lsp_custom::TestRunProgressMessage,
+) {
+  client
+    .send_custom_notification::<lsp_custom::TestRunProgressNotification>(
+      lsp_custom::TestRunProgressParams {
+        id,
+        test_id,
+        message,
+      },
+    )
+    .await;
+}
+
+async fn execute_test_run(
+  client: &lspower::Client,
+  run: TestRun,
+) -> Result<(), AnyError> {
+  let ps = ProcState::build(run.flags.clone()).await?;
+  let permissions = Permissions::from_options(&run.flags.clone().into());
+  let lib = if run.flags.unstable {
+    emit::TypeLib::UnstableDenoWindow
+  } else {
+    emit::TypeLib::DenoWindow
+  };
+  test::check_specifiers(
+    ps.clone(),
+    permissions.clone(),
+    run
+      .modules
+      .iter()
+      .map(|(specifier, _)| (specifier.clone(), test::TestMode::Executable))
+      .collect(),
+    lib,
+  )
+  .await?;
+
+  for (specifier, maybe_names) in run.modules {
+    let (sender, receiver) = channel::<TestEvent>();
+    let (event_tx, mut event_rx) = mpsc::unbounded_channel::<TestEvent>();
+    let join_handle = {
+      let ps = ps.clone();
+      let permissions = permissions.clone();
+      let specifier = specifier.clone();
+      thread::spawn(move || {
+        run_basic(test::test_specifier(
+          ps,
+          permissions,
+          specifier,
+          test::TestMode::Executable,
+          None,
+          maybe_names,
+          None,
+          None,
+          false,
+          sender,
+        ))
+      })
+    };
+
+    // the events are forwarded from a blocking task, so that waiting for them
+    // doesn't stall the runtime of the test server, and the channel closes
+    // once the worker running the module is dropped
+    let forward_handle = tokio::task::spawn_blocking(move || {
+      for event in receiver.iter() {
+        if event_tx.send(event).is_err() {
+          break;
+        }
+      }
+      join_handle.join()
+    });
+    while let Some(event) = event_rx.recv().await {
+      let test_id = get_event_test_id(&event);
+      send_progress(
+        client,
+        run.id,
+        test_id,
+        lsp_custom::TestRunProgressMessage::Event(event),
+      )
+      .await;
+    }
+
+    let result = forward_handle.await?.map_err(|_| {
+      anyhow!("The test runner for \"{}\" panicked.", specifier)
+    })?;
+    if let Err(err) = result {
+      send_progress(
+        client,
+        run.id,
+        None,
+        lsp_custom::TestRunProgressMessage::Error(format!(
+          "{}: {}",
+          specifier, err
+        )),
+      )
+      .await;
+    }
+  }
+
+  Ok(())
+}
+
+/// A "server" that handles requests from the language server to run tests in
+/// its own thread.
+#[derive(Debug)]
+pub(crate) struct TestServer(mpsc::UnboundedSender<TestRun>);
+
+impl TestServer {
+  pub fn new(client: lspower::Client) -> Self {
+    let (tx, mut rx) = mpsc::unbounded_channel::<TestRun>();
+    let _join_handle = thread::spawn(move || {
+      let runtime = create_basic_runtime();
+      runtime.block_on(async {
+        while let Some(run) = rx.recv().await {
+          let id = run.id;
+          if let Err(err) = execute_test_run(&client, run).await {
+            error!("Test run {} failed: {}", id, err);
+            send_progress(
+              &client,
+              id,
+              None,
+              lsp_custom::TestRunProgressMessage::Error(err.to_string()),
+            )
+            .await;
+          }
+          send_progress(
+            &client,
+            id,
+            None,
+            lsp_custom::TestRunProgressMessage::End,
+          )
+          .await;
+        }
+      })
+    });
+
+    Self(tx)
+  }
+
+  /// Enqueue a test run, where progress of the run is sent to the client as
+  /// notifications.
+  pub fn run(&self, run: TestRun) -> Result<(), AnyError> {
+    self
+      .0
+      .send(run)
+      .map_err(|_| anyhow!("failed to send request to test thread"))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use deno_ast::MediaType;
+  use deno_ast::SourceTextInfo;
+  use deno_core::resolve_url;
+  use std::sync::Arc;
+
+  fn range(
+    start_line: u32,
+    start_character: u32,
+    end_line: u32,
+    end_character: u32,
+  ) -> lsp::Range {
+    lsp::Range {
+      start: lsp::Position {
+        line: start_line,
+        character: start_character,
+      },
+      end: lsp::Position {
+        line: end_line,
+        character: end_character,
+      },
+    }
+  }
+
+  #[test]
+  fn test_collect_tests() {
+    let specifier = resolve_url("file:///a/mod_test.ts").unwrap();
+    let source = Arc::new(
+      r#"
+      Deno.test({
+        name: "test a",
+        async fn(t) {
+          await t.step("step a", async (t2) => {
+            await t2.step({ name: "step b", fn() {} });
+          });
+        }
+      });
+
+      const { test } = Deno;
+      test("test b", () => {});
+    "#
+      .to_string(),
+    );
+    let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+      specifier: specifier.to_string(),
+      source: SourceTextInfo::new(source),
+      media_type: MediaType::TypeScript,
+      capture_tokens: true,
+      scope_analysis: true,
+      maybe_syntax: None,
+    })
+    .unwrap();
+    let test_a_id = test_id(specifier.as_str(), "test a");
+    let step_a_id = step_id(&test_a_id, 1, "step a");
+    assert_eq!(
+      collect_tests(&specifier, &parsed_source),
+      vec![
+        TestDefinition {
+          id: test_a_id.clone(),
+          name: "test a".to_string(),
+          range: range(1, 11, 1, 15),
+          steps: vec![TestDefinition {
+            id: step_a_id,
+            name: "step a".to_string(),
+            range: range(4, 18, 4, 22),
+            steps: vec![TestDefinition {
+              id: step_id(&test_a_id, 2, "step b"),
+              name: "step b".to_string(),
+              range: range(5, 21, 5, 25),
+              steps: vec![],
+            }],
+          }],
+        },
+        TestDefinition {
+          id: test_id(specifier.as_str(), "test b"),
+          name: "test b".to_string(),
+          range: range(11, 6, 11, 10),
+          steps: vec![],
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn test_get_event_test_id() {
+    let description = test::TestDescription {
+      origin: "file:///a/mod_test.ts".to_string(),
+      name: "test a".to_string(),
+    };
+    let id = test_id("file:///a/mod_test.ts", "test a");
+    assert_eq!(
+      get_event_test_id(&TestEvent::Wait(description.clone())),
+      Some(id.clone())
+    );
+    assert_eq!(
+      get_event_test_id(&TestEvent::StepWait(test::TestStepDescription {
+        test: description,
+        level: 1,
+        name: "step a".to_string(),
+      })),
+      Some(step_id(&id, 1, "step a"))
+    );
+  }
+}
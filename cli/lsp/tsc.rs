@@ -4,6 +4,7 @@ use super::code_lens;
 use super::config;
 use super::language_server;
 use super::language_server::StateSnapshot;
+use super::lsp_custom;
 use super::refactor::RefactorCodeActionData;
 use super::refactor::ALL_KNOWN_REFACTOR_ACTION_KINDS;
 use super::refactor::EXTRACT_CONSTANT;
@@ -1977,6 +1978,46 @@ pub enum OutliningSpanKind {
   Imports,
 }
 
+#[derive(Debug, Deserialize)]
+pub enum InlayHintKind {
+  Type,
+  Parameter,
+  Enum,
+}
+
+impl InlayHintKind {
+  pub fn to_lsp(&self) -> Option<lsp_custom::InlayHintKind> {
+    match self {
+      Self::Parameter => Some(lsp_custom::InlayHintKind::PARAMETER),
+      Self::Type => Some(lsp_custom::InlayHintKind::TYPE),
+      // the protocol has no kind for enum member values
+      Self::Enum => None,
+    }
+  }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlayHint {
+  pub text: String,
+  pub position: u32,
+  pub kind: InlayHintKind,
+  pub whitespace_before: Option<bool>,
+  pub whitespace_after: Option<bool>,
+}
+
+impl InlayHint {
+  pub fn to_lsp(&self, line_index: Arc<LineIndex>) -> lsp_custom::InlayHint {
+    lsp_custom::InlayHint {
+      position: line_index.position_tsc(self.position.into()),
+      label: self.text.clone(),
+      kind: self.kind.to_lsp(),
+      padding_left: self.whitespace_before,
+      padding_right: self.whitespace_after,
+    }
+  }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OutliningSpan {
@@ -2480,6 +2521,9 @@ fn op_script_names(
   state: &mut State,
   _args: Value,
 ) -> Result<Vec<ModuleSpecifier>, AnyError> {
+  let mut seen = HashSet::new();
+  // the workspace files are only populated when workspace diagnostics are
+  // enabled, in which case tsc needs to know about the closed modules too
   Ok(
     state
       .state_snapshot
@@ -2487,6 +2531,8 @@ fn op_script_names(
       .documents(true, true)
       .into_iter()
       .map(|d| d.specifier().clone())
+      .chain(state.state_snapshot.workspace_files.iter().cloned())
+      .filter(|s| seen.insert(s.clone()))
       .collect(),
   )
 }
@@ -2611,6 +2657,26 @@ pub enum IncludePackageJsonAutoImports {
   Off,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IncludeInlayParameterNameHints {
+  None,
+  Literals,
+  All,
+}
+
+impl From<&config::InlayHintsParamNamesEnabled>
+  for IncludeInlayParameterNameHints
+{
+  fn from(setting: &config::InlayHintsParamNamesEnabled) -> Self {
+    match setting {
+      config::InlayHintsParamNamesEnabled::None => Self::None,
+      config::InlayHintsParamNamesEnabled::Literals => Self::Literals,
+      config::InlayHintsParamNamesEnabled::All => Self::All,
+    }
+  }
+}
+
 #[derive(Debug, Default, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetCompletionsAtPositionOptions {
@@ -2652,6 +2718,47 @@ pub struct UserPreferences {
   pub include_package_json_auto_imports: Option<IncludePackageJsonAutoImports>,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub provide_refactor_not_applicable_reason: Option<bool>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub include_inlay_parameter_name_hints:
+    Option<IncludeInlayParameterNameHints>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub include_inlay_parameter_name_hints_when_argument_matches_name:
+    Option<bool>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub include_inlay_function_parameter_type_hints: Option<bool>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub include_inlay_variable_type_hints: Option<bool>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub include_inlay_property_declaration_type_hints: Option<bool>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub include_inlay_function_like_return_type_hints: Option<bool>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub include_inlay_enum_member_value_hints: Option<bool>,
+}
+
+impl From<&config::InlayHintsSettings> for UserPreferences {
+  fn from(settings: &config::InlayHintsSettings) -> Self {
+    Self {
+      include_inlay_parameter_name_hints: Some(
+        (&settings.parameter_names).into(),
+      ),
+      include_inlay_parameter_name_hints_when_argument_matches_name: Some(
+        settings.parameter_names_when_argument_matches_name,
+      ),
+      include_inlay_function_parameter_type_hints: Some(
+        settings.parameter_types,
+      ),
+      include_inlay_variable_type_hints: Some(settings.variable_types),
+      include_inlay_property_declaration_type_hints: Some(
+        settings.property_declaration_types,
+      ),
+      include_inlay_function_like_return_type_hints: Some(
+        settings.function_like_return_types,
+      ),
+      include_inlay_enum_member_value_hints: Some(settings.enum_member_values),
+      ..Default::default()
+    }
+  }
 }
 
 #[derive(Debug, Serialize)]
@@ -2778,12 +2885,17 @@ pub enum RequestMethod {
     specifier: ModuleSpecifier,
     position: u32,
   },
+  /// Sort, merge and remove unused imports of a module. When the flag is set,
+  /// unused imports are kept.
+  OrganizeImports((ModuleSpecifier, bool)),
   /// Resolve a call hierarchy item for a specific position.
   PrepareCallHierarchy((ModuleSpecifier, u32)),
   /// Resolve incoming call hierarchy items for a specific position.
   ProvideCallHierarchyIncomingCalls((ModuleSpecifier, u32)),
   /// Resolve outgoing call hierarchy items for a specific position.
   ProvideCallHierarchyOutgoingCalls((ModuleSpecifier, u32)),
+  /// Get the inlay hints for a span of a file.
+  ProvideInlayHints((ModuleSpecifier, TextSpan, UserPreferences)),
 }
 
 impl RequestMethod {
@@ -2968,6 +3080,15 @@ impl RequestMethod {
         "specifier": state.denormalize_specifier(specifier),
         "position": position
       }),
+      RequestMethod::OrganizeImports((
+        specifier,
+        skip_destructive_code_actions,
+      )) => json!({
+        "id": id,
+        "method": "organizeImports",
+        "specifier": state.denormalize_specifier(specifier),
+        "skipDestructiveCodeActions": skip_destructive_code_actions,
+      }),
       RequestMethod::PrepareCallHierarchy((specifier, position)) => {
         json!({
           "id": id,
@@ -2998,6 +3119,15 @@ impl RequestMethod {
           "position": position
         })
       }
+      RequestMethod::ProvideInlayHints((specifier, span, preferences)) => {
+        json!({
+          "id": id,
+          "method": "provideInlayHints",
+          "specifier": state.denormalize_specifier(specifier),
+          "span": span,
+          "preferences": preferences,
+        })
+      }
     }
   }
 }
@@ -20,6 +20,7 @@ mod fmt_errors;
 mod fs_util;
 mod http_cache;
 mod http_util;
+mod incremental_cache;
 mod lockfile;
 mod logger;
 mod lsp;
@@ -35,9 +36,12 @@ mod tsc;
 mod unix_util;
 mod version;
 mod windows_util;
+mod xml_util;
 
+use crate::diagnostics::Diagnostics;
 use crate::file_fetcher::File;
 use crate::file_watcher::ResolutionResult;
+use crate::flags::BenchFlags;
 use crate::flags::BundleFlags;
 use crate::flags::CacheFlags;
 use crate::flags::CheckFlag;
@@ -45,6 +49,7 @@ use crate::flags::CompileFlags;
 use crate::flags::CompletionsFlags;
 use crate::flags::CoverageFlags;
 use crate::flags::DenoSubcommand;
+use crate::flags::DiagnosticsFormat;
 use crate::flags::DocFlags;
 use crate::flags::EvalFlags;
 use crate::flags::Flags;
@@ -577,7 +582,8 @@ async fn lint_command(
     None
   };
 
-  tools::lint::lint(maybe_lint_config, lint_flags, flags.watch).await?;
+  tools::lint::lint(maybe_lint_config, lint_flags, flags.watch, &ps.dir)
+    .await?;
   Ok(0)
 }
 
@@ -919,6 +925,8 @@ async fn doc_command(
     doc_flags.json,
     doc_flags.filter,
     doc_flags.private,
+    doc_flags.html,
+    doc_flags.lint,
   )
   .await?;
   Ok(0)
@@ -943,7 +951,7 @@ async fn format_command(
     return Ok(0);
   }
 
-  tools::fmt::format(fmt_flags, flags.watch, maybe_fmt_config).await?;
+  tools::fmt::format(fmt_flags, flags.watch, maybe_fmt_config, &ps.dir).await?;
   Ok(0)
 }
 
@@ -1270,15 +1278,46 @@ async fn coverage_command(
     return Err(generic_error("No matching coverage profiles found"));
   }
 
+  let reporter_kind = if coverage_flags.lcov {
+    tools::coverage::CoverageReporterKind::Lcov
+  } else if coverage_flags.cobertura {
+    tools::coverage::CoverageReporterKind::Cobertura
+  } else if let Some(output_dir) = coverage_flags.html {
+    tools::coverage::CoverageReporterKind::Html(output_dir)
+  } else {
+    tools::coverage::CoverageReporterKind::Pretty
+  };
+
   tools::coverage::cover_files(
     flags.clone(),
     coverage_flags.files,
     coverage_flags.ignore,
     coverage_flags.include,
     coverage_flags.exclude,
-    coverage_flags.lcov,
+    reporter_kind,
+    config_file::CoverageThresholds {
+      lines: coverage_flags.min_lines,
+      branches: coverage_flags.min_branches,
+      functions: coverage_flags.min_functions,
+    },
+  )
+  .await?;
+  Ok(0)
+}
+
+async fn bench_command(
+  flags: Flags,
+  bench_flags: BenchFlags,
+) -> Result<i32, AnyError> {
+  tools::bench::run_benchmarks(
+    flags,
+    bench_flags.include,
+    bench_flags.ignore,
+    bench_flags.filter,
+    bench_flags.json,
   )
   .await?;
+
   Ok(0)
 }
 
@@ -1304,7 +1343,12 @@ async fn test_command(
       test_flags.fail_fast,
       test_flags.filter,
       test_flags.shuffle,
+      test_flags.rerun_failed,
+      test_flags.timeout,
+      test_flags.update_snapshots,
       test_flags.concurrent_jobs,
+      test_flags.reporter,
+      test_flags.reporter_output,
     )
     .await?;
 
@@ -1321,7 +1365,14 @@ async fn test_command(
     test_flags.allow_none,
     test_flags.filter,
     test_flags.shuffle,
+    test_flags.shard,
+    test_flags.shard_by_duration,
+    test_flags.rerun_failed,
+    test_flags.timeout,
+    test_flags.update_snapshots,
     test_flags.concurrent_jobs,
+    test_flags.reporter,
+    test_flags.reporter_output,
   )
   .await?;
 
@@ -1356,6 +1407,9 @@ fn get_subcommand(
   flags: Flags,
 ) -> Pin<Box<dyn Future<Output = Result<i32, AnyError>>>> {
   match flags.clone().subcommand {
+    DenoSubcommand::Bench(bench_flags) => {
+      bench_command(flags, bench_flags).boxed_local()
+    }
     DenoSubcommand::Bundle(bundle_flags) => {
       bundle_command(flags, bundle_flags).boxed_local()
     }
@@ -1455,6 +1509,22 @@ fn unwrap_or_exit<T>(result: Result<T, AnyError>) -> T {
   }
 }
 
+/// Prints type checking diagnostics to stdout and exits when they were
+/// requested in a machine readable format.
+fn report_diagnostics(error: &AnyError, format: DiagnosticsFormat) {
+  let diagnostics = match error.downcast_ref::<Diagnostics>() {
+    Some(diagnostics) => diagnostics,
+    None => return,
+  };
+  let document = match format {
+    DiagnosticsFormat::Pretty => return,
+    DiagnosticsFormat::Json => diagnostics.to_json(),
+    DiagnosticsFormat::Sarif => diagnostics.to_sarif(),
+  };
+  println!("{}", serde_json::to_string_pretty(&document).unwrap());
+  std::process::exit(1);
+}
+
 pub fn main() {
   setup_exit_process_panic_hook();
 
@@ -1494,7 +1564,12 @@ pub fn main() {
 
   logger::init(flags.log_level);
 
-  let exit_code = unwrap_or_exit(run_basic(get_subcommand(flags)));
+  let diagnostics_format = flags.diagnostics_format;
+  let result = run_basic(get_subcommand(flags));
+  if let Err(error) = &result {
+    report_diagnostics(error, diagnostics_format);
+  }
+  let exit_code = unwrap_or_exit(result);
 
   std::process::exit(exit_code);
 }
@@ -0,0 +1,57 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+use crate::tools::bench::BenchEvent;
+use deno_core::error::AnyError;
+use deno_core::JsRuntime;
+use deno_core::ModuleSpecifier;
+use deno_core::OpState;
+use std::sync::mpsc::Sender;
+use std::time::Instant;
+
+pub fn init(rt: &mut JsRuntime) {
+  super::reg_sync(
+    rt,
+    "op_pledge_test_permissions",
+    super::testing::op_pledge_test_permissions,
+  );
+  super::reg_sync(
+    rt,
+    "op_restore_test_permissions",
+    super::testing::op_restore_test_permissions,
+  );
+  super::reg_sync(rt, "op_get_bench_origin", op_get_bench_origin);
+  super::reg_sync(rt, "op_dispatch_bench_event", op_dispatch_bench_event);
+  super::reg_sync(rt, "op_bench_now", op_bench_now);
+
+  rt.op_state().borrow_mut().put(BenchClock(Instant::now()));
+}
+
+/// The instant all timings of a bench module are measured from.
+struct BenchClock(Instant);
+
+fn op_get_bench_origin(
+  state: &mut OpState,
+  _: (),
+  _: (),
+) -> Result<String, AnyError> {
+  Ok(state.borrow::<ModuleSpecifier>().to_string())
+}
+
+fn op_dispatch_bench_event(
+  state: &mut OpState,
+  event: BenchEvent,
+  _: (),
+) -> Result<(), AnyError> {
+  let sender = state.borrow::<Sender<BenchEvent>>().clone();
+  sender.send(event).ok();
+
+  Ok(())
+}
+
+/// Returns the nanoseconds elapsed since the module started. Unlike
+/// `performance.now()` this is never coarsened, as benchmarks need the full
+/// precision of the monotonic clock.
+fn op_bench_now(state: &mut OpState, _: (), _: ()) -> Result<u64, AnyError> {
+  let clock = state.borrow::<BenchClock>();
+  Ok(clock.0.elapsed().as_nanos() as u64)
+}
@@ -1,13 +1,22 @@
+use crate::tools::test::CompletedTests;
 use crate::tools::test::TestEvent;
+use crate::tools::test::TestSnapshots;
+use crate::tools::test::TestTimeouts;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::JsRuntime;
 use deno_core::ModuleSpecifier;
 use deno_core::OpState;
+use deno_runtime::deno_timers::StartTime;
+use deno_runtime::deno_timers::VirtualClock;
 use deno_runtime::permissions::create_child_permissions;
 use deno_runtime::permissions::ChildPermissionsArg;
 use deno_runtime::permissions::Permissions;
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::sync::mpsc::Sender;
+use std::time::Duration;
 use uuid::Uuid;
 
 pub fn init(rt: &mut JsRuntime) {
@@ -19,6 +28,16 @@ pub fn init(rt: &mut JsRuntime) {
   );
   super::reg_sync(rt, "op_get_test_origin", op_get_test_origin);
   super::reg_sync(rt, "op_dispatch_test_event", op_dispatch_test_event);
+  super::reg_sync(rt, "op_start_test_timeout", op_start_test_timeout);
+  super::reg_sync(rt, "op_clear_test_timeout", op_clear_test_timeout);
+  super::reg_sync(rt, "op_assert_test_snapshot", op_assert_test_snapshot);
+  super::reg_sync(rt, "op_complete_test_snapshots", op_complete_test_snapshots);
+  super::reg_sync(rt, "op_virtual_clock_install", op_virtual_clock_install);
+  super::reg_sync(rt, "op_virtual_clock_uninstall", op_virtual_clock_uninstall);
+  super::reg_sync(rt, "op_virtual_clock_now", op_virtual_clock_now);
+  super::reg_sync(rt, "op_virtual_clock_next", op_virtual_clock_next);
+  super::reg_sync(rt, "op_virtual_clock_advance", op_virtual_clock_advance);
+  super::reg_async(rt, "op_virtual_clock_yield", op_virtual_clock_yield);
 }
 
 #[derive(Clone)]
@@ -78,3 +97,123 @@ fn op_dispatch_test_event(
 
   Ok(())
 }
+
+fn op_start_test_timeout(
+  state: &mut OpState,
+  timeout: u64,
+  _: (),
+) -> Result<u32, AnyError> {
+  let timeouts = state.borrow::<TestTimeouts>();
+  Ok(timeouts.start(Duration::from_millis(timeout)))
+}
+
+fn op_clear_test_timeout(
+  state: &mut OpState,
+  id: u32,
+  _: (),
+) -> Result<(), AnyError> {
+  let timeouts = state.borrow::<TestTimeouts>();
+  timeouts.clear(id);
+
+  Ok(())
+}
+
+#[derive(Deserialize)]
+struct AssertTestSnapshotArgs {
+  name: String,
+  actual: String,
+}
+
+fn op_assert_test_snapshot(
+  state: &mut OpState,
+  args: AssertTestSnapshotArgs,
+  _: (),
+) -> Result<Option<String>, AnyError> {
+  let snapshots = state.borrow_mut::<TestSnapshots>();
+  snapshots.assert(args.name, args.actual)
+}
+
+fn op_complete_test_snapshots(
+  state: &mut OpState,
+  completed: CompletedTests,
+  _: (),
+) -> Result<(), AnyError> {
+  let snapshots = state.borrow_mut::<TestSnapshots>();
+  snapshots.complete(completed);
+
+  Ok(())
+}
+
+/// Installs a [`VirtualClock`] starting at the current time, truncated to whole
+/// milliseconds so that advancing it by whole milliseconds stays exact.
+fn op_virtual_clock_install(
+  state: &mut OpState,
+  _: (),
+  _: (),
+) -> Result<(), AnyError> {
+  if state.has::<VirtualClock>() {
+    return Err(generic_error("A virtual clock is already installed."));
+  }
+
+  let elapsed = state.borrow::<StartTime>().elapsed();
+  state.put(VirtualClock::new(elapsed.as_millis() as f64));
+  Ok(())
+}
+
+fn op_virtual_clock_uninstall(
+  state: &mut OpState,
+  _: (),
+  _: (),
+) -> Result<(), AnyError> {
+  state.try_take::<VirtualClock>();
+  Ok(())
+}
+
+/// Returns the time of the [`VirtualClock`], which unlike `op_now` isn't
+/// reduced in precision, so the test runner can advance the clock exactly.
+fn op_virtual_clock_now(
+  state: &mut OpState,
+  _: (),
+  _: (),
+) -> Result<f64, AnyError> {
+  let clock = state
+    .try_borrow::<VirtualClock>()
+    .ok_or_else(|| generic_error("No virtual clock is installed."))?;
+  Ok(clock.now())
+}
+
+/// Returns the deadline of the next pending timer on the [`VirtualClock`], if
+/// there is one.
+fn op_virtual_clock_next(
+  state: &mut OpState,
+  _: (),
+  _: (),
+) -> Result<Option<f64>, AnyError> {
+  Ok(
+    state
+      .try_borrow_mut::<VirtualClock>()
+      .and_then(|clock| clock.next_deadline()),
+  )
+}
+
+fn op_virtual_clock_advance(
+  state: &mut OpState,
+  time: f64,
+  _: (),
+) -> Result<(), AnyError> {
+  let clock = state
+    .try_borrow_mut::<VirtualClock>()
+    .ok_or_else(|| generic_error("No virtual clock is installed."))?;
+  clock.advance_to(time);
+  Ok(())
+}
+
+/// Resolves on the next turn of the event loop, which gives the timers woken
+/// by `op_virtual_clock_advance` the chance to run.
+async fn op_virtual_clock_yield(
+  _state: Rc<RefCell<OpState>>,
+  _: (),
+  _: (),
+) -> Result<(), AnyError> {
+  Ok(())
+}
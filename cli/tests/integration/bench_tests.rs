@@ -0,0 +1,51 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+use crate::itest;
+
+itest!(pass {
+  args: "bench --unstable bench/pass.ts",
+  exit_code: 0,
+  output: "bench/pass.out",
+});
+
+itest!(fail {
+  args: "bench --unstable bench/fail.ts",
+  exit_code: 1,
+  output: "bench/fail.out",
+});
+
+itest!(ignore {
+  args: "bench --unstable bench/ignore.ts",
+  exit_code: 0,
+  output: "bench/ignore.out",
+});
+
+itest!(only {
+  args: "bench --unstable bench/only.ts",
+  exit_code: 1,
+  output: "bench/only.out",
+});
+
+itest!(filter {
+  args: "bench --unstable --filter=parse bench/filter.ts",
+  exit_code: 0,
+  output: "bench/filter.out",
+});
+
+itest!(json {
+  args: "bench --unstable --json bench/json.ts",
+  exit_code: 0,
+  output: "bench/json.out",
+});
+
+itest!(collect {
+  args: "bench --unstable --ignore=bench/collect/ignore bench/collect",
+  exit_code: 0,
+  output: "bench/collect.out",
+});
+
+itest!(no_bench_modules {
+  args: "bench --unstable test/collect",
+  exit_code: 1,
+  output_str: Some("error: No bench modules found\n"),
+});
@@ -78,6 +78,77 @@ fn branch() {
   }
 
   assert!(output.status.success());
+
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("coverage")
+    .arg("--quiet")
+    .arg("--unstable")
+    .arg("--cobertura")
+    .arg(format!("{}/", tempdir.to_str().unwrap()))
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::inherit())
+    .output()
+    .expect("failed to spawn coverage reporter");
+
+  let actual = std::str::from_utf8(&output.stdout).unwrap().to_string();
+
+  let expected = fs::read_to_string(
+    util::testdata_path().join("coverage/expected_branch.cobertura.xml"),
+  )
+  .unwrap();
+
+  if !util::wildcard_match(&expected, &actual) {
+    println!("OUTPUT\n{}\nOUTPUT", actual);
+    println!("EXPECTED\n{}\nEXPECTED", expected);
+    panic!("pattern match failed");
+  }
+
+  assert!(output.status.success());
+}
+
+#[test]
+fn html() {
+  let tempdir = TempDir::new().expect("tempdir fail");
+  let profile_dir = tempdir.path().join("cov");
+  let html_dir = tempdir.path().join("html");
+  let status = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("test")
+    .arg("--quiet")
+    .arg("--unstable")
+    .arg(format!("--coverage={}", profile_dir.to_str().unwrap()))
+    .arg("coverage/branch_test.ts")
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::inherit())
+    .status()
+    .expect("failed to spawn test runner");
+
+  assert!(status.success());
+
+  let status = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("coverage")
+    .arg("--quiet")
+    .arg("--unstable")
+    .arg(format!("--html={}", html_dir.to_str().unwrap()))
+    .arg(format!("{}/", profile_dir.to_str().unwrap()))
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::inherit())
+    .status()
+    .expect("failed to spawn coverage reporter");
+
+  assert!(status.success());
+
+  let index = fs::read_to_string(html_dir.join("index.html")).unwrap();
+  assert!(index.contains("<a href=\"branch.ts.html\">branch.ts</a>"));
+  assert!(index.contains("28.57% (4/14)"));
+
+  let page = fs::read_to_string(html_dir.join("branch.ts.html")).unwrap();
+  assert!(page.contains("<a href=\"index.html\">All files</a>"));
+  assert!(page.contains(
+    "<td class=\"line-number\">4</td><td class=\"hits\">0x</td><td class=\"branches\">0/1</td>"
+  ));
 }
 
 #[test]
@@ -154,3 +225,55 @@ fn complex() {
 
   assert!(output.status.success());
 }
+
+#[test]
+fn thresholds() {
+  let tempdir = TempDir::new().expect("tempdir fail");
+  let status = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("test")
+    .arg("--quiet")
+    .arg("--unstable")
+    .arg(format!("--coverage={}", tempdir.path().to_str().unwrap()))
+    .arg("coverage/branch_test.ts")
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::inherit())
+    .status()
+    .expect("failed to spawn test runner");
+
+  assert!(status.success());
+
+  let run_coverage = |args: &[&str]| {
+    util::deno_cmd()
+      .current_dir(util::testdata_path())
+      .arg("coverage")
+      .arg("--quiet")
+      .arg("--unstable")
+      .args(args)
+      .arg(format!("{}/", tempdir.path().to_str().unwrap()))
+      .stdout(std::process::Stdio::piped())
+      .stderr(std::process::Stdio::piped())
+      .output()
+      .expect("failed to spawn coverage reporter")
+  };
+
+  let output = run_coverage(&["--min-lines=25", "--min-functions=50"]);
+  assert!(output.status.success());
+
+  let output = run_coverage(&["--min-lines=80"]);
+  assert!(!output.status.success());
+  let stderr =
+    util::strip_ansi_codes(std::str::from_utf8(&output.stderr).unwrap())
+      .to_string();
+  assert!(stderr.contains("All files: lines 28.57% (minimum 80%)"));
+  assert!(stderr.contains("branch.ts: lines 28.57%"));
+
+  let output = run_coverage(&["--config=coverage/thresholds.json"]);
+  assert!(!output.status.success());
+  let stderr =
+    util::strip_ansi_codes(std::str::from_utf8(&output.stderr).unwrap())
+      .to_string();
+  assert!(stderr
+    .contains("branch.ts: branches 0.00% (minimum 50% for \"branch.ts\")"));
+  assert!(!stderr.contains("All files"));
+}
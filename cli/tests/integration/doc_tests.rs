@@ -1,6 +1,8 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
 use crate::itest;
+use tempfile::TempDir;
+use test_util as util;
 
 itest!(deno_doc_builtin {
   args: "doc",
@@ -44,8 +46,58 @@ itest!(deno_doc_types_header_direct {
   http_server: true,
 });
 
+itest!(deno_doc_lint {
+  args: "doc --lint doc/lint/mod.ts",
+  output: "doc/lint.out",
+  exit_code: 1,
+});
+
 itest!(deno_doc_invalid_url {
   args: "doc https://raw.githubusercontent.com%2Fdyedgreen%2Fdeno-sqlite%2Frework_api%2Fmod.ts",
   output: "doc/invalid_url.out",
   exit_code: 1,
 });
+
+#[test]
+fn deno_doc_html() {
+  let temp_dir = TempDir::new().expect("tempdir fail");
+  let out_dir = temp_dir.path().join("docs");
+  let status = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("doc")
+    .arg(format!("--html={}", out_dir.to_string_lossy()))
+    .arg("doc/html/mod.ts")
+    .arg("doc/module/fun.js")
+    .spawn()
+    .unwrap()
+    .wait()
+    .unwrap();
+  assert!(status.success());
+
+  let index = std::fs::read_to_string(out_dir.join("index.html")).unwrap();
+  assert!(index.contains("href=\"doc_html_mod.ts/index.html\""));
+  assert!(index.contains("href=\"doc_module_fun.js/index.html\""));
+
+  let module =
+    std::fs::read_to_string(out_dir.join("doc_html_mod.ts/index.html"))
+      .unwrap();
+  assert!(module.contains("href=\"../doc_html_mod.ts/~/Greeter.html\""));
+  // re-exported symbols are documented as part of the entrypoint
+  assert!(module.contains("href=\"../doc_html_mod.ts/~/greet.html\""));
+
+  let greeter =
+    std::fs::read_to_string(out_dir.join("doc_html_mod.ts/~/Greeter.html"))
+      .unwrap();
+  assert!(greeter
+    .contains("<a href=\"../../doc_html_mod.ts/~/greet.html\"><code>greet"));
+  assert!(greeter
+    .contains("href=\"../../doc_html_mod.ts/~/Greeter.defaultName.html\""));
+  assert!(out_dir
+    .join("doc_html_mod.ts/~/Greeter.defaultName.html")
+    .exists());
+  assert!(out_dir.join("doc_module_fun.js/~/fun.html").exists());
+
+  let search_index =
+    std::fs::read_to_string(out_dir.join("search_index.js")).unwrap();
+  assert!(search_index.contains("\"name\":\"Greeter.defaultName\""));
+}
@@ -152,6 +152,12 @@ itest!(fmt_stdin {
   output_str: Some("const a = 1;\n"),
 });
 
+itest!(fmt_stdin_no_semicolons {
+  args: "fmt --options-no-semicolons --options-trailing-commas=never -",
+  input: Some("const a = [\n  1,\n  2,\n];\n"),
+  output_str: Some("const a = [\n  1,\n  2\n]\n"),
+});
+
 itest!(fmt_stdin_markdown {
   args: "fmt --ext=md -",
   input: Some("# Hello      Markdown\n```ts\nconsole.log( \"text\")\n```\n"),
@@ -1,6 +1,8 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
 use crate::itest;
+use std::fs;
+use tempfile::TempDir;
 use test_util as util;
 
 #[test]
@@ -69,6 +71,33 @@ itest!(stdin_json {
   exit_code: 1,
 });
 
+itest!(stdin_compact {
+  args: "lint --reporter=compact -",
+  input: Some("let _a: any;"),
+  output: "lint/expected_from_stdin_compact.out",
+  exit_code: 1,
+});
+
+itest!(stdin_github {
+  args: "lint --reporter=github -",
+  input: Some("let _a: any;"),
+  output: "lint/expected_from_stdin_github.out",
+  exit_code: 1,
+});
+
+itest!(stdin_sarif {
+  args: "lint --reporter=sarif -",
+  input: Some("let _a: any;"),
+  output: "lint/expected_from_stdin_sarif.out",
+  exit_code: 1,
+});
+
+itest!(stdin_fix {
+  args: "lint --fix -",
+  input: Some("let a = 1;;\nconsole.log(a);\n"),
+  output: "lint/expected_from_stdin_fix.out",
+});
+
 itest!(rules {
   args: "lint --rules",
   output: "lint/expected_rules.out",
@@ -88,6 +117,62 @@ itest!(lint_with_config {
   exit_code: 1,
 });
 
+#[test]
+fn lint_plugins_imported_module_changed() {
+  let tempdir = TempDir::new().expect("tempdir fail");
+  fs::write(
+    tempdir.path().join("deno.json"),
+    r#"{ "lint": { "plugins": ["./plugin.ts"] } }"#,
+  )
+  .unwrap();
+  fs::write(
+    tempdir.path().join("plugin.ts"),
+    r#"// deno-lint-ignore-file no-explicit-any
+import { message } from "./message.ts";
+export default {
+  rules: {
+    "no-anything": (context: any) => {
+      if (message !== null) {
+        context.report({ span: context.ast.span, message });
+      }
+    },
+  },
+};
+"#,
+  )
+  .unwrap();
+  fs::write(tempdir.path().join("mod.ts"), "console.log(1);\n").unwrap();
+  let message_path = tempdir.path().join("message.ts");
+  let lint = || {
+    util::deno_cmd()
+      .current_dir(tempdir.path())
+      .env("DENO_DIR", tempdir.path().join("deno_dir"))
+      .env("NO_COLOR", "1")
+      .arg("lint")
+      .arg("--config")
+      .arg("deno.json")
+      .arg("mod.ts")
+      .stdout(std::process::Stdio::null())
+      .stderr(std::process::Stdio::null())
+      .status()
+      .expect("failed to spawn lint")
+      .success()
+  };
+
+  fs::write(&message_path, "export const message = null;\n").unwrap();
+  assert!(lint());
+  // a change to a module the plugin imports invalidates the cached results
+  fs::write(&message_path, "export const message = \"Not allowed.\";\n")
+    .unwrap();
+  assert!(!lint());
+}
+
+itest!(lint_with_plugins {
+  args: "lint --config lint/with_plugins/Deno.jsonc lint/with_plugins/mod.ts",
+  output: "lint/with_plugins.out",
+  exit_code: 1,
+});
+
 // Check if CLI flags take precedence
 itest!(lint_with_config_and_flags {
   args: "lint --config lint/Deno.jsonc --ignore=lint/with_config/a.ts",
@@ -2476,6 +2476,96 @@ fn lsp_code_actions_imports() {
   shutdown(&mut client);
 }
 
+#[test]
+fn lsp_code_actions_organize_imports() {
+  let mut client = init("initialize_params.json");
+  did_open(
+    &mut client,
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file00.ts",
+        "languageId": "typescript",
+        "version": 1,
+        "text": "export const abc = \"abc\";\nexport const def = \"def\";\n"
+      }
+    }),
+  );
+  did_open(
+    &mut client,
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file01.ts",
+        "languageId": "typescript",
+        "version": 1,
+        "text": "import { def, abc } from \"./file00.ts\";\n\nconsole.log(abc);\n"
+      }
+    }),
+  );
+  let (maybe_res, maybe_err) = client
+    .write_request(
+      "textDocument/codeAction",
+      json!({
+        "textDocument": {
+          "uri": "file:///a/file01.ts"
+        },
+        "range": {
+          "start": {
+            "line": 0,
+            "character": 0
+          },
+          "end": {
+            "line": 0,
+            "character": 0
+          }
+        },
+        "context": {
+          "diagnostics": [],
+          "only": [
+            "source.organizeImports"
+          ]
+        }
+      }),
+    )
+    .unwrap();
+  assert!(maybe_err.is_none());
+  assert_eq!(
+    maybe_res,
+    Some(json!([
+      {
+        "title": "Organize imports",
+        "kind": "source.organizeImports",
+        "data": {
+          "specifier": "file:///a/file01.ts"
+        }
+      }
+    ]))
+  );
+  let (maybe_res, maybe_err) = client
+    .write_request::<_, _, Value>(
+      "codeAction/resolve",
+      json!({
+        "title": "Organize imports",
+        "kind": "source.organizeImports",
+        "data": {
+          "specifier": "file:///a/file01.ts"
+        }
+      }),
+    )
+    .unwrap();
+  assert!(maybe_err.is_none());
+  let res = maybe_res.unwrap();
+  let edits = &res["edit"]["documentChanges"][0]["edits"];
+  let new_text: String = edits
+    .as_array()
+    .unwrap()
+    .iter()
+    .map(|e| e["newText"].as_str().unwrap())
+    .collect();
+  assert!(new_text.contains("import { abc } from \"./file00.ts\";"));
+  assert!(!new_text.contains("def"));
+  shutdown(&mut client);
+}
+
 #[test]
 fn lsp_code_actions_refactor() {
   let mut client = init("initialize_params.json");
@@ -3483,6 +3573,325 @@ fn lsp_format_json() {
   shutdown(&mut client);
 }
 
+#[test]
+fn lsp_format_range() {
+  let mut client = init("initialize_params.json");
+  did_open(
+    &mut client,
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts",
+        "languageId": "typescript",
+        "version": 1,
+        "text": "const a  =  1;\nconst b  =  2;\nconst c  =  3;\n"
+      }
+    }),
+  );
+
+  let (maybe_res, maybe_err) = client
+    .write_request::<_, _, Vec<lsp::TextEdit>>(
+      "textDocument/rangeFormatting",
+      json!({
+        "textDocument": {
+          "uri": "file:///a/file.ts"
+        },
+        "range": {
+          "start": { "line": 1, "character": 8 },
+          "end": { "line": 1, "character": 9 }
+        },
+        "options": {
+          "tabSize": 2,
+          "insertSpaces": true
+        }
+      }),
+    )
+    .unwrap();
+  assert!(maybe_err.is_none());
+  let text_edits = maybe_res.unwrap();
+  assert!(!text_edits.is_empty());
+  assert!(text_edits
+    .iter()
+    .all(|e| e.range.start.line == 1 && e.range.end.line == 1));
+
+  let (maybe_res, maybe_err) = client
+    .write_request::<_, _, Vec<lsp::TextEdit>>(
+      "textDocument/onTypeFormatting",
+      json!({
+        "textDocument": {
+          "uri": "file:///a/file.ts"
+        },
+        "position": { "line": 2, "character": 14 },
+        "ch": ";",
+        "options": {
+          "tabSize": 2,
+          "insertSpaces": true
+        }
+      }),
+    )
+    .unwrap();
+  assert!(maybe_err.is_none());
+  let text_edits = maybe_res.unwrap();
+  assert!(!text_edits.is_empty());
+  assert!(text_edits
+    .iter()
+    .all(|e| e.range.start.line == 2 && e.range.end.line == 2));
+  shutdown(&mut client);
+}
+
+#[test]
+fn lsp_document_link() {
+  let mut client = init("initialize_params.json");
+  did_open(
+    &mut client,
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts",
+        "languageId": "typescript",
+        "version": 1,
+        "text": "import * as b from \"./b.ts\";\n\nconsole.log(b);\n"
+      }
+    }),
+  );
+  let (maybe_res, maybe_err) = client
+    .write_request(
+      "textDocument/documentLink",
+      json!({
+        "textDocument": {
+          "uri": "file:///a/file.ts"
+        }
+      }),
+    )
+    .unwrap();
+  assert!(maybe_err.is_none());
+  assert_eq!(
+    maybe_res,
+    Some(json!([
+      {
+        "range": {
+          "start": { "line": 0, "character": 19 },
+          "end": { "line": 0, "character": 27 }
+        },
+        "target": "file:///a/b.ts",
+        "tooltip": "file:///a/b.ts"
+      }
+    ]))
+  );
+  shutdown(&mut client);
+}
+
+#[test]
+fn lsp_inlay_hints() {
+  let mut client = init("initialize_params_inlay_hints.json");
+  did_open(
+    &mut client,
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts",
+        "languageId": "typescript",
+        "version": 1,
+        "text": "function a(b: string) {\n  return b;\n}\n\na(\"c\");\n"
+      }
+    }),
+  );
+  let (maybe_res, maybe_err) = client
+    .write_request(
+      "textDocument/inlayHint",
+      json!({
+        "textDocument": {
+          "uri": "file:///a/file.ts"
+        },
+        "range": {
+          "start": { "line": 0, "character": 0 },
+          "end": { "line": 5, "character": 0 }
+        }
+      }),
+    )
+    .unwrap();
+  assert!(maybe_err.is_none());
+  assert_eq!(
+    maybe_res,
+    Some(json!([
+      {
+        "position": { "line": 4, "character": 2 },
+        "label": "b:",
+        "kind": 2,
+        "paddingRight": true
+      }
+    ]))
+  );
+  shutdown(&mut client);
+}
+
+#[test]
+fn lsp_test_module_notification() {
+  let mut client = init("initialize_params_testing_api.json");
+  client
+    .write_notification(
+      "textDocument/didOpen",
+      json!({
+        "textDocument": {
+          "uri": "file:///a/file_test.ts",
+          "languageId": "typescript",
+          "version": 1,
+          "text": "Deno.test(\"test a\", async (t) => {\n  await t.step(\"step a\", () => {});\n});\n"
+        }
+      }),
+    )
+    .unwrap();
+  let (id, method, _) = client.read_request::<Value>().unwrap();
+  assert_eq!(method, "workspace/configuration");
+  client
+    .write_response(id, json!({ "enable": true }))
+    .unwrap();
+
+  let (method, maybe_params) = client.read_notification::<Value>().unwrap();
+  assert_eq!(method, "deno/testModule");
+  let params = maybe_params.unwrap();
+  assert_eq!(
+    params["textDocument"],
+    json!({ "uri": "file:///a/file_test.ts" })
+  );
+  let tests = params["tests"].as_array().unwrap();
+  assert_eq!(tests.len(), 1);
+  assert_eq!(tests[0]["name"], json!("test a"));
+  assert_eq!(
+    tests[0]["range"],
+    json!({
+      "start": { "line": 0, "character": 5 },
+      "end": { "line": 0, "character": 9 }
+    })
+  );
+  assert_eq!(tests[0]["steps"][0]["name"], json!("step a"));
+  shutdown(&mut client);
+}
+
+#[test]
+fn lsp_workspace_diagnostics() {
+  let temp_dir = TempDir::new().unwrap();
+  fs::write(
+    temp_dir.path().join("deno.json"),
+    r#"{ "files": { "exclude": ["excluded/"] } }"#,
+  )
+  .unwrap();
+  fs::write(temp_dir.path().join("a.ts"), "export const a = 1;\n").unwrap();
+  fs::write(
+    temp_dir.path().join("b.ts"),
+    "import { b } from \"./a.ts\";\n\nconsole.log(b);\n",
+  )
+  .unwrap();
+  fs::create_dir(temp_dir.path().join("excluded")).unwrap();
+  fs::write(
+    temp_dir.path().join("excluded/c.ts"),
+    "const c: string = 1;\n",
+  )
+  .unwrap();
+
+  let mut params: lsp::InitializeParams =
+    serde_json::from_value(load_fixture("initialize_params.json")).unwrap();
+  params.root_uri = Some(Url::from_file_path(temp_dir.path()).unwrap());
+  if let Some(Value::Object(mut map)) = params.initialization_options {
+    map.insert("config".to_string(), json!("./deno.json"));
+    map.insert("workspaceDiagnostics".to_string(), json!(true));
+    params.initialization_options = Some(Value::Object(map));
+  }
+
+  let deno_exe = deno_exe_path();
+  let mut client = LspClient::new(&deno_exe).unwrap();
+  client
+    .write_request::<_, _, Value>("initialize", params)
+    .unwrap();
+  client.write_notification("initialized", json!({})).unwrap();
+
+  // neither module is open, so the lint, TypeScript and dependency
+  // diagnostics are each published once for both of them
+  let mut diagnostics = std::collections::HashMap::new();
+  for _ in 0..6 {
+    let (method, maybe_params) = client
+      .read_notification::<lsp::PublishDiagnosticsParams>()
+      .unwrap();
+    assert_eq!(method, "textDocument/publishDiagnostics");
+    let params = maybe_params.unwrap();
+    assert_eq!(params.version, None);
+    diagnostics.insert(params.uri.clone(), params.diagnostics);
+  }
+  let a_uri = Url::from_file_path(temp_dir.path().join("a.ts")).unwrap();
+  let b_uri = Url::from_file_path(temp_dir.path().join("b.ts")).unwrap();
+  assert_eq!(diagnostics.len(), 2);
+  assert_eq!(diagnostics[&a_uri], vec![]);
+  let b_diagnostics = &diagnostics[&b_uri];
+  assert_eq!(b_diagnostics.len(), 1);
+  assert_eq!(
+    b_diagnostics[0].code,
+    Some(lsp::NumberOrString::Number(2305))
+  );
+  assert_eq!(b_diagnostics[0].source, Some("deno-ts".to_string()));
+  shutdown(&mut client);
+}
+
+#[test]
+fn lsp_will_rename_files() {
+  let mut client = init("initialize_params.json");
+  did_open(
+    &mut client,
+    json!({
+      "textDocument": {
+        "uri": "file:///a/b.ts",
+        "languageId": "typescript",
+        "version": 1,
+        "text": "export const b = \"b\";\n"
+      }
+    }),
+  );
+  did_open(
+    &mut client,
+    json!({
+      "textDocument": {
+        "uri": "file:///a/c.ts",
+        "languageId": "typescript",
+        "version": 1,
+        "text": "import { b } from \"./b.ts\";\n\nconsole.log(b);\n"
+      }
+    }),
+  );
+  let (maybe_res, maybe_err) = client
+    .write_request(
+      "workspace/willRenameFiles",
+      json!({
+        "files": [
+          {
+            "oldUri": "file:///a/b.ts",
+            "newUri": "file:///a/src/b.ts"
+          }
+        ]
+      }),
+    )
+    .unwrap();
+  assert!(maybe_err.is_none());
+  assert_eq!(
+    maybe_res,
+    Some(json!({
+      "documentChanges": [
+        {
+          "textDocument": {
+            "uri": "file:///a/c.ts",
+            "version": 1
+          },
+          "edits": [
+            {
+              "range": {
+                "start": { "line": 0, "character": 19 },
+                "end": { "line": 0, "character": 25 }
+              },
+              "newText": "./src/b.ts"
+            }
+          ]
+        }
+      ]
+    }))
+  );
+  shutdown(&mut client);
+}
+
 #[test]
 fn lsp_json_no_diagnostics() {
   let mut client = init("initialize_params.json");
@@ -3533,6 +3942,104 @@ fn lsp_json_no_diagnostics() {
   shutdown(&mut client);
 }
 
+#[test]
+fn lsp_config_file() {
+  let mut client = init("initialize_params.json");
+  client
+    .write_notification(
+      "textDocument/didOpen",
+      json!({
+        "textDocument": {
+          "uri": "file:///a/deno.json",
+          "languageId": "json",
+          "version": 1,
+          "text": "{\n  \"compilerOptions\": {\n    \"target\": \"es5\"\n  },\n  \"fmt\": {\n    \"options\": { \"useTabs\": true }\n  }\n}\n"
+        }
+      }),
+    )
+    .unwrap();
+  let (id, method, _) = client.read_request::<Value>().unwrap();
+  assert_eq!(method, "workspace/configuration");
+  client
+    .write_response(id, json!({ "enable": true }))
+    .unwrap();
+  let (method, maybe_params) = client
+    .read_notification::<lsp::PublishDiagnosticsParams>()
+    .unwrap();
+  assert_eq!(method, "textDocument/publishDiagnostics");
+  let params = maybe_params.unwrap();
+  assert_eq!(params.uri.as_str(), "file:///a/deno.json");
+  assert_eq!(params.version, Some(1));
+  assert_eq!(
+    json!(params.diagnostics),
+    json!([{
+      "range": {
+        "start": { "line": 2, "character": 4 },
+        "end": { "line": 2, "character": 12 }
+      },
+      "severity": 2,
+      "code": "ignored-compiler-option",
+      "source": "deno",
+      "message": "The compiler option \"target\" is not supported by Deno and will be ignored."
+    }])
+  );
+
+  let (maybe_res, maybe_err) = client
+    .write_request::<_, _, Value>(
+      "textDocument/hover",
+      json!({
+        "textDocument": {
+          "uri": "file:///a/deno.json"
+        },
+        "position": {
+          "line": 5,
+          "character": 19
+        }
+      }),
+    )
+    .unwrap();
+  assert!(maybe_err.is_none());
+  assert_eq!(
+    maybe_res,
+    Some(json!({
+      "contents": {
+        "kind": "markdown",
+        "value": "**fmt.options.useTabs**\n\nWhether to use tabs (true) or spaces (false) for indentation."
+      },
+      "range": {
+        "start": { "line": 5, "character": 17 },
+        "end": { "line": 5, "character": 26 }
+      }
+    }))
+  );
+
+  let (maybe_res, maybe_err) = client
+    .write_request::<_, _, Value>(
+      "textDocument/completion",
+      json!({
+        "textDocument": {
+          "uri": "file:///a/deno.json"
+        },
+        "position": {
+          "line": 4,
+          "character": 10
+        },
+        "context": {
+          "triggerKind": 1
+        }
+      }),
+    )
+    .unwrap();
+  assert!(maybe_err.is_none());
+  let res = maybe_res.unwrap();
+  let items = res["items"].as_array().unwrap();
+  assert_eq!(items.len(), 1);
+  assert_eq!(items[0]["label"], json!("files"));
+  assert_eq!(items[0]["insertText"], json!("\"files\""));
+
+  shutdown(&mut client);
+}
+
 #[test]
 fn lsp_format_markdown() {
   let mut client = init("initialize_params.json");
@@ -698,6 +698,18 @@ itest!(error_003_typescript2 {
   output: "error_003_typescript.ts.out",
 });
 
+itest!(error_003_typescript_json {
+  args: "run --diagnostics-format=json error_003_typescript.ts",
+  exit_code: 1,
+  output: "error_003_typescript_json.out",
+});
+
+itest!(error_003_typescript_sarif {
+  args: "run --diagnostics-format=sarif error_003_typescript.ts",
+  exit_code: 1,
+  output: "error_003_typescript_sarif.out",
+});
+
 itest!(error_004_missing_module {
   args: "run --reload error_004_missing_module.ts",
   exit_code: 1,
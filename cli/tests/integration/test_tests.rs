@@ -1,6 +1,8 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
 use crate::itest;
+use std::fs;
+use tempfile::TempDir;
 use test_util as util;
 
 #[test]
@@ -55,6 +57,24 @@ itest!(fail {
   output: "test/fail.out",
 });
 
+itest!(timeout_async {
+  args: "test test/timeout/async.ts",
+  exit_code: 1,
+  output: "test/timeout/async.out",
+});
+
+itest!(timeout_sync {
+  args: "test --timeout=100 test/timeout/sync.ts",
+  exit_code: 1,
+  output: "test/timeout/sync.out",
+});
+
+itest!(timeout_step {
+  args: "test --unstable test/timeout/step.ts",
+  exit_code: 1,
+  output: "test/timeout/step.out",
+});
+
 itest!(collect {
   args: "test --ignore=test/collect/ignore test/collect",
   exit_code: 0,
@@ -256,3 +276,280 @@ itest!(steps_no_unstable_flag {
   exit_code: 1,
   output: "test/steps/no_unstable_flag.out",
 });
+
+itest!(reporter_junit {
+  args: "test --unstable --reporter=junit test/reporter/reporter.ts",
+  exit_code: 1,
+  output: "test/reporter/junit.out",
+});
+
+itest!(reporter_tap {
+  args: "test --unstable --reporter=tap test/reporter/reporter.ts",
+  exit_code: 1,
+  output: "test/reporter/tap.out",
+});
+
+itest!(reporter_json {
+  args: "test --unstable --reporter=json test/reporter/reporter.ts",
+  exit_code: 1,
+  output: "test/reporter/json.out",
+});
+
+itest!(fake_time {
+  args: "test --unstable test/fake_time.ts",
+  exit_code: 0,
+  output: "test/fake_time.out",
+});
+
+itest!(snapshot_match {
+  args: "test --unstable test/snapshot/match.ts",
+  exit_code: 0,
+  output: "test/snapshot/match.out",
+});
+
+itest!(snapshot_mismatch {
+  args: "test --unstable test/snapshot/mismatch.ts",
+  exit_code: 1,
+  output: "test/snapshot/mismatch.out",
+});
+
+#[test]
+fn snapshot_update() {
+  let tempdir = TempDir::new().expect("tempdir fail");
+  let module_path = tempdir.path().join("render_test.ts");
+  let snapshot_path = tempdir.path().join("__snapshots__/render_test.ts.snap");
+  let run = |update: bool| {
+    let mut cmd = util::deno_cmd();
+    cmd
+      .current_dir(tempdir.path())
+      .env("NO_COLOR", "1")
+      .arg("test")
+      .arg("--unstable");
+    if update {
+      cmd.arg("--update-snapshots");
+    }
+    let output = cmd
+      .arg("render_test.ts")
+      .stderr(std::process::Stdio::null())
+      .output()
+      .expect("failed to spawn test runner");
+    (
+      output.status.success(),
+      String::from_utf8(output.stdout).unwrap(),
+    )
+  };
+
+  fs::write(
+    &module_path,
+    r#"Deno.test("render", (t) => {
+  t.assertSnapshot({ title: "Hello", items: [1, 2, 3] });
+  t.assertSnapshot(new Map([["key", "value"]]));
+});
+"#,
+  )
+  .unwrap();
+
+  let (success, out) = run(false);
+  assert!(!success);
+  assert!(out.contains("Snapshot \"render 1\" does not exist."));
+  assert!(!snapshot_path.exists());
+
+  let (success, out) = run(true);
+  assert!(success);
+  assert!(out.contains("snapshots: 2 written; 0 updated; 0 removed"));
+  let snapshots = fs::read_to_string(&snapshot_path).unwrap();
+  assert!(snapshots.contains("\"render\": ["));
+
+  let (success, out) = run(false);
+  assert!(success);
+  assert!(!out.contains("snapshots:"));
+
+  fs::write(
+    &module_path,
+    r#"Deno.test("render", (t) => {
+  t.assertSnapshot({ title: "Goodbye", items: [1, 2, 3] });
+});
+"#,
+  )
+  .unwrap();
+
+  let (success, out) = run(false);
+  assert!(!success);
+  assert!(out.contains("Snapshot \"render 1\" does not match:"));
+  assert!(!out.contains("obsolete snapshots:"));
+
+  let (success, out) = run(true);
+  assert!(success);
+  assert!(out.contains("snapshots: 0 written; 1 updated; 1 removed"));
+  assert_ne!(fs::read_to_string(&snapshot_path).unwrap(), snapshots);
+
+  fs::write(&module_path, "Deno.test(\"render\", () => {});\n").unwrap();
+
+  let (success, out) = run(false);
+  assert!(success);
+  assert!(out.contains("obsolete snapshots:\n\trender 1"));
+
+  let (success, out) = run(true);
+  assert!(success);
+  assert!(out.contains("snapshots: 0 written; 0 updated; 1 removed"));
+  assert!(!snapshot_path.exists());
+}
+
+#[test]
+fn shard() {
+  let run = |index: usize| {
+    let output = util::deno_cmd()
+      .current_dir(util::testdata_path())
+      .env("NO_COLOR", "1")
+      .arg("test")
+      .arg(format!("--shard={}/2", index))
+      .arg("test/shard")
+      .stderr(std::process::Stdio::null())
+      .output()
+      .expect("failed to spawn test runner");
+    assert!(output.status.success());
+    String::from_utf8(output.stdout)
+      .unwrap()
+      .lines()
+      .filter_map(|line| line.strip_prefix("running 1 test from "))
+      .map(|origin| origin.rsplit('/').next().unwrap().to_string())
+      .collect::<Vec<_>>()
+  };
+
+  let first = run(1);
+  let second = run(2);
+  assert!(!first.is_empty());
+  assert!(!second.is_empty());
+  let mut all = first.into_iter().chain(second).collect::<Vec<_>>();
+  all.sort();
+  assert_eq!(
+    all,
+    vec![
+      "a_test.ts",
+      "b_test.ts",
+      "c_test.ts",
+      "d_test.ts",
+      "e_test.ts"
+    ]
+  );
+}
+
+/// Runs `deno test` on the modules of `dir` with a DENO_DIR of its own, so the
+/// recorded failures persist between the runs of a test.
+fn run_recording_failures(
+  dir: &std::path::Path,
+  args: &[&str],
+) -> (bool, String, String) {
+  let output = util::deno_cmd()
+    .current_dir(dir)
+    .env("DENO_DIR", dir.join("deno_dir"))
+    .env("NO_COLOR", "1")
+    .arg("test")
+    .args(args)
+    .output()
+    .expect("failed to spawn test runner");
+  (
+    output.status.success(),
+    String::from_utf8(output.stdout).unwrap(),
+    String::from_utf8(output.stderr).unwrap(),
+  )
+}
+
+#[test]
+fn rerun_failed() {
+  let tempdir = TempDir::new().expect("tempdir fail");
+  let module_path = tempdir.path().join("math_test.ts");
+  let run = |rerun_failed: bool| {
+    let args: &[&str] = if rerun_failed {
+      &["--rerun-failed", "math_test.ts"]
+    } else {
+      &["math_test.ts"]
+    };
+    run_recording_failures(tempdir.path(), args)
+  };
+  let write = |sum: i32| {
+    fs::write(
+      &module_path,
+      format!(
+        r#"Deno.test("sub", () => {{
+  if (2 - 1 !== 1) throw new Error("sub");
+}});
+Deno.test("add", () => {{
+  if (1 + 1 !== {}) throw new Error("add");
+}});
+"#,
+        sum
+      ),
+    )
+    .unwrap();
+  };
+
+  write(3);
+  let (success, out, _) = run(false);
+  assert!(!success);
+  assert!(out.contains("running 2 tests"));
+  assert!(out.contains("test add ... FAILED"));
+
+  let (success, out, _) = run(true);
+  assert!(!success);
+  assert!(out.contains("running 1 test"));
+  assert!(out.contains("test add ... FAILED"));
+  assert!(!out.contains("test sub ..."));
+
+  write(2);
+  let (success, out, _) = run(true);
+  assert!(success);
+  assert!(out.contains("running 1 test"));
+  assert!(out.contains("test add ... ok"));
+
+  // with every failure fixed there is nothing left to rerun
+  let (success, out, err) = run(true);
+  assert!(success);
+  assert!(!out.contains("running"));
+  assert!(err.contains("No test failures were recorded"));
+}
+
+#[test]
+fn rerun_failed_after_filtered_run() {
+  let tempdir = TempDir::new().expect("tempdir fail");
+  let module_path = tempdir.path().join("math_test.ts");
+  let write = |first_passes: bool| {
+    fs::write(
+      &module_path,
+      format!(
+        r#"Deno.test("first", () => {{
+  if (!{}) throw new Error("first");
+}});
+Deno.test("second", () => {{
+  throw new Error("second");
+}});
+"#,
+        first_passes
+      ),
+    )
+    .unwrap();
+  };
+
+  write(false);
+  let (success, out, _) =
+    run_recording_failures(tempdir.path(), &["math_test.ts"]);
+  assert!(!success);
+  assert!(out.contains("test first ... FAILED"));
+  assert!(out.contains("test second ... FAILED"));
+
+  // the failure of the filtered out test must survive the filtered run
+  write(true);
+  let (success, out, _) = run_recording_failures(
+    tempdir.path(),
+    &["--filter", "first", "math_test.ts"],
+  );
+  assert!(success);
+  assert!(out.contains("test first ... ok"));
+
+  let (success, out, _) =
+    run_recording_failures(tempdir.path(), &["--rerun-failed", "math_test.ts"]);
+  assert!(!success);
+  assert!(out.contains("running 1 test"));
+  assert!(out.contains("test second ... FAILED"));
+  assert!(!out.contains("test first ..."));
+}
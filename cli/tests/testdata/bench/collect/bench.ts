@@ -0,0 +1 @@
+Deno.bench("collected", () => {});
@@ -0,0 +1 @@
+throw new Error("this module is not a bench module");
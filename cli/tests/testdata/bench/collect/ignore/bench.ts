@@ -0,0 +1 @@
+throw new Error("this module should be ignored");
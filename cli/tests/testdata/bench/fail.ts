@@ -0,0 +1,4 @@
+Deno.bench("pass", () => {});
+Deno.bench("fail", () => {
+  throw new Error("boom");
+});
@@ -0,0 +1,9 @@
+Deno.bench("parse url", () => {
+  new URL("https://deno.land/");
+});
+Deno.bench("parse json", () => {
+  JSON.parse("{}");
+});
+Deno.bench("stringify json", () => {
+  JSON.stringify({});
+});
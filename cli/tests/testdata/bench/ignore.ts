@@ -0,0 +1,8 @@
+Deno.bench({
+  name: "ignored",
+  ignore: true,
+  fn() {
+    throw new Error("unreachable");
+  },
+});
+Deno.bench("run", () => {});
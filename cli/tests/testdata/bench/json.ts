@@ -0,0 +1,2 @@
+Deno.bench("nop", { n: 5 }, () => {});
+Deno.bench({ name: "skipped", ignore: true, fn() {} });
@@ -0,0 +1,3 @@
+Deno.bench("before", () => {});
+Deno.bench({ name: "only", only: true, fn() {} });
+Deno.bench("after", () => {});
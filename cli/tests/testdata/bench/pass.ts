@@ -0,0 +1,7 @@
+Deno.bench("bench0", () => {});
+Deno.bench("bench1", { n: 10, warmup: 0 }, () => {});
+Deno.bench(function bench2() {});
+Deno.bench({ name: "bench3", fn() {} });
+Deno.bench("async", async () => {
+  await Promise.resolve();
+});
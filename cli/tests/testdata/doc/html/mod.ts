@@ -0,0 +1,17 @@
+/** A greeter, see {@link greet} for a shortcut. */
+export class Greeter {
+  constructor(public name: string) {}
+
+  /** Returns the greeting. */
+  greet(): string {
+    return `Hello ${this.name}`;
+  }
+}
+
+/** Utilities for greeters. */
+export namespace Greeter {
+  /** The default name. */
+  export const defaultName = "world";
+}
+
+export * from "./util.ts";
@@ -0,0 +1,8 @@
+/**
+ * Greets someone.
+ *
+ * Uses a {@link Greeter} under the hood.
+ */
+export function greet(name: string): string {
+  return `Hello ${name}`;
+}
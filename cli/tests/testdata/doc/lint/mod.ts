@@ -0,0 +1,29 @@
+interface Options {
+  verbose: boolean;
+}
+
+/**
+ * Adds two numbers.
+ *
+ * @param a The first number.
+ * @returns The sum.
+ */
+export function add(a: number, b: number): number {
+  return a + b;
+}
+
+/** Runs the task. */
+export function run(options: Options): void {
+  console.log(options.verbose);
+}
+
+export const VERSION = "1.0.0";
+
+/**
+ * Logs a message.
+ *
+ * @param message The message to log.
+ */
+export function log(message: string): void {
+  console.log(message);
+}
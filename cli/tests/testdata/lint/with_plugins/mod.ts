@@ -0,0 +1,5 @@
+import a from "lodash";
+// deno-lint-ignore no-lodash
+import b from "lodash";
+
+console.log(a, b);
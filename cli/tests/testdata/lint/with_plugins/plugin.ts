@@ -0,0 +1,18 @@
+// deno-lint-ignore-file no-explicit-any
+export default {
+  rules: {
+    "no-lodash": (context: any) => {
+      for (const item of context.ast.body) {
+        if (
+          item.type === "ImportDeclaration" && item.source.value === "lodash"
+        ) {
+          context.report({
+            span: item.span,
+            message: "Do not import lodash.",
+            hint: "Use the standard library instead.",
+          });
+        }
+      }
+    },
+  },
+};
@@ -0,0 +1,80 @@
+function assertEquals(actual: unknown, expected: unknown) {
+  if (actual !== expected) {
+    throw new Error(`Expected ${expected} but got ${actual}`);
+  }
+}
+
+Deno.test({ name: "timeout", fakeTime: true }, async (t) => {
+  const calls: number[] = [];
+  setTimeout(() => calls.push(1), 100);
+  setTimeout(() => {
+    calls.push(2);
+    setTimeout(() => calls.push(3), 50);
+  }, 200);
+
+  await t.clock.tick(99);
+  assertEquals(calls.join(), "");
+  await t.clock.tick(1);
+  assertEquals(calls.join(), "1");
+  await t.clock.tick(150);
+  assertEquals(calls.join(), "1,2,3");
+});
+
+Deno.test({ name: "interval", fakeTime: true }, async (t) => {
+  let calls = 0;
+  const id = setInterval(() => calls++, 10);
+  await t.clock.tick(55);
+  assertEquals(calls, 5);
+  clearInterval(id);
+  await t.clock.runAll();
+  assertEquals(calls, 5);
+});
+
+Deno.test({ name: "date", fakeTime: 1_000_000 }, async (t) => {
+  assertEquals(Date.now(), 1_000_000);
+  assertEquals(new Date().getTime(), 1_000_000);
+  const start = performance.now();
+  await t.clock.tick(2_500);
+  assertEquals(Date.now(), 1_002_500);
+  assertEquals(t.clock.now, 1_002_500);
+  assertEquals(performance.now() - start, 2_500);
+  assertEquals(new Date(0).getTime(), 0);
+});
+
+Deno.test({ name: "epoch", fakeTime: 0 }, async (t) => {
+  assertEquals(Date.now(), 0);
+  await t.clock.tick(10);
+  assertEquals(t.clock.now, 10);
+});
+
+Deno.test({ name: "reduced precision", fakeTime: true }, async (t) => {
+  // without --allow-hrtime, performance.now() is rounded to 2ms even though
+  // the clock itself moves exactly
+  for (let i = 0; i < 2; i++) {
+    await t.clock.tick(1);
+    assertEquals(performance.now() % 2, 0);
+  }
+  const now = t.clock.now;
+  await t.clock.tick(1);
+  assertEquals(t.clock.now, now + 1);
+});
+
+Deno.test({ name: "pending timers are discarded", fakeTime: true }, () => {
+  setTimeout(() => {
+    throw new Error("unreachable");
+  }, 1_000);
+});
+
+Deno.test("real time", (t) => {
+  let error: Error | undefined;
+  try {
+    t.clock;
+  } catch (err) {
+    error = err;
+  }
+  assertEquals(
+    error?.message,
+    'The clock is only available to tests with the "fakeTime" option.',
+  );
+  assertEquals(Date.now() > 1_002_500, true);
+});
@@ -0,0 +1,17 @@
+Deno.test("passes", () => {
+  console.log("hello from passes");
+});
+
+Deno.test("fails", () => {
+  throw new Error("boom");
+});
+
+Deno.test({
+  name: "ignored",
+  ignore: true,
+  fn() {},
+});
+
+Deno.test("steps", async (t) => {
+  await t.step("inner", () => {});
+});
@@ -0,0 +1 @@
+Deno.test("a", function () {});
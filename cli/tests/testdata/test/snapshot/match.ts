@@ -0,0 +1,10 @@
+Deno.test("strings", (t) => {
+  t.assertSnapshot("line 1\nline 2");
+  t.assertSnapshot(42);
+});
+
+Deno.test("steps", async (t) => {
+  await t.step("inner", (t) => {
+    t.assertSnapshot("from a step");
+  });
+});
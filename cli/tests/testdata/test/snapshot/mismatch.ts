@@ -0,0 +1,11 @@
+Deno.test("changed", (t) => {
+  t.assertSnapshot("line 1\nline two");
+});
+
+Deno.test("missing", (t) => {
+  t.assertSnapshot("new");
+});
+
+Deno.test("passes", (t) => {
+  t.assertSnapshot("same");
+});
@@ -0,0 +1,9 @@
+Deno.test({
+  name: "waits for a timer",
+  timeout: 100,
+  fn() {
+    return new Promise((resolve) => setTimeout(resolve, 60_000));
+  },
+});
+
+Deno.test("never runs", () => {});
@@ -0,0 +1,9 @@
+Deno.test("outer", async (t) => {
+  await t.step({
+    name: "inner",
+    timeout: 100,
+    fn() {
+      return new Promise((resolve) => setTimeout(resolve, 60_000));
+    },
+  });
+});
@@ -0,0 +1,5 @@
+Deno.test("loops forever", () => {
+  while (true) {
+    // spin
+  }
+});
@@ -0,0 +1,600 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+use crate::colors;
+use crate::create_main_worker;
+use crate::emit;
+use crate::flags::Flags;
+use crate::fs_util::collect_specifiers;
+use crate::fs_util::is_supported_bench_path;
+use crate::located_script_name;
+use crate::ops;
+use crate::proc_state::ProcState;
+use crate::tools::test::human_elapsed;
+
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::futures::future;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::JsRuntime;
+use deno_core::ModuleSpecifier;
+use deno_runtime::permissions::Permissions;
+use deno_runtime::tokio_util::run_basic;
+use log::Level;
+use serde::de;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::mpsc::channel;
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+use std::time::Instant;
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchDescription {
+  pub origin: String,
+  pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BenchOutput {
+  Console(String),
+}
+
+/// Statistics over the measured iterations of a bench. All durations are in
+/// nanoseconds.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BenchStats {
+  pub n: usize,
+  pub mean: f64,
+  pub min: u64,
+  pub max: u64,
+  pub p75: u64,
+  pub p99: u64,
+}
+
+impl BenchStats {
+  fn from_samples(samples: &[u64]) -> Option<BenchStats> {
+    if samples.is_empty() {
+      return None;
+    }
+
+    let mut sorted = samples.to_vec();
+    sorted.sort_unstable();
+    let total: u128 = sorted.iter().map(|sample| *sample as u128).sum();
+
+    Some(BenchStats {
+      n: sorted.len(),
+      mean: total as f64 / sorted.len() as f64,
+      min: sorted[0],
+      max: sorted[sorted.len() - 1],
+      p75: percentile(&sorted, 75),
+      p99: percentile(&sorted, 99),
+    })
+  }
+}
+
+/// Returns the nearest-rank percentile of the given sorted samples.
+fn percentile(sorted: &[u64], p: usize) -> u64 {
+  let rank = (p * sorted.len() + 99) / 100;
+  sorted[rank.max(1) - 1]
+}
+
+/// The runtime sends the duration of every measured iteration, which are
+/// reduced to their statistics as soon as they arrive.
+fn deserialize_samples<'de, D>(deserializer: D) -> Result<BenchStats, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  let samples = Vec::<u64>::deserialize(deserializer)?;
+  BenchStats::from_samples(&samples)
+    .ok_or_else(|| de::Error::custom("a bench must measure at least once"))
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BenchResult {
+  #[serde(deserialize_with = "deserialize_samples")]
+  Ok(BenchStats),
+  Ignored,
+  Failed(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchPlan {
+  pub origin: String,
+  pub total: usize,
+  pub filtered_out: usize,
+  pub used_only: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BenchEvent {
+  Plan(BenchPlan),
+  Wait(BenchDescription),
+  Output(BenchOutput),
+  Result(BenchDescription, BenchResult, u64),
+}
+
+#[derive(Debug, Clone, Default)]
+struct BenchSummary {
+  total: usize,
+  passed: usize,
+  failed: usize,
+  ignored: usize,
+  filtered_out: usize,
+  failures: Vec<(BenchDescription, String)>,
+}
+
+impl BenchSummary {
+  fn has_failed(&self) -> bool {
+    self.failed > 0 || !self.failures.is_empty()
+  }
+
+  fn has_pending(&self) -> bool {
+    self.total - self.passed - self.failed - self.ignored > 0
+  }
+}
+
+trait BenchReporter {
+  fn report_plan(&mut self, plan: &BenchPlan);
+  fn report_wait(&mut self, description: &BenchDescription);
+  fn report_output(&mut self, output: &BenchOutput);
+  fn report_result(
+    &mut self,
+    description: &BenchDescription,
+    result: &BenchResult,
+    elapsed: u64,
+  );
+  fn report_summary(&mut self, summary: &BenchSummary, elapsed: &Duration);
+}
+
+/// Formats a duration in nanoseconds using the largest unit that keeps the
+/// value above one.
+fn format_nanos(nanos: f64) -> String {
+  if nanos < 1e3 {
+    format!("{:.2}ns", nanos)
+  } else if nanos < 1e6 {
+    format!("{:.2}µs", nanos / 1e3)
+  } else if nanos < 1e9 {
+    format!("{:.2}ms", nanos / 1e6)
+  } else {
+    format!("{:.2}s", nanos / 1e9)
+  }
+}
+
+fn format_stats(stats: &BenchStats) -> String {
+  format!(
+    "{}/iter (min {}, max {}, p75 {}, p99 {}, n = {})",
+    format_nanos(stats.mean),
+    format_nanos(stats.min as f64),
+    format_nanos(stats.max as f64),
+    format_nanos(stats.p75 as f64),
+    format_nanos(stats.p99 as f64),
+    stats.n,
+  )
+}
+
+struct PrettyBenchReporter {
+  echo_output: bool,
+}
+
+impl BenchReporter for PrettyBenchReporter {
+  fn report_plan(&mut self, plan: &BenchPlan) {
+    let inflection = if plan.total == 1 { "bench" } else { "benches" };
+    println!("running {} {} from {}", plan.total, inflection, plan.origin);
+  }
+
+  fn report_wait(&mut self, description: &BenchDescription) {
+    print!("bench {} ...", description.name);
+    // flush for faster feedback when line buffered
+    std::io::stdout().flush().unwrap();
+  }
+
+  fn report_output(&mut self, output: &BenchOutput) {
+    if self.echo_output {
+      match output {
+        BenchOutput::Console(line) => println!("{}", line),
+      }
+    }
+  }
+
+  fn report_result(
+    &mut self,
+    _description: &BenchDescription,
+    result: &BenchResult,
+    elapsed: u64,
+  ) {
+    let status = match result {
+      BenchResult::Ok(stats) => {
+        format!("{} {}", colors::green("ok"), format_stats(stats))
+      }
+      BenchResult::Ignored => colors::yellow("ignored").to_string(),
+      BenchResult::Failed(_) => colors::red("FAILED").to_string(),
+    };
+
+    println!(
+      " {} {}",
+      status,
+      colors::gray(human_elapsed(elapsed.into())).to_string()
+    );
+  }
+
+  fn report_summary(&mut self, summary: &BenchSummary, elapsed: &Duration) {
+    if !summary.failures.is_empty() {
+      println!("\nfailures:\n");
+      for (description, error) in &summary.failures {
+        println!("{}", description.name);
+        println!("{}", error);
+        println!();
+      }
+
+      println!("failures:\n");
+      for (description, _) in &summary.failures {
+        println!("\t{}", description.name);
+      }
+    }
+
+    let status = if summary.has_failed() || summary.has_pending() {
+      colors::red("FAILED").to_string()
+    } else {
+      colors::green("ok").to_string()
+    };
+
+    println!(
+      "\nbench result: {}. {} passed; {} failed; {} ignored; {} filtered out {}\n",
+      status,
+      summary.passed,
+      summary.failed,
+      summary.ignored,
+      summary.filtered_out,
+      colors::gray(human_elapsed(elapsed.as_millis())),
+    );
+  }
+}
+
+/// Collects the results and prints them as a single JSON document once all
+/// benches have run. Console output is written to stderr so that stdout stays
+/// parseable.
+#[derive(Default)]
+struct JsonBenchReporter {
+  echo_output: bool,
+  benches: Vec<serde_json::Value>,
+}
+
+impl BenchReporter for JsonBenchReporter {
+  fn report_plan(&mut self, _plan: &BenchPlan) {}
+
+  fn report_wait(&mut self, _description: &BenchDescription) {}
+
+  fn report_output(&mut self, output: &BenchOutput) {
+    if self.echo_output {
+      match output {
+        BenchOutput::Console(line) => eprintln!("{}", line),
+      }
+    }
+  }
+
+  fn report_result(
+    &mut self,
+    description: &BenchDescription,
+    result: &BenchResult,
+    elapsed: u64,
+  ) {
+    let mut record = json!({
+      "origin": description.origin,
+      "name": description.name,
+    });
+    match result {
+      BenchResult::Ok(stats) => {
+        record["status"] = json!("ok");
+        record["stats"] = json!(stats);
+      }
+      BenchResult::Ignored => {
+        record["status"] = json!("ignored");
+      }
+      BenchResult::Failed(error) => {
+        record["status"] = json!("failed");
+        record["error"] = json!(error);
+      }
+    }
+    record["duration"] = json!(elapsed);
+
+    self.benches.push(record);
+  }
+
+  fn report_summary(&mut self, summary: &BenchSummary, elapsed: &Duration) {
+    let report = json!({
+      "benches": self.benches,
+      "summary": {
+        "total": summary.total,
+        "passed": summary.passed,
+        "failed": summary.failed,
+        "ignored": summary.ignored,
+        "filteredOut": summary.filtered_out,
+        "duration": elapsed.as_millis() as u64,
+      },
+    });
+
+    println!("{}", serde_json::to_string_pretty(&report).unwrap());
+  }
+}
+
+fn create_reporter(
+  json: bool,
+  echo_output: bool,
+) -> Box<dyn BenchReporter + Send> {
+  if json {
+    Box::new(JsonBenchReporter {
+      echo_output,
+      ..Default::default()
+    })
+  } else {
+    Box::new(PrettyBenchReporter { echo_output })
+  }
+}
+
+/// Runs the benches registered by a single module.
+async fn bench_specifier(
+  ps: ProcState,
+  permissions: Permissions,
+  specifier: ModuleSpecifier,
+  filter: Option<String>,
+  channel: Sender<BenchEvent>,
+) -> Result<(), AnyError> {
+  let init_ops = |js_runtime: &mut JsRuntime| {
+    ops::bench::init(js_runtime);
+    js_runtime
+      .op_state()
+      .borrow_mut()
+      .put::<Sender<BenchEvent>>(channel.clone());
+  };
+
+  let mut worker =
+    create_main_worker(&ps, specifier.clone(), permissions, Some(&init_ops));
+
+  // We execute the module as a side module so that import.meta.main is not set.
+  worker.execute_side_module(&specifier).await?;
+
+  worker.js_runtime.execute_script(
+    &located_script_name!(),
+    "window.dispatchEvent(new Event('load'));",
+  )?;
+
+  let bench_result = worker.js_runtime.execute_script(
+    &located_script_name!(),
+    &format!(
+      r#"Deno[Deno.internal].runBenchmarks({})"#,
+      json!({
+        "filter": filter,
+      }),
+    ),
+  )?;
+
+  worker.js_runtime.resolve_value(bench_result).await?;
+
+  worker.js_runtime.execute_script(
+    &located_script_name!(),
+    "window.dispatchEvent(new Event('unload'));",
+  )?;
+
+  Ok(())
+}
+
+/// Runs the benches of each specifier, one module at a time. Unlike tests,
+/// benches are never run concurrently as they would compete for the CPU and
+/// skew each other's timings.
+async fn bench_specifiers(
+  ps: ProcState,
+  permissions: Permissions,
+  specifiers: Vec<ModuleSpecifier>,
+  filter: Option<String>,
+  json: bool,
+) -> Result<(), AnyError> {
+  let log_level = ps.flags.log_level;
+  let (sender, receiver) = channel::<BenchEvent>();
+
+  let runner = async move {
+    for specifier in specifiers {
+      let ps = ps.clone();
+      let permissions = permissions.clone();
+      let filter = filter.clone();
+      let sender = sender.clone();
+
+      tokio::task::spawn_blocking(move || {
+        let join_handle = std::thread::spawn(move || {
+          let future =
+            bench_specifier(ps, permissions, specifier, filter, sender);
+
+          run_basic(future)
+        });
+
+        join_handle.join().unwrap()
+      })
+      .await??;
+    }
+
+    Ok::<(), AnyError>(())
+  };
+
+  let mut reporter = create_reporter(json, log_level != Some(Level::Error));
+
+  let handler = {
+    tokio::task::spawn_blocking(move || {
+      let earlier = Instant::now();
+      let mut summary = BenchSummary::default();
+      let mut used_only = false;
+
+      for event in receiver.iter() {
+        match event {
+          BenchEvent::Plan(plan) => {
+            summary.total += plan.total;
+            summary.filtered_out += plan.filtered_out;
+
+            if plan.used_only {
+              used_only = true;
+            }
+
+            reporter.report_plan(&plan);
+          }
+
+          BenchEvent::Wait(description) => {
+            reporter.report_wait(&description);
+          }
+
+          BenchEvent::Output(output) => {
+            reporter.report_output(&output);
+          }
+
+          BenchEvent::Result(description, result, elapsed) => {
+            match &result {
+              BenchResult::Ok(_) => {
+                summary.passed += 1;
+              }
+              BenchResult::Ignored => {
+                summary.ignored += 1;
+              }
+              BenchResult::Failed(error) => {
+                summary.failed += 1;
+                summary.failures.push((description.clone(), error.clone()));
+              }
+            }
+
+            reporter.report_result(&description, &result, elapsed);
+          }
+        }
+      }
+
+      let elapsed = Instant::now().duration_since(earlier);
+      reporter.report_summary(&summary, &elapsed);
+
+      if used_only {
+        return Err(generic_error(
+          "Bench failed because the \"only\" option was used",
+        ));
+      }
+
+      if summary.failed > 0 {
+        return Err(generic_error("Bench failed"));
+      }
+
+      Ok(())
+    })
+  };
+
+  let (runner_result, handler_result) = future::join(runner, handler).await;
+
+  runner_result?;
+  handler_result??;
+
+  Ok(())
+}
+
+pub async fn run_benchmarks(
+  flags: Flags,
+  include: Option<Vec<String>>,
+  ignore: Vec<PathBuf>,
+  filter: Option<String>,
+  json: bool,
+) -> Result<(), AnyError> {
+  let ps = ProcState::build(flags.clone()).await?;
+  let permissions = Permissions::from_options(&flags.clone().into());
+  let specifiers = collect_specifiers(
+    include.unwrap_or_else(|| vec![".".to_string()]),
+    &ignore,
+    is_supported_bench_path,
+  )?;
+
+  if specifiers.is_empty() {
+    return Err(generic_error("No bench modules found"));
+  }
+
+  let lib = if flags.unstable {
+    emit::TypeLib::UnstableDenoWindow
+  } else {
+    emit::TypeLib::DenoWindow
+  };
+
+  ps.prepare_module_load(
+    specifiers.clone(),
+    false,
+    lib,
+    Permissions::allow_all(),
+    permissions.clone(),
+    true,
+  )
+  .await?;
+
+  bench_specifiers(ps, permissions, specifiers, filter, json).await?;
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_bench_stats_from_samples() {
+    let samples: Vec<u64> = (1..=100).rev().collect();
+    let stats = BenchStats::from_samples(&samples).unwrap();
+    assert_eq!(
+      stats,
+      BenchStats {
+        n: 100,
+        mean: 50.5,
+        min: 1,
+        max: 100,
+        p75: 75,
+        p99: 99,
+      }
+    );
+
+    let stats = BenchStats::from_samples(&[7]).unwrap();
+    assert_eq!(stats.p75, 7);
+    assert_eq!(stats.p99, 7);
+
+    let stats = BenchStats::from_samples(&[10, 30, 20, 40]).unwrap();
+    assert_eq!(stats.mean, 25.0);
+    assert_eq!(stats.p75, 30);
+    assert_eq!(stats.p99, 40);
+
+    assert_eq!(BenchStats::from_samples(&[]), None);
+  }
+
+  #[test]
+  fn test_deserialize_bench_result() {
+    let result: BenchResult =
+      serde_json::from_value(json!({ "ok": [3, 1, 2] })).unwrap();
+    assert_eq!(
+      result,
+      BenchResult::Ok(BenchStats {
+        n: 3,
+        mean: 2.0,
+        min: 1,
+        max: 3,
+        p75: 3,
+        p99: 3,
+      })
+    );
+
+    let result: BenchResult = serde_json::from_value(json!("ignored")).unwrap();
+    assert_eq!(result, BenchResult::Ignored);
+
+    assert!(serde_json::from_value::<BenchResult>(json!({ "ok": [] })).is_err());
+  }
+
+  #[test]
+  fn test_format_nanos() {
+    assert_eq!(format_nanos(12.0), "12.00ns");
+    assert_eq!(format_nanos(1_234.0), "1.23µs");
+    assert_eq!(format_nanos(4_560_000.0), "4.56ms");
+    assert_eq!(format_nanos(2_500_000_000.0), "2.50s");
+  }
+}
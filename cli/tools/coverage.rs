@@ -1,20 +1,25 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
 use crate::colors;
+use crate::config_file::CoverageConfig;
+use crate::config_file::CoverageThresholds;
 use crate::emit;
 use crate::flags::Flags;
 use crate::fs_util::collect_files;
 use crate::proc_state::ProcState;
 use crate::source_maps::SourceMapGetter;
 use crate::tools::fmt::format_json;
+use crate::xml_util;
 
 use deno_ast::swc::common::Span;
 use deno_ast::MediaType;
+use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::serde_json;
 use deno_core::url::Url;
 use deno_core::LocalInspectorSession;
 use deno_runtime::permissions::Permissions;
+use log::info;
 use regex::Regex;
 use serde::Deserialize;
 use serde::Serialize;
@@ -23,8 +28,11 @@ use std::fs;
 use std::fs::File;
 use std::io::BufWriter;
 use std::io::Write;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
 use uuid::Uuid;
 
 // TODO(caspervonb) all of these structs can and should be made private, possibly moved to
@@ -177,6 +185,8 @@ impl CoverageCollector {
 pub enum CoverageReporterKind {
   Pretty,
   Lcov,
+  Cobertura,
+  Html(PathBuf),
 }
 
 fn create_reporter(
@@ -185,6 +195,12 @@ fn create_reporter(
   match kind {
     CoverageReporterKind::Lcov => Box::new(LcovCoverageReporter::new()),
     CoverageReporterKind::Pretty => Box::new(PrettyCoverageReporter::new()),
+    CoverageReporterKind::Cobertura => {
+      Box::new(CoberturaCoverageReporter::new())
+    }
+    CoverageReporterKind::Html(output_dir) => {
+      Box::new(HtmlCoverageReporter::new(output_dir))
+    }
   }
 }
 
@@ -197,45 +213,171 @@ pub trait CoverageReporter {
     maybe_original_source: Option<Arc<String>>,
   );
 
-  fn done(&mut self);
+  fn done(&mut self) -> Result<(), AnyError>;
 }
 
-pub struct LcovCoverageReporter {}
+struct FunctionCoverageItem {
+  name: String,
+  line_index: usize,
+  execution_count: usize,
+}
 
-impl LcovCoverageReporter {
-  pub fn new() -> LcovCoverageReporter {
-    LcovCoverageReporter {}
+struct BranchCoverageItem {
+  line_index: usize,
+  block_number: usize,
+  branch_number: usize,
+  /// `None` when the block containing the branch was never executed.
+  taken: Option<usize>,
+  is_hit: bool,
+}
+
+/// The coverage of a single module, with functions, branches and lines
+/// remapped to the original source when the module has a source map.
+struct CoverageReport {
+  url: String,
+  named_functions: Vec<FunctionCoverageItem>,
+  branches: Vec<BranchCoverageItem>,
+  /// Pairs of line index and hit count, ordered by line index.
+  found_lines: Vec<(usize, usize)>,
+}
+
+impl CoverageReport {
+  fn stats(&self) -> CoverageStats {
+    CoverageStats {
+      lines_found: self.found_lines.len(),
+      lines_hit: self
+        .found_lines
+        .iter()
+        .filter(|(_, count)| *count != 0)
+        .count(),
+      branches_found: self.branches.len(),
+      branches_hit: self.branches.iter().filter(|b| b.is_hit).count(),
+      functions_found: self.named_functions.len(),
+      functions_hit: self
+        .named_functions
+        .iter()
+        .filter(|f| f.execution_count != 0)
+        .count(),
+    }
+  }
+
+  /// Returns the number of branches hit and found on the given line.
+  fn line_branches(&self, line_index: usize) -> (usize, usize) {
+    self
+      .branches
+      .iter()
+      .filter(|branch| branch.line_index == line_index)
+      .fold((0, 0), |(hit, found), branch| {
+        (hit + branch.is_hit as usize, found + 1)
+      })
   }
 }
 
-impl CoverageReporter for LcovCoverageReporter {
-  fn visit_coverage(
-    &mut self,
-    script_coverage: &ScriptCoverage,
-    script_source: &str,
-    maybe_source_map: Option<Vec<u8>>,
-    _maybe_original_source: Option<Arc<String>>,
-  ) {
-    // TODO(caspervonb) cleanup and reduce duplication between reporters, pre-compute line coverage
-    // elsewhere.
-    let maybe_source_map = maybe_source_map
-      .map(|source_map| SourceMap::from_slice(&source_map).unwrap());
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct CoverageStats {
+  lines_hit: usize,
+  lines_found: usize,
+  branches_hit: usize,
+  branches_found: usize,
+  functions_hit: usize,
+  functions_found: usize,
+}
 
-    let url = Url::parse(&script_coverage.url).unwrap();
-    let file_path = url.to_file_path().unwrap();
-    println!("SF:{}", file_path.to_str().unwrap());
+impl CoverageStats {
+  fn add(&mut self, other: &CoverageStats) {
+    self.lines_hit += other.lines_hit;
+    self.lines_found += other.lines_found;
+    self.branches_hit += other.branches_hit;
+    self.branches_found += other.branches_found;
+    self.functions_hit += other.functions_hit;
+    self.functions_found += other.functions_found;
+  }
 
-    let mut functions_found = 0;
-    for function in &script_coverage.functions {
-      if function.function_name.is_empty() {
-        continue;
+  fn line_rate(&self) -> f64 {
+    rate(self.lines_hit, self.lines_found)
+  }
+
+  fn branch_rate(&self) -> f64 {
+    rate(self.branches_hit, self.branches_found)
+  }
+
+  fn function_rate(&self) -> f64 {
+    rate(self.functions_hit, self.functions_found)
+  }
+
+  /// Returns the name, percentage and minimum of every threshold that is not
+  /// met.
+  fn unmet_thresholds(
+    &self,
+    thresholds: &CoverageThresholds,
+  ) -> Vec<(&'static str, f64, f64)> {
+    [
+      ("lines", self.line_rate(), thresholds.lines),
+      ("branches", self.branch_rate(), thresholds.branches),
+      ("functions", self.function_rate(), thresholds.functions),
+    ]
+    .into_iter()
+    .filter_map(|(name, rate, maybe_minimum)| {
+      let percent = rate * 100.0;
+      match maybe_minimum {
+        Some(minimum) if percent < minimum => Some((name, percent, minimum)),
+        _ => None,
       }
+    })
+    .collect()
+  }
+}
+
+/// The ratio of hit to found items, where nothing to cover counts as fully
+/// covered.
+fn rate(hit: usize, found: usize) -> f64 {
+  if found == 0 {
+    1.0
+  } else {
+    hit as f64 / found as f64
+  }
+}
+
+fn generate_coverage_report(
+  script_coverage: &ScriptCoverage,
+  script_source: &str,
+  maybe_source_map: Option<&SourceMap>,
+) -> CoverageReport {
+  let mut named_functions = Vec::new();
+  for function in &script_coverage.functions {
+    if function.function_name.is_empty() {
+      continue;
+    }
 
-      let source_line = script_source[0..function.ranges[0].start_offset]
-        .split('\n')
-        .count();
+    let source_line = script_source[0..function.ranges[0].start_offset]
+      .split('\n')
+      .count();
 
-      let line_index = if let Some(source_map) = maybe_source_map.as_ref() {
+    let line_index = if let Some(source_map) = maybe_source_map {
+      source_map
+        .tokens()
+        .find(|token| token.get_dst_line() as usize == source_line)
+        .map(|token| token.get_src_line() as usize)
+        .unwrap_or(0)
+    } else {
+      source_line
+    };
+
+    named_functions.push(FunctionCoverageItem {
+      name: function.function_name.clone(),
+      line_index,
+      execution_count: function.ranges[0].count,
+    });
+  }
+
+  let mut branches = Vec::new();
+  for (block_number, function) in script_coverage.functions.iter().enumerate() {
+    let block_hits = function.ranges[0].count;
+    for (branch_number, range) in function.ranges[1..].iter().enumerate() {
+      let source_line =
+        script_source[0..range.start_offset].split('\n').count();
+
+      let line_index = if let Some(source_map) = maybe_source_map {
         source_map
           .tokens()
           .find(|token| token.get_dst_line() as usize == source_line)
@@ -245,175 +387,187 @@ impl CoverageReporter for LcovCoverageReporter {
         source_line
       };
 
-      let function_name = &function.function_name;
-
-      println!("FN:{},{}", line_index + 1, function_name);
+      // From https://manpages.debian.org/unstable/lcov/geninfo.1.en.html:
+      //
+      // Block number and branch number are gcc internal IDs for the branch. Taken is either '-'
+      // if the basic block containing the branch was never executed or a number indicating how
+      // often that branch was taken.
+      //
+      // However with the data we get from v8 coverage profiles it seems we can't actually hit
+      // this as appears it won't consider any nested branches it hasn't seen but its here for
+      // the sake of accuracy.
+      let taken = if block_hits > 0 {
+        Some(range.count)
+      } else {
+        None
+      };
 
-      functions_found += 1;
+      branches.push(BranchCoverageItem {
+        line_index,
+        block_number,
+        branch_number,
+        taken,
+        is_hit: range.count > 0,
+      });
     }
+  }
 
-    let mut functions_hit = 0;
-    for function in &script_coverage.functions {
-      if function.function_name.is_empty() {
-        continue;
-      }
+  let lines = script_source.split('\n').collect::<Vec<_>>();
+  let line_offsets = {
+    let mut offsets: Vec<(usize, usize)> = Vec::new();
+    let mut index = 0;
 
-      let execution_count = function.ranges[0].count;
-      let function_name = &function.function_name;
+    for line in &lines {
+      offsets.push((index, index + line.len() + 1));
+      index += line.len() + 1;
+    }
 
-      println!("FNDA:{},{}", execution_count, function_name);
+    offsets
+  };
 
-      if execution_count != 0 {
-        functions_hit += 1;
+  let line_counts = line_offsets
+    .iter()
+    .map(|(line_start_offset, line_end_offset)| {
+      let mut count = 0;
+
+      // Count the hits of ranges that include the entire line which will always be at-least one
+      // as long as the code has been evaluated.
+      for function in &script_coverage.functions {
+        for range in &function.ranges {
+          if range.start_offset <= *line_start_offset
+            && range.end_offset >= *line_end_offset
+          {
+            count += range.count;
+          }
+        }
       }
-    }
 
-    println!("FNF:{}", functions_found);
-    println!("FNH:{}", functions_hit);
+      // We reset the count if any block with a zero count overlaps with the line range.
+      for function in &script_coverage.functions {
+        for range in &function.ranges {
+          if range.count > 0 {
+            continue;
+          }
 
-    let mut branches_found = 0;
-    let mut branches_hit = 0;
-    for (block_number, function) in script_coverage.functions.iter().enumerate()
-    {
-      let block_hits = function.ranges[0].count;
-      for (branch_number, range) in function.ranges[1..].iter().enumerate() {
-        let source_line =
-          script_source[0..range.start_offset].split('\n').count();
+          let overlaps = std::cmp::max(line_end_offset, &range.end_offset)
+            - std::cmp::min(line_start_offset, &range.start_offset)
+            < (line_end_offset - line_start_offset)
+              + (range.end_offset - range.start_offset);
 
-        let line_index = if let Some(source_map) = maybe_source_map.as_ref() {
-          source_map
-            .tokens()
-            .find(|token| token.get_dst_line() as usize == source_line)
-            .map(|token| token.get_src_line() as usize)
-            .unwrap_or(0)
-        } else {
-          source_line
-        };
-
-        // From https://manpages.debian.org/unstable/lcov/geninfo.1.en.html:
-        //
-        // Block number and branch number are gcc internal IDs for the branch. Taken is either '-'
-        // if the basic block containing the branch was never executed or a number indicating how
-        // often that branch was taken.
-        //
-        // However with the data we get from v8 coverage profiles it seems we can't actually hit
-        // this as appears it won't consider any nested branches it hasn't seen but its here for
-        // the sake of accuracy.
-        let taken = if block_hits > 0 {
-          range.count.to_string()
-        } else {
-          "-".to_string()
-        };
-
-        println!(
-          "BRDA:{},{},{},{}",
-          line_index + 1,
-          block_number,
-          branch_number,
-          taken
-        );
-
-        branches_found += 1;
-        if range.count > 0 {
-          branches_hit += 1;
+          if overlaps {
+            count = 0;
+          }
         }
       }
-    }
 
-    println!("BRF:{}", branches_found);
-    println!("BRH:{}", branches_hit);
+      count
+    })
+    .collect::<Vec<usize>>();
 
-    let lines = script_source.split('\n').collect::<Vec<_>>();
-    let line_offsets = {
-      let mut offsets: Vec<(usize, usize)> = Vec::new();
-      let mut index = 0;
+  let found_lines = if let Some(source_map) = maybe_source_map {
+    let mut found_lines = line_counts
+      .iter()
+      .enumerate()
+      .map(|(index, count)| {
+        source_map
+          .tokens()
+          .filter(move |token| token.get_dst_line() as usize == index)
+          .map(move |token| (token.get_src_line() as usize, *count))
+      })
+      .flatten()
+      .collect::<Vec<(usize, usize)>>();
 
-      for line in &lines {
-        offsets.push((index, index + line.len() + 1));
-        index += line.len() + 1;
-      }
+    found_lines.sort_unstable_by_key(|(index, _)| *index);
+    found_lines.dedup_by_key(|(index, _)| *index);
+    found_lines
+  } else {
+    line_counts
+      .iter()
+      .enumerate()
+      .map(|(index, count)| (index, *count))
+      .collect::<Vec<(usize, usize)>>()
+  };
 
-      offsets
-    };
+  CoverageReport {
+    url: script_coverage.url.clone(),
+    named_functions,
+    branches,
+    found_lines,
+  }
+}
 
-    let line_counts = line_offsets
-      .iter()
-      .map(|(line_start_offset, line_end_offset)| {
-        let mut count = 0;
+pub struct LcovCoverageReporter {}
 
-        // Count the hits of ranges that include the entire line which will always be at-least one
-        // as long as the code has been evaluated.
-        for function in &script_coverage.functions {
-          for range in &function.ranges {
-            if range.start_offset <= *line_start_offset
-              && range.end_offset >= *line_end_offset
-            {
-              count += range.count;
-            }
-          }
-        }
+impl LcovCoverageReporter {
+  pub fn new() -> LcovCoverageReporter {
+    LcovCoverageReporter {}
+  }
+}
 
-        // We reset the count if any block with a zero count overlaps with the line range.
-        for function in &script_coverage.functions {
-          for range in &function.ranges {
-            if range.count > 0 {
-              continue;
-            }
+impl CoverageReporter for LcovCoverageReporter {
+  fn visit_coverage(
+    &mut self,
+    script_coverage: &ScriptCoverage,
+    script_source: &str,
+    maybe_source_map: Option<Vec<u8>>,
+    _maybe_original_source: Option<Arc<String>>,
+  ) {
+    let maybe_source_map = maybe_source_map
+      .map(|source_map| SourceMap::from_slice(&source_map).unwrap());
+    let report = generate_coverage_report(
+      script_coverage,
+      script_source,
+      maybe_source_map.as_ref(),
+    );
 
-            let overlaps = std::cmp::max(line_end_offset, &range.end_offset)
-              - std::cmp::min(line_start_offset, &range.start_offset)
-              < (line_end_offset - line_start_offset)
-                + (range.end_offset - range.start_offset);
+    let url = Url::parse(&report.url).unwrap();
+    let file_path = url.to_file_path().unwrap();
+    println!("SF:{}", file_path.to_str().unwrap());
 
-            if overlaps {
-              count = 0;
-            }
-          }
-        }
+    for function in &report.named_functions {
+      println!("FN:{},{}", function.line_index + 1, function.name);
+    }
 
-        count
-      })
-      .collect::<Vec<usize>>();
+    for function in &report.named_functions {
+      println!("FNDA:{},{}", function.execution_count, function.name);
+    }
 
-    let found_lines = if let Some(source_map) = maybe_source_map.as_ref() {
-      let mut found_lines = line_counts
-        .iter()
-        .enumerate()
-        .map(|(index, count)| {
-          source_map
-            .tokens()
-            .filter(move |token| token.get_dst_line() as usize == index)
-            .map(move |token| (token.get_src_line() as usize, *count))
-        })
-        .flatten()
-        .collect::<Vec<(usize, usize)>>();
+    let stats = report.stats();
+    println!("FNF:{}", stats.functions_found);
+    println!("FNH:{}", stats.functions_hit);
 
-      found_lines.sort_unstable_by_key(|(index, _)| *index);
-      found_lines.dedup_by_key(|(index, _)| *index);
-      found_lines
-    } else {
-      line_counts
-        .iter()
-        .enumerate()
-        .map(|(index, count)| (index, *count))
-        .collect::<Vec<(usize, usize)>>()
-    };
+    for branch in &report.branches {
+      let taken = if let Some(taken) = branch.taken {
+        taken.to_string()
+      } else {
+        "-".to_string()
+      };
 
-    for (index, count) in &found_lines {
-      println!("DA:{},{}", index + 1, count);
+      println!(
+        "BRDA:{},{},{},{}",
+        branch.line_index + 1,
+        branch.block_number,
+        branch.branch_number,
+        taken
+      );
     }
 
-    let lines_hit = found_lines.iter().filter(|(_, count)| *count != 0).count();
+    println!("BRF:{}", stats.branches_found);
+    println!("BRH:{}", stats.branches_hit);
 
-    println!("LH:{}", lines_hit);
+    for (index, count) in &report.found_lines {
+      println!("DA:{},{}", index + 1, count);
+    }
 
-    let lines_found = found_lines.len();
-    println!("LF:{}", lines_found);
+    println!("LH:{}", stats.lines_hit);
+    println!("LF:{}", stats.lines_found);
 
     println!("end_of_record");
   }
 
-  fn done(&mut self) {}
+  fn done(&mut self) -> Result<(), AnyError> {
+    Ok(())
+  }
 }
 
 pub struct PrettyCoverageReporter {}
@@ -587,7 +741,452 @@ impl CoverageReporter for PrettyCoverageReporter {
     }
   }
 
-  fn done(&mut self) {}
+  fn done(&mut self) -> Result<(), AnyError> {
+    Ok(())
+  }
+}
+
+/// Returns the path of each report relative to the closest directory that
+/// contains all of the local modules. Remote modules are placed under their
+/// scheme and host.
+fn relative_report_paths(
+  reports: &[CoverageReport],
+) -> (PathBuf, Vec<PathBuf>) {
+  let file_paths = reports
+    .iter()
+    .map(|report| {
+      Url::parse(&report.url)
+        .ok()
+        .and_then(|url| url.to_file_path().ok())
+    })
+    .collect::<Vec<_>>();
+
+  let mut parents = file_paths
+    .iter()
+    .flatten()
+    .filter_map(|file_path| file_path.parent());
+  let root = if let Some(first) = parents.next() {
+    parents.fold(first.to_path_buf(), |mut root, parent| {
+      while !parent.starts_with(&root) && root.pop() {}
+      root
+    })
+  } else {
+    PathBuf::new()
+  };
+
+  let relative_paths = reports
+    .iter()
+    .zip(file_paths.iter())
+    .map(|(report, maybe_file_path)| {
+      if let Some(file_path) = maybe_file_path {
+        file_path
+          .strip_prefix(&root)
+          .map(|path| path.to_path_buf())
+          .unwrap_or_else(|_| file_path.clone())
+      } else if let Ok(url) = Url::parse(&report.url) {
+        let mut path = PathBuf::from(url.scheme());
+        if let Some(host) = url.host_str() {
+          path.push(host);
+        }
+        path.push(url.path().trim_start_matches('/'));
+        path
+      } else {
+        PathBuf::from(&report.url)
+      }
+    })
+    .collect();
+
+  (root, relative_paths)
+}
+
+/// Formats a path with forward slashes, as used in the rendered reports.
+fn display_report_path(path: &Path) -> String {
+  path.to_string_lossy().replace('\\', "/")
+}
+
+fn display_report_dir(path: &Path) -> String {
+  match path.parent() {
+    Some(parent) if !parent.as_os_str().is_empty() => {
+      display_report_path(parent)
+    }
+    _ => ".".to_string(),
+  }
+}
+
+/// Writes a Cobertura XML document to stdout, with a package for every
+/// directory and a class for every module.
+pub struct CoberturaCoverageReporter {
+  reports: Vec<CoverageReport>,
+}
+
+impl CoberturaCoverageReporter {
+  pub fn new() -> CoberturaCoverageReporter {
+    CoberturaCoverageReporter {
+      reports: Vec::new(),
+    }
+  }
+}
+
+impl CoverageReporter for CoberturaCoverageReporter {
+  fn visit_coverage(
+    &mut self,
+    script_coverage: &ScriptCoverage,
+    script_source: &str,
+    maybe_source_map: Option<Vec<u8>>,
+    _maybe_original_source: Option<Arc<String>>,
+  ) {
+    let maybe_source_map = maybe_source_map
+      .map(|source_map| SourceMap::from_slice(&source_map).unwrap());
+    self.reports.push(generate_coverage_report(
+      script_coverage,
+      script_source,
+      maybe_source_map.as_ref(),
+    ));
+  }
+
+  fn done(&mut self) -> Result<(), AnyError> {
+    let timestamp = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|duration| duration.as_secs())
+      .unwrap_or(0);
+    print!("{}", render_cobertura(&self.reports, timestamp));
+    Ok(())
+  }
+}
+
+fn render_cobertura(reports: &[CoverageReport], timestamp: u64) -> String {
+  let (root, relative_paths) = relative_report_paths(reports);
+
+  let mut packages: Vec<(String, Vec<(&PathBuf, &CoverageReport)>)> =
+    Vec::new();
+  for (relative_path, report) in relative_paths.iter().zip(reports) {
+    let package_name = display_report_dir(relative_path);
+    match packages.iter_mut().find(|(name, _)| *name == package_name) {
+      Some((_, classes)) => classes.push((relative_path, report)),
+      None => packages.push((package_name, vec![(relative_path, report)])),
+    }
+  }
+
+  let mut total = CoverageStats::default();
+  let mut rendered_packages = String::new();
+  for (package_name, classes) in &packages {
+    let mut package_stats = CoverageStats::default();
+    let mut rendered_classes = String::new();
+
+    for (relative_path, report) in classes {
+      let stats = report.stats();
+      package_stats.add(&stats);
+
+      let mut methods = String::new();
+      for function in &report.named_functions {
+        let hit = (function.execution_count != 0) as usize;
+        methods.push_str(&format!(
+          "            <method name=\"{}\" signature=\"\" line-rate=\"{}\" branch-rate=\"1\" complexity=\"0\">\n              <lines>\n                <line number=\"{}\" hits=\"{}\"/>\n              </lines>\n            </method>\n",
+          xml_util::escape(&function.name),
+          hit,
+          function.line_index + 1,
+          function.execution_count,
+        ));
+      }
+
+      let mut lines = String::new();
+      for (index, count) in &report.found_lines {
+        let (branches_hit, branches_found) = report.line_branches(*index);
+        if branches_found > 0 {
+          lines.push_str(&format!(
+            "            <line number=\"{}\" hits=\"{}\" branch=\"true\" condition-coverage=\"{}% ({}/{})\"/>\n",
+            index + 1,
+            count,
+            (rate(branches_hit, branches_found) * 100.0).round(),
+            branches_hit,
+            branches_found,
+          ));
+        } else {
+          lines.push_str(&format!(
+            "            <line number=\"{}\" hits=\"{}\" branch=\"false\"/>\n",
+            index + 1,
+            count,
+          ));
+        }
+      }
+
+      let file_name = display_report_path(relative_path);
+      rendered_classes.push_str(&format!(
+        "        <class name=\"{}\" filename=\"{}\" line-rate=\"{:.4}\" branch-rate=\"{:.4}\" complexity=\"0\">\n          <methods>\n{}          </methods>\n          <lines>\n{}          </lines>\n        </class>\n",
+        xml_util::escape(
+          &relative_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| file_name.clone())
+        ),
+        xml_util::escape(&file_name),
+        stats.line_rate(),
+        stats.branch_rate(),
+        methods,
+        lines,
+      ));
+    }
+
+    total.add(&package_stats);
+    rendered_packages.push_str(&format!(
+      "    <package name=\"{}\" line-rate=\"{:.4}\" branch-rate=\"{:.4}\" complexity=\"0\">\n      <classes>\n{}      </classes>\n    </package>\n",
+      xml_util::escape(package_name),
+      package_stats.line_rate(),
+      package_stats.branch_rate(),
+      rendered_classes,
+    ));
+  }
+
+  format!(
+    "<?xml version=\"1.0\" ?>\n<!DOCTYPE coverage SYSTEM \"http://cobertura.sourceforge.net/xml/coverage-04.dtd\">\n<coverage line-rate=\"{:.4}\" branch-rate=\"{:.4}\" lines-covered=\"{}\" lines-valid=\"{}\" branches-covered=\"{}\" branches-valid=\"{}\" complexity=\"0\" version=\"deno {}\" timestamp=\"{}\">\n  <sources>\n    <source>{}</source>\n  </sources>\n  <packages>\n{}  </packages>\n</coverage>\n",
+    total.line_rate(),
+    total.branch_rate(),
+    total.lines_hit,
+    total.lines_found,
+    total.branches_hit,
+    total.branches_found,
+    crate::version::deno(),
+    timestamp,
+    xml_util::escape(&root.to_string_lossy()),
+    rendered_packages,
+  )
+}
+
+const HTML_REPORT_STYLE: &str = r#"
+body { font-family: sans-serif; margin: 2em; color: #222; }
+a { color: #0366d6; text-decoration: none; }
+table { border-collapse: collapse; }
+th, td { padding: 0.2em 0.8em; text-align: left; }
+.summary th, .summary td { border-bottom: 1px solid #ddd; }
+.summary .directory td { font-weight: bold; background: #f6f8fa; }
+.high { color: #22863a; }
+.medium { color: #b08800; }
+.low { color: #cb2431; }
+.source { font-family: monospace; width: 100%; }
+.source td { padding: 0 0.8em; white-space: pre; vertical-align: top; }
+.source .line-number, .source .hits, .source .branches { color: #999; text-align: right; user-select: none; }
+.source .covered .hits { background: #e6ffed; }
+.source .uncovered { background: #ffeef0; }
+.source .partial .branches { background: #fff5b1; }
+"#;
+
+/// Writes a static HTML site with a summary of every directory and a page
+/// with the annotated source of every module.
+pub struct HtmlCoverageReporter {
+  output_dir: PathBuf,
+  reports: Vec<(CoverageReport, String)>,
+}
+
+impl HtmlCoverageReporter {
+  pub fn new(output_dir: PathBuf) -> HtmlCoverageReporter {
+    HtmlCoverageReporter {
+      output_dir,
+      reports: Vec::new(),
+    }
+  }
+}
+
+impl CoverageReporter for HtmlCoverageReporter {
+  fn visit_coverage(
+    &mut self,
+    script_coverage: &ScriptCoverage,
+    script_source: &str,
+    maybe_source_map: Option<Vec<u8>>,
+    maybe_original_source: Option<Arc<String>>,
+  ) {
+    let maybe_source_map = maybe_source_map
+      .map(|source_map| SourceMap::from_slice(&source_map).unwrap());
+    let report = generate_coverage_report(
+      script_coverage,
+      script_source,
+      maybe_source_map.as_ref(),
+    );
+    let source = maybe_original_source
+      .map(|source| source.to_string())
+      .unwrap_or_else(|| script_source.to_string());
+
+    self.reports.push((report, source));
+  }
+
+  fn done(&mut self) -> Result<(), AnyError> {
+    let (reports, sources): (Vec<_>, Vec<_>) =
+      std::mem::take(&mut self.reports).into_iter().unzip();
+    let (_, relative_paths) = relative_report_paths(&reports);
+
+    for ((report, source), relative_path) in reports
+      .iter()
+      .zip(sources.iter())
+      .zip(relative_paths.iter())
+    {
+      let page_path = self
+        .output_dir
+        .join(format!("{}.html", relative_path.to_string_lossy()));
+      if let Some(parent) = page_path.parent() {
+        fs::create_dir_all(parent)?;
+      }
+      fs::write(page_path, render_html_file(report, source, relative_path))?;
+    }
+
+    fs::create_dir_all(&self.output_dir)?;
+    fs::write(
+      self.output_dir.join("index.html"),
+      render_html_index(&reports, &relative_paths),
+    )?;
+
+    info!(
+      "Wrote coverage report to {}",
+      self.output_dir.join("index.html").display()
+    );
+
+    Ok(())
+  }
+}
+
+fn html_rate_cell(hit: usize, found: usize) -> String {
+  let rate = rate(hit, found);
+  let class = if rate >= 0.9 {
+    "high"
+  } else if rate >= 0.75 {
+    "medium"
+  } else {
+    "low"
+  };
+
+  format!(
+    "<td class=\"{}\">{:.2}% ({}/{})</td>",
+    class,
+    rate * 100.0,
+    hit,
+    found
+  )
+}
+
+fn html_stats_cells(stats: &CoverageStats) -> String {
+  format!(
+    "{}{}{}",
+    html_rate_cell(stats.lines_hit, stats.lines_found),
+    html_rate_cell(stats.branches_hit, stats.branches_found),
+    html_rate_cell(stats.functions_hit, stats.functions_found),
+  )
+}
+
+fn html_page(title: &str, body: &str) -> String {
+  format!(
+    "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+    xml_util::escape(title),
+    HTML_REPORT_STYLE,
+    body
+  )
+}
+
+fn render_html_index(
+  reports: &[CoverageReport],
+  relative_paths: &[PathBuf],
+) -> String {
+  let mut directories: Vec<(String, Vec<(&PathBuf, &CoverageReport)>)> =
+    Vec::new();
+  for (relative_path, report) in relative_paths.iter().zip(reports) {
+    let directory = display_report_dir(relative_path);
+    match directories.iter_mut().find(|(name, _)| *name == directory) {
+      Some((_, files)) => files.push((relative_path, report)),
+      None => directories.push((directory, vec![(relative_path, report)])),
+    }
+  }
+  directories.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+  let mut total = CoverageStats::default();
+  let mut rows = String::new();
+  for (directory, files) in &directories {
+    let mut directory_stats = CoverageStats::default();
+    let mut file_rows = String::new();
+    for (relative_path, report) in files {
+      let stats = report.stats();
+      directory_stats.add(&stats);
+      let file_name = display_report_path(relative_path);
+      file_rows.push_str(&format!(
+        "<tr><td><a href=\"{}.html\">{}</a></td>{}</tr>\n",
+        xml_util::escape(&file_name),
+        xml_util::escape(&file_name),
+        html_stats_cells(&stats),
+      ));
+    }
+
+    total.add(&directory_stats);
+    rows.push_str(&format!(
+      "<tr class=\"directory\"><td>{}/</td>{}</tr>\n{}",
+      xml_util::escape(directory),
+      html_stats_cells(&directory_stats),
+      file_rows,
+    ));
+  }
+
+  html_page(
+    "Coverage report",
+    &format!(
+      "<h1>Coverage report</h1>\n<table class=\"summary\">\n<tr><th>File</th><th>Lines</th><th>Branches</th><th>Functions</th></tr>\n<tr class=\"directory\"><td>All files</td>{}</tr>\n{}</table>\n",
+      html_stats_cells(&total),
+      rows,
+    ),
+  )
+}
+
+fn render_html_file(
+  report: &CoverageReport,
+  source: &str,
+  relative_path: &Path,
+) -> String {
+  let depth = relative_path.components().count().saturating_sub(1);
+  let index_href = format!("{}index.html", "../".repeat(depth));
+  let file_name = display_report_path(relative_path);
+
+  let mut rows = String::new();
+  for (line_index, line) in source.split('\n').enumerate() {
+    let maybe_count = report
+      .found_lines
+      .binary_search_by_key(&line_index, |(index, _)| *index)
+      .ok()
+      .map(|position| report.found_lines[position].1);
+    let (branches_hit, branches_found) = report.line_branches(line_index);
+
+    let mut classes = Vec::new();
+    match maybe_count {
+      Some(0) => classes.push("uncovered"),
+      Some(_) => classes.push("covered"),
+      None => {}
+    }
+    if branches_hit < branches_found {
+      classes.push("partial");
+    }
+
+    let hits = maybe_count
+      .map(|count| format!("{}x", count))
+      .unwrap_or_default();
+    let branches = if branches_found > 0 {
+      format!("{}/{}", branches_hit, branches_found)
+    } else {
+      String::new()
+    };
+
+    rows.push_str(&format!(
+      "<tr class=\"{}\"><td class=\"line-number\">{}</td><td class=\"hits\">{}</td><td class=\"branches\">{}</td><td>{}</td></tr>\n",
+      classes.join(" "),
+      line_index + 1,
+      hits,
+      branches,
+      xml_util::escape(line.trim_end_matches('\r')),
+    ));
+  }
+
+  html_page(
+    &format!("Coverage report: {}", file_name),
+    &format!(
+      "<h1><a href=\"{}\">All files</a> / {}</h1>\n<table class=\"summary\">\n<tr><th>Lines</th><th>Branches</th><th>Functions</th></tr>\n<tr>{}</tr>\n</table>\n<table class=\"source\">\n{}</table>\n",
+      index_href,
+      xml_util::escape(&file_name),
+      html_stats_cells(&report.stats()),
+      rows,
+    ),
+  )
 }
 
 fn collect_coverages(
@@ -673,20 +1272,31 @@ pub async fn cover_files(
   ignore: Vec<PathBuf>,
   include: Vec<String>,
   exclude: Vec<String>,
-  lcov: bool,
+  reporter_kind: CoverageReporterKind,
+  thresholds: CoverageThresholds,
 ) -> Result<(), AnyError> {
   let ps = ProcState::build(flags).await?;
 
-  let script_coverages = collect_coverages(files, ignore)?;
-  let script_coverages = filter_coverages(script_coverages, include, exclude);
-
-  let reporter_kind = if lcov {
-    CoverageReporterKind::Lcov
+  let maybe_coverage_config = if let Some(config_file) = &ps.maybe_config_file {
+    config_file.to_coverage_config()?
   } else {
-    CoverageReporterKind::Pretty
+    None
   };
+  let thresholds = maybe_coverage_config
+    .as_ref()
+    .map(|config| config.thresholds.merge(&thresholds))
+    .unwrap_or(thresholds);
+  let check_thresholds = !thresholds.is_empty()
+    || maybe_coverage_config
+      .as_ref()
+      .map(|config| !config.files.is_empty())
+      .unwrap_or(false);
+
+  let script_coverages = collect_coverages(files, ignore)?;
+  let script_coverages = filter_coverages(script_coverages, include, exclude);
 
   let mut reporter = create_reporter(reporter_kind);
+  let mut reports = Vec::new();
 
   for script_coverage in script_coverages {
     let module_specifier =
@@ -710,6 +1320,17 @@ pub async fn cover_files(
       .get_source(&module_specifier)
       .map(|f| f.source);
 
+    if check_thresholds {
+      let maybe_source_map = maybe_source_map
+        .as_ref()
+        .map(|source_map| SourceMap::from_slice(source_map).unwrap());
+      reports.push(generate_coverage_report(
+        &script_coverage,
+        script_source,
+        maybe_source_map.as_ref(),
+      ));
+    }
+
     reporter.visit_coverage(
       &script_coverage,
       script_source,
@@ -718,7 +1339,72 @@ pub async fn cover_files(
     );
   }
 
-  reporter.done();
+  reporter.done()?;
+
+  if check_thresholds {
+    let failures = check_coverage_thresholds(
+      &reports,
+      &thresholds,
+      maybe_coverage_config.as_ref(),
+    );
+    if !failures.is_empty() {
+      eprintln!("{}", colors::red_bold("Coverage thresholds not met:"));
+      for failure in failures {
+        eprintln!("  {}", failure);
+      }
+      return Err(generic_error("Coverage is below the configured thresholds"));
+    }
+  }
 
   Ok(())
 }
+
+/// Checks the combined coverage against the global thresholds and every
+/// report against the per-file thresholds that match it, returning a line of
+/// output for each threshold that was not met. When a global threshold is not
+/// met, the files that are below it on their own are listed too.
+fn check_coverage_thresholds(
+  reports: &[CoverageReport],
+  thresholds: &CoverageThresholds,
+  maybe_coverage_config: Option<&CoverageConfig>,
+) -> Vec<String> {
+  let mut failures = Vec::new();
+
+  let mut total = CoverageStats::default();
+  for report in reports {
+    total.add(&report.stats());
+  }
+  for (name, percent, minimum) in total.unmet_thresholds(thresholds) {
+    failures.push(format!(
+      "All files: {} {:.2}% (minimum {}%)",
+      name, percent, minimum
+    ));
+    for report in reports {
+      let unmet = report
+        .stats()
+        .unmet_thresholds(thresholds)
+        .into_iter()
+        .find(|(unmet_name, _, _)| *unmet_name == name);
+      if let Some((_, percent, _)) = unmet {
+        failures.push(format!("  {}: {} {:.2}%", report.url, name, percent));
+      }
+    }
+  }
+
+  if let Some(coverage_config) = maybe_coverage_config {
+    for report in reports {
+      let stats = report.stats();
+      for file in coverage_config.file_thresholds(&report.url) {
+        for (name, percent, minimum) in stats.unmet_thresholds(&file.thresholds)
+        {
+          failures.push(format!(
+            "{}: {} {:.2}% (minimum {}% for \"{}\")",
+            report.url, name, percent, minimum, file.glob
+          ));
+        }
+      }
+    }
+  }
+
+  failures
+}
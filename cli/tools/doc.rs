@@ -1,17 +1,27 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
 use crate::colors;
+use crate::config_file::LintReporterKind;
 use crate::file_fetcher::File;
+use crate::flags::DocHtmlFlags;
+use crate::flags::DocLintFlags;
 use crate::flags::Flags;
 use crate::get_types;
 use crate::proc_state::ProcState;
+use crate::tools::lint::create_reporter;
+use crate::tools::lint::sort_diagnostics;
 use crate::write_json_to_stdout;
 use crate::write_to_stdout_ignore_sigpipe;
+use crate::xml_util;
 use deno_ast::MediaType;
+use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::futures::future;
 use deno_core::futures::future::FutureExt;
 use deno_core::resolve_url_or_path;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
 use deno_doc as doc;
 use deno_graph::create_graph;
 use deno_graph::source::LoadFuture;
@@ -19,8 +29,17 @@ use deno_graph::source::LoadResponse;
 use deno_graph::source::Loader;
 use deno_graph::source::Resolver;
 use deno_graph::ModuleSpecifier;
+use deno_lint::diagnostic::LintDiagnostic;
+use deno_lint::diagnostic::Position;
+use deno_lint::diagnostic::Range;
 use deno_runtime::permissions::Permissions;
 use import_map::ImportMap;
+use log::info;
+use regex::Regex;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -96,8 +115,16 @@ pub async fn print_docs(
   json: bool,
   maybe_filter: Option<String>,
   private: bool,
+  maybe_html: Option<DocHtmlFlags>,
+  maybe_lint: Option<DocLintFlags>,
 ) -> Result<(), AnyError> {
   let ps = ProcState::build(flags.clone()).await?;
+  if let Some(html) = maybe_html {
+    return generate_html_docs(&ps, html, private).await;
+  }
+  if let Some(lint) = maybe_lint {
+    return lint_docs(&ps, lint).await;
+  }
   let source_file = source_file.unwrap_or_else(|| "--builtin".to_string());
   let source_parser = deno_graph::DefaultSourceParser::new();
 
@@ -190,3 +217,892 @@ pub async fn print_docs(
     write_to_stdout_ignore_sigpipe(details.as_bytes()).map_err(AnyError::from)
   }
 }
+
+/// Parses the documentation of several entrypoints, including the symbols
+/// they re-export.
+async fn parse_entrypoints(
+  ps: &ProcState,
+  specifiers: &[ModuleSpecifier],
+  private: bool,
+) -> Result<Vec<Vec<doc::DocNode>>, AnyError> {
+  let source_parser = deno_graph::DefaultSourceParser::new();
+  let mut loader = DocLoader { ps: ps.clone() };
+  let resolver = DocResolver {
+    import_map: ps.maybe_import_map.clone(),
+  };
+  let graph = create_graph(
+    specifiers.to_vec(),
+    false,
+    None,
+    &mut loader,
+    Some(&resolver),
+    None,
+    None,
+  )
+  .await;
+  let doc_parser = doc::DocParser::new(graph, private, &source_parser);
+  specifiers
+    .iter()
+    .map(|specifier| {
+      doc_parser
+        .parse_with_reexports(specifier)
+        .map_err(|err| generic_error(err.to_string()))
+    })
+    .collect()
+}
+
+fn resolve_entrypoints(
+  source_files: &[String],
+) -> Result<Vec<ModuleSpecifier>, AnyError> {
+  source_files
+    .iter()
+    .map(|source_file| resolve_url_or_path(source_file))
+    .collect()
+}
+
+async fn generate_html_docs(
+  ps: &ProcState,
+  html: DocHtmlFlags,
+  private: bool,
+) -> Result<(), AnyError> {
+  let specifiers = resolve_entrypoints(&html.source_files)?;
+  let parsed = parse_entrypoints(ps, &specifiers, private).await?;
+
+  let cwd = std::env::current_dir()?;
+  let mut modules = Vec::new();
+  let mut used_slugs = HashSet::new();
+  for (specifier, doc_nodes) in specifiers.iter().zip(parsed) {
+    let display_name = module_display_name(specifier, &cwd);
+    modules.push(HtmlModule {
+      slug: unique_module_slug(&display_name, &mut used_slugs),
+      display_name,
+      doc_nodes,
+    });
+  }
+
+  let files_written = write_html_docs(&html.out_dir, &modules, private)?;
+  info!(
+    "Wrote {} files to {}",
+    files_written,
+    html.out_dir.join("index.html").display()
+  );
+
+  Ok(())
+}
+
+async fn lint_docs(ps: &ProcState, lint: DocLintFlags) -> Result<(), AnyError> {
+  let specifiers = resolve_entrypoints(&lint.source_files)?;
+  let public = parse_entrypoints(ps, &specifiers, false).await?;
+  let all = parse_entrypoints(ps, &specifiers, true).await?;
+
+  let mut diagnostics = Vec::new();
+  for (public_nodes, all_nodes) in public.iter().zip(all.iter()) {
+    let public_names: HashSet<&str> =
+      public_nodes.iter().map(|n| n.name.as_str()).collect();
+    let private_names: HashSet<&str> = all_nodes
+      .iter()
+      .filter(|n| n.kind != doc::DocNodeKind::Import)
+      .map(|n| n.name.as_str())
+      .filter(|name| !public_names.contains(name))
+      .collect();
+    lint_doc_nodes(public_nodes, "", &private_names, &mut diagnostics);
+  }
+  // a symbol re-exported by several entrypoints is only reported once
+  let mut seen = HashSet::new();
+  diagnostics.retain(|d| {
+    seen.insert((
+      d.filename.clone(),
+      d.range.start.line_index,
+      d.range.start.column_index,
+      d.message.clone(),
+    ))
+  });
+  sort_diagnostics(&mut diagnostics);
+
+  let mut reporter = create_reporter(LintReporterKind::Pretty);
+  for mut diagnostic in diagnostics.iter().cloned() {
+    let source = resolve_url_or_path(&diagnostic.filename)
+      .ok()
+      .and_then(|specifier| ps.file_fetcher.get_source(&specifier))
+      .map(|file| file.source)
+      .unwrap_or_default();
+    let source_lines: Vec<&str> = source.split('\n').collect();
+    // underline the rest of the line the declaration starts on
+    if let Some(line) = source_lines.get(diagnostic.range.start.line_index) {
+      diagnostic.range.end.column_index = line
+        .trim_end()
+        .len()
+        .max(diagnostic.range.start.column_index);
+    }
+    reporter.visit_diagnostic(&diagnostic, source_lines);
+  }
+  reporter.close(specifiers.len());
+
+  if !diagnostics.is_empty() {
+    std::process::exit(1);
+  }
+  Ok(())
+}
+
+fn doc_diagnostic(
+  doc_node: &doc::DocNode,
+  code: &str,
+  message: String,
+  hint: String,
+) -> LintDiagnostic {
+  let position = Position {
+    // `deno_doc` locations have 1-indexed lines
+    line_index: doc_node.location.line.saturating_sub(1),
+    column_index: doc_node.location.col,
+    byte_pos: 0,
+  };
+  LintDiagnostic {
+    range: Range {
+      start: position.clone(),
+      end: position,
+    },
+    filename: doc_node.location.filename.clone(),
+    message,
+    code: code.to_string(),
+    hint: Some(hint),
+  }
+}
+
+fn lint_doc_nodes(
+  doc_nodes: &[doc::DocNode],
+  prefix: &str,
+  private_names: &HashSet<&str>,
+  diagnostics: &mut Vec<LintDiagnostic>,
+) {
+  for doc_node in doc_nodes {
+    if doc_node.kind == doc::DocNodeKind::Import {
+      continue;
+    }
+    let name = format!("{}{}", prefix, doc_node.name);
+
+    match doc_node.js_doc.as_deref().map(str::trim) {
+      None | Some("") => diagnostics.push(doc_diagnostic(
+        doc_node,
+        "missing-jsdoc",
+        format!("Exported symbol `{}` is missing a JSDoc comment", name),
+        "Add a JSDoc comment describing the symbol".to_string(),
+      )),
+      Some(js_doc) => {
+        if let Some(function_def) = &doc_node.function_def {
+          let function_def =
+            serde_json::to_value(function_def).unwrap_or_default();
+          lint_function_js_doc(
+            doc_node,
+            &name,
+            js_doc,
+            &function_def,
+            diagnostics,
+          );
+        }
+      }
+    }
+
+    let node_value = serde_json::to_value(doc_node).unwrap_or_default();
+    let mut type_refs = Vec::new();
+    collect_type_refs(&node_value, &mut type_refs);
+    let mut reported = HashSet::new();
+    for type_ref in type_refs {
+      let type_name = type_ref.split('.').next().unwrap_or(type_ref);
+      if private_names.contains(type_name) && reported.insert(type_name) {
+        diagnostics.push(doc_diagnostic(
+          doc_node,
+          "private-type-ref",
+          format!(
+            "Exported symbol `{}` references `{}`, which is not exported",
+            name, type_name
+          ),
+          format!(
+            "Export `{}` or remove it from the public signature",
+            type_name
+          ),
+        ));
+      }
+    }
+
+    if let Some(namespace_def) = &doc_node.namespace_def {
+      lint_doc_nodes(
+        &namespace_def.elements,
+        &format!("{}.", name),
+        private_names,
+        diagnostics,
+      );
+    }
+  }
+}
+
+/// Checks that every parameter of a function is documented with a `@param`
+/// tag, and that a non-void return type is documented with `@returns`.
+fn lint_function_js_doc(
+  doc_node: &doc::DocNode,
+  name: &str,
+  js_doc: &str,
+  function_def: &Value,
+  diagnostics: &mut Vec<LintDiagnostic>,
+) {
+  lazy_static::lazy_static! {
+    static ref PARAM_TAG_RE: Regex =
+      Regex::new(r"@param\s+(?:\{[^}]*\}\s+)?\[?([\w$]+)").unwrap();
+    static ref RETURNS_TAG_RE: Regex = Regex::new(r"@returns?\b").unwrap();
+  }
+  let documented_params: HashSet<&str> = PARAM_TAG_RE
+    .captures_iter(js_doc)
+    .filter_map(|captures| captures.get(1))
+    .map(|name| name.as_str())
+    .collect();
+
+  let params = function_def["params"]
+    .as_array()
+    .cloned()
+    .unwrap_or_default();
+  for param in &params {
+    if let Some(param_name) = param_name(param) {
+      if param_name != "this" && !documented_params.contains(param_name) {
+        diagnostics.push(doc_diagnostic(
+          doc_node,
+          "missing-param-tag",
+          format!("Parameter `{}` of `{}` is not documented", param_name, name),
+          format!("Add a `@param {}` tag to the JSDoc comment", param_name),
+        ));
+      }
+    }
+  }
+
+  let return_type = &function_def["returnType"];
+  if !return_type.is_null()
+    && !is_void_type(return_type)
+    && !RETURNS_TAG_RE.is_match(js_doc)
+  {
+    diagnostics.push(doc_diagnostic(
+      doc_node,
+      "missing-return-tag",
+      format!("The return value of `{}` is not documented", name),
+      "Add a `@returns` tag to the JSDoc comment".to_string(),
+    ));
+  }
+}
+
+/// The name a parameter is bound to, `None` for destructured parameters.
+fn param_name(param: &Value) -> Option<&str> {
+  match param["kind"].as_str()? {
+    "identifier" => param["name"].as_str(),
+    "rest" => param_name(&param["arg"]),
+    "assign" => param_name(&param["left"]),
+    _ => None,
+  }
+}
+
+/// Returns true for `void` and `Promise<void>`.
+fn is_void_type(ts_type: &Value) -> bool {
+  if ts_type["repr"] == "void" {
+    return true;
+  }
+  ts_type["kind"] == "typeRef"
+    && ts_type["typeRef"]["typeName"] == "Promise"
+    && ts_type["typeRef"]["typeParams"][0]["repr"] == "void"
+}
+
+/// Collects the names of all the types referenced in a serialized doc node.
+fn collect_type_refs<'a>(value: &'a Value, type_refs: &mut Vec<&'a str>) {
+  match value {
+    Value::Object(map) => {
+      if map.get("kind").and_then(Value::as_str) == Some("typeRef") {
+        if let Some(type_name) = map
+          .get("typeRef")
+          .and_then(|type_ref| type_ref.get("typeName"))
+          .and_then(Value::as_str)
+        {
+          type_refs.push(type_name);
+        }
+      }
+      for value in map.values() {
+        collect_type_refs(value, type_refs);
+      }
+    }
+    Value::Array(values) => {
+      for value in values {
+        collect_type_refs(value, type_refs);
+      }
+    }
+    _ => {}
+  }
+}
+
+const HTML_DOCS_STYLE: &str = r#"
+body { font-family: sans-serif; margin: 0; color: #222; }
+header { display: flex; gap: 1em; align-items: center; padding: 0.8em 2em; background: #f6f8fa; border-bottom: 1px solid #ddd; position: relative; }
+main { margin: 2em; max-width: 60em; }
+a { color: #0366d6; text-decoration: none; }
+table { border-collapse: collapse; }
+th, td { padding: 0.2em 0.8em; text-align: left; vertical-align: top; }
+td p { margin: 0; }
+pre { background: #f6f8fa; padding: 0.8em; overflow-x: auto; }
+.kind { color: #6a737d; font-size: 0.8em; text-transform: uppercase; }
+#search-results { position: absolute; top: 100%; list-style: none; margin: 0; padding: 0; background: #fff; border: 1px solid #ddd; }
+#search-results:empty { display: none; }
+#search-results li { padding: 0.2em 0.8em; }
+"#;
+
+const HTML_DOCS_SEARCH_SCRIPT: &str = r#"
+(function () {
+  const root = document.body.dataset.root;
+  const input = document.getElementById("search");
+  const results = document.getElementById("search-results");
+  input.addEventListener("input", () => {
+    const query = input.value.trim().toLowerCase();
+    results.textContent = "";
+    if (!query) {
+      return;
+    }
+    const matches = window.DENO_DOC_SEARCH_INDEX
+      .filter((symbol) => symbol.name.toLowerCase().includes(query))
+      .slice(0, 20);
+    for (const symbol of matches) {
+      const item = document.createElement("li");
+      const link = document.createElement("a");
+      link.href = root + symbol.href;
+      link.textContent = symbol.name;
+      const kind = document.createElement("span");
+      kind.className = "kind";
+      kind.textContent = " " + symbol.kind + " in " + symbol.module;
+      item.append(link, kind);
+      results.append(item);
+    }
+  });
+})();
+"#;
+
+/// The documentation of one of the entrypoints passed to `deno doc --html`.
+struct HtmlModule {
+  display_name: String,
+  slug: String,
+  doc_nodes: Vec<doc::DocNode>,
+}
+
+impl HtmlModule {
+  fn href(&self) -> String {
+    format!("{}/index.html", self.slug)
+  }
+
+  fn symbol_href(&self, qualified_name: &str) -> String {
+    format!("{}/~/{}.html", self.slug, qualified_name)
+  }
+}
+
+/// All the declarations which share a (namespace qualified) name, for example
+/// the overloads of a function or a class merged with a namespace.
+struct HtmlSymbol<'a> {
+  qualified_name: String,
+  nodes: Vec<&'a doc::DocNode>,
+}
+
+fn collect_html_symbols<'a>(
+  doc_nodes: &'a [doc::DocNode],
+  prefix: &str,
+  symbols: &mut Vec<HtmlSymbol<'a>>,
+) {
+  for doc_node in doc_nodes {
+    if doc_node.kind == doc::DocNodeKind::Import {
+      continue;
+    }
+    let qualified_name = format!("{}{}", prefix, doc_node.name);
+    match symbols
+      .iter_mut()
+      .find(|symbol| symbol.qualified_name == qualified_name)
+    {
+      Some(symbol) => symbol.nodes.push(doc_node),
+      None => symbols.push(HtmlSymbol {
+        qualified_name: qualified_name.clone(),
+        nodes: vec![doc_node],
+      }),
+    }
+    if let Some(namespace_def) = &doc_node.namespace_def {
+      collect_html_symbols(
+        &namespace_def.elements,
+        &format!("{}.", qualified_name),
+        symbols,
+      );
+    }
+  }
+}
+
+/// The order in which the kinds of symbols are listed on a module page.
+const HTML_KIND_ORDER: &[doc::DocNodeKind] = &[
+  doc::DocNodeKind::Namespace,
+  doc::DocNodeKind::Class,
+  doc::DocNodeKind::Enum,
+  doc::DocNodeKind::Variable,
+  doc::DocNodeKind::Function,
+  doc::DocNodeKind::Interface,
+  doc::DocNodeKind::TypeAlias,
+];
+
+fn kind_label(kind: &doc::DocNodeKind) -> &'static str {
+  match kind {
+    doc::DocNodeKind::Namespace => "Namespace",
+    doc::DocNodeKind::Class => "Class",
+    doc::DocNodeKind::Enum => "Enum",
+    doc::DocNodeKind::Variable => "Variable",
+    doc::DocNodeKind::Function => "Function",
+    doc::DocNodeKind::Interface => "Interface",
+    doc::DocNodeKind::TypeAlias => "Type Alias",
+    _ => "Symbol",
+  }
+}
+
+fn module_display_name(specifier: &ModuleSpecifier, cwd: &Path) -> String {
+  if specifier.scheme() == "file" {
+    if let Ok(path) = specifier.to_file_path() {
+      if let Ok(relative_path) = path.strip_prefix(cwd) {
+        return relative_path.to_string_lossy().replace('\\', "/");
+      }
+    }
+  }
+  specifier.to_string()
+}
+
+fn module_slug(display_name: &str) -> String {
+  display_name
+    .chars()
+    .map(|c| {
+      if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+        c
+      } else {
+        '_'
+      }
+    })
+    .collect::<String>()
+    .trim_start_matches('.')
+    .to_string()
+}
+
+/// Returns the slug of a module which isn't in `used_slugs` yet, adding a
+/// numeric suffix when distinct modules map to the same slug, for example
+/// `lib/mod.ts` and `lib_mod.ts`. Slugs are compared case insensitively, as
+/// they are used as directory names.
+fn unique_module_slug(
+  display_name: &str,
+  used_slugs: &mut HashSet<String>,
+) -> String {
+  let slug = module_slug(display_name);
+  let mut unique_slug = slug.clone();
+  let mut suffix = 1;
+  while !used_slugs.insert(unique_slug.to_lowercase()) {
+    suffix += 1;
+    unique_slug = format!("{}-{}", slug, suffix);
+  }
+  unique_slug
+}
+
+/// The symbols of all the modules of the site, which JSDoc `{@link}` tags are
+/// resolved against.
+struct HtmlSymbolIndex {
+  /// The qualified names of the symbols of each module, by the module slug.
+  module_symbols: HashMap<String, HashSet<String>>,
+  /// The page of each qualified name, in the first module which has it.
+  hrefs: HashMap<String, String>,
+}
+
+impl HtmlSymbolIndex {
+  fn new(modules: &[HtmlModule], symbols: &[Vec<HtmlSymbol>]) -> Self {
+    let mut module_symbols = HashMap::new();
+    let mut hrefs = HashMap::new();
+    for (module, symbols) in modules.iter().zip(symbols) {
+      let mut names = HashSet::new();
+      for symbol in symbols {
+        hrefs
+          .entry(symbol.qualified_name.clone())
+          .or_insert_with(|| module.symbol_href(&symbol.qualified_name));
+        names.insert(symbol.qualified_name.clone());
+      }
+      module_symbols.insert(module.slug.clone(), names);
+    }
+    Self {
+      module_symbols,
+      hrefs,
+    }
+  }
+
+  /// Resolves a symbol name used in a JSDoc `{@link}` tag to the path of its
+  /// page relative to the root of the site, preferring symbols of the module
+  /// the documentation belongs to.
+  fn resolve(&self, current: &HtmlModule, name: &str) -> Option<String> {
+    let in_current = self
+      .module_symbols
+      .get(&current.slug)
+      .map_or(false, |names| names.contains(name));
+    if in_current {
+      Some(current.symbol_href(name))
+    } else {
+      self.hrefs.get(name).cloned()
+    }
+  }
+}
+
+/// Renders JSDoc as Markdown, turning `{@link Symbol}` tags into links to
+/// the pages of the symbols.
+fn render_js_doc(
+  js_doc: &str,
+  root: &str,
+  index: &HtmlSymbolIndex,
+  current: &HtmlModule,
+) -> String {
+  lazy_static::lazy_static! {
+    static ref LINK_RE: Regex =
+      Regex::new(r"\{@link(?:code|plain)?\s+([^\s|}]+)(?:[\s|]+([^}]+))?\}")
+        .unwrap();
+  }
+  let markdown = LINK_RE.replace_all(js_doc, |captures: &regex::Captures| {
+    let name = &captures[1];
+    let text = captures.get(2).map_or(name, |text| text.as_str().trim());
+    match index.resolve(current, name) {
+      Some(href) => format!("[`{}`]({}{})", text, root, href),
+      None => format!("`{}`", text),
+    }
+  });
+
+  let options = pulldown_cmark::Options::ENABLE_TABLES
+    | pulldown_cmark::Options::ENABLE_STRIKETHROUGH
+    | pulldown_cmark::Options::ENABLE_TASKLISTS;
+  let mut html = String::new();
+  pulldown_cmark::html::push_html(
+    &mut html,
+    pulldown_cmark::Parser::new_ext(&markdown, options),
+  );
+  html
+}
+
+/// The first paragraph of the JSDoc of a symbol.
+fn js_doc_summary(doc_node: &doc::DocNode) -> &str {
+  doc_node
+    .js_doc
+    .as_deref()
+    .and_then(|js_doc| js_doc.split("\n\n").next())
+    .unwrap_or("")
+}
+
+fn html_docs_page(title: &str, root: &str, body: &str) -> String {
+  format!(
+    "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<link rel=\"stylesheet\" href=\"{}styles.css\">\n<script src=\"{}search_index.js\" defer></script>\n<script src=\"{}search.js\" defer></script>\n</head>\n<body data-root=\"{}\">\n<header><a href=\"{}index.html\">Index</a><input id=\"search\" type=\"search\" placeholder=\"Search\" autocomplete=\"off\"><ul id=\"search-results\"></ul></header>\n<main>\n{}</main>\n</body>\n</html>\n",
+    xml_util::escape(title),
+    root,
+    root,
+    root,
+    root,
+    root,
+    body
+  )
+}
+
+fn render_html_index(modules: &[HtmlModule]) -> String {
+  let mut rows = String::new();
+  for module in modules {
+    rows.push_str(&format!(
+      "<tr><td><a href=\"{}\">{}</a></td></tr>\n",
+      xml_util::escape(&module.href()),
+      xml_util::escape(&module.display_name),
+    ));
+  }
+  html_docs_page(
+    "Documentation",
+    "",
+    &format!(
+      "<h1>Documentation</h1>\n<table>\n<tr><th>Module</th></tr>\n{}</table>\n",
+      rows
+    ),
+  )
+}
+
+fn render_html_module(index: &HtmlSymbolIndex, module: &HtmlModule) -> String {
+  let root = "../";
+  let mut sections = String::new();
+  for kind in HTML_KIND_ORDER {
+    let mut rows = String::new();
+    for doc_node in module.doc_nodes.iter().filter(|n| &n.kind == kind) {
+      rows.push_str(&format!(
+        "<tr><td><a href=\"{}{}\">{}</a></td><td>{}</td></tr>\n",
+        root,
+        xml_util::escape(&module.symbol_href(&doc_node.name)),
+        xml_util::escape(&doc_node.name),
+        render_js_doc(js_doc_summary(doc_node), root, index, module),
+      ));
+    }
+    if !rows.is_empty() {
+      sections.push_str(&format!(
+        "<h2>{}</h2>\n<table>\n{}</table>\n",
+        kind_label(kind),
+        rows
+      ));
+    }
+  }
+  html_docs_page(
+    &module.display_name,
+    root,
+    &format!(
+      "<h1>{}</h1>\n{}",
+      xml_util::escape(&module.display_name),
+      sections
+    ),
+  )
+}
+
+fn render_html_symbol(
+  index: &HtmlSymbolIndex,
+  module: &HtmlModule,
+  symbol: &HtmlSymbol,
+  private: bool,
+) -> String {
+  let root = "../../";
+
+  let mut breadcrumbs = format!(
+    "<a href=\"{}{}\">{}</a>",
+    root,
+    xml_util::escape(&module.href()),
+    xml_util::escape(&module.display_name)
+  );
+  let parts: Vec<&str> = symbol.qualified_name.split('.').collect();
+  for (i, part) in parts.iter().enumerate().take(parts.len() - 1) {
+    breadcrumbs.push_str(&format!(
+      " / <a href=\"{}{}\">{}</a>",
+      root,
+      xml_util::escape(&module.symbol_href(&parts[..=i].join("."))),
+      xml_util::escape(part)
+    ));
+  }
+  breadcrumbs
+    .push_str(&format!(" / {}", xml_util::escape(parts[parts.len() - 1])));
+
+  let mut sections = String::new();
+  for doc_node in &symbol.nodes {
+    let mut signature_node = (*doc_node).clone();
+    signature_node.js_doc = None;
+    let signature = format!(
+      "{}",
+      doc::DocPrinter::new(&[signature_node], false, private)
+    );
+    let js_doc = doc_node
+      .js_doc
+      .as_deref()
+      .map(|js_doc| render_js_doc(js_doc, root, index, module))
+      .unwrap_or_default();
+
+    let mut members = String::new();
+    if let Some(namespace_def) = &doc_node.namespace_def {
+      for element in &namespace_def.elements {
+        let qualified_name =
+          format!("{}.{}", symbol.qualified_name, element.name);
+        members.push_str(&format!(
+          "<tr><td class=\"kind\">{}</td><td><a href=\"{}{}\">{}</a></td><td>{}</td></tr>\n",
+          kind_label(&element.kind),
+          root,
+          xml_util::escape(&module.symbol_href(&qualified_name)),
+          xml_util::escape(&element.name),
+          render_js_doc(js_doc_summary(element), root, index, module),
+        ));
+      }
+      members = format!("<h3>Members</h3>\n<table>\n{}</table>\n", members);
+    }
+
+    sections.push_str(&format!(
+      "<section>\n<p class=\"kind\">{}</p>\n<pre>{}</pre>\n{}{}</section>\n",
+      kind_label(&doc_node.kind),
+      xml_util::escape(signature.trim_end()),
+      js_doc,
+      members
+    ));
+  }
+
+  html_docs_page(
+    &symbol.qualified_name,
+    root,
+    &format!(
+      "<p>{}</p>\n<h1>{}</h1>\n{}",
+      breadcrumbs,
+      xml_util::escape(&symbol.qualified_name),
+      sections
+    ),
+  )
+}
+
+/// Writes a static site with an index of the modules, a page per module
+/// listing its exports and a page per symbol, returning the number of files
+/// written.
+fn write_html_docs(
+  out_dir: &Path,
+  modules: &[HtmlModule],
+  private: bool,
+) -> Result<usize, AnyError> {
+  let mut files_written = 0;
+  let mut write_file = |path: PathBuf, content: String| {
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)?;
+    files_written += 1;
+    Ok::<_, AnyError>(())
+  };
+
+  let module_symbols = modules
+    .iter()
+    .map(|module| {
+      let mut symbols = Vec::new();
+      collect_html_symbols(&module.doc_nodes, "", &mut symbols);
+      symbols
+    })
+    .collect::<Vec<_>>();
+  let index = HtmlSymbolIndex::new(modules, &module_symbols);
+
+  let mut search_index = Vec::new();
+  for (module, symbols) in modules.iter().zip(&module_symbols) {
+    write_file(
+      out_dir.join(module.href()),
+      render_html_module(&index, module),
+    )?;
+
+    for symbol in symbols {
+      write_file(
+        out_dir.join(module.symbol_href(&symbol.qualified_name)),
+        render_html_symbol(&index, module, symbol, private),
+      )?;
+      search_index.push(json!({
+        "name": symbol.qualified_name,
+        "kind": kind_label(&symbol.nodes[0].kind),
+        "module": module.display_name,
+        "href": module.symbol_href(&symbol.qualified_name),
+      }));
+    }
+  }
+
+  write_file(out_dir.join("index.html"), render_html_index(modules))?;
+  write_file(
+    out_dir.join("styles.css"),
+    HTML_DOCS_STYLE.trim_start().to_string(),
+  )?;
+  write_file(
+    out_dir.join("search.js"),
+    HTML_DOCS_SEARCH_SCRIPT.trim_start().to_string(),
+  )?;
+  write_file(
+    out_dir.join("search_index.js"),
+    format!(
+      "window.DENO_DOC_SEARCH_INDEX = {};\n",
+      serde_json::to_string(&search_index)?
+    ),
+  )?;
+
+  Ok(files_written)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_param_name() {
+    assert_eq!(
+      param_name(&json!({ "kind": "identifier", "name": "a" })),
+      Some("a")
+    );
+    assert_eq!(
+      param_name(&json!({
+        "kind": "rest",
+        "arg": { "kind": "identifier", "name": "rest" }
+      })),
+      Some("rest")
+    );
+    assert_eq!(
+      param_name(&json!({
+        "kind": "assign",
+        "left": { "kind": "identifier", "name": "b" },
+        "right": "1"
+      })),
+      Some("b")
+    );
+    assert_eq!(param_name(&json!({ "kind": "object", "props": [] })), None);
+  }
+
+  #[test]
+  fn test_is_void_type() {
+    assert!(is_void_type(&json!({ "repr": "void", "kind": "keyword" })));
+    assert!(is_void_type(&json!({
+      "repr": "Promise",
+      "kind": "typeRef",
+      "typeRef": {
+        "typeName": "Promise",
+        "typeParams": [{ "repr": "void", "kind": "keyword" }]
+      }
+    })));
+    assert!(!is_void_type(
+      &json!({ "repr": "string", "kind": "keyword" })
+    ));
+  }
+
+  #[test]
+  fn test_module_slug() {
+    assert_eq!(module_slug("mod.ts"), "mod.ts");
+    assert_eq!(module_slug("./lib/mod.ts"), "_lib_mod.ts");
+    assert_eq!(
+      module_slug("https://deno.land/std/testing/asserts.ts"),
+      "https___deno.land_std_testing_asserts.ts"
+    );
+  }
+
+  #[test]
+  fn test_unique_module_slug() {
+    let mut used_slugs = HashSet::new();
+    assert_eq!(
+      unique_module_slug("lib/mod.ts", &mut used_slugs),
+      "lib_mod.ts"
+    );
+    assert_eq!(
+      unique_module_slug("lib_mod.ts", &mut used_slugs),
+      "lib_mod.ts-2"
+    );
+    assert_eq!(
+      unique_module_slug("Lib_mod.ts", &mut used_slugs),
+      "Lib_mod.ts-3"
+    );
+    assert_eq!(unique_module_slug("mod.ts", &mut used_slugs), "mod.ts");
+  }
+
+  #[test]
+  fn test_write_html_docs_slug_collision() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let mut used_slugs = HashSet::new();
+    let modules = ["lib/mod.ts", "lib_mod.ts"]
+      .iter()
+      .map(|display_name| HtmlModule {
+        display_name: display_name.to_string(),
+        slug: unique_module_slug(display_name, &mut used_slugs),
+        doc_nodes: Vec::new(),
+      })
+      .collect::<Vec<_>>();
+    write_html_docs(temp_dir.path(), &modules, false).unwrap();
+    for (slug, display_name) in
+      [("lib_mod.ts", "lib/mod.ts"), ("lib_mod.ts-2", "lib_mod.ts")]
+    {
+      let page =
+        fs::read_to_string(temp_dir.path().join(slug).join("index.html"))
+          .unwrap();
+      assert!(page.contains(&format!("<h1>{}</h1>", display_name)));
+    }
+  }
+
+  #[test]
+  fn test_module_display_name() {
+    let cwd = std::env::current_dir().unwrap();
+    let specifier =
+      ModuleSpecifier::from_file_path(cwd.join("lib").join("mod.ts")).unwrap();
+    assert_eq!(module_display_name(&specifier, &cwd), "lib/mod.ts");
+    let specifier =
+      ModuleSpecifier::parse("https://deno.land/x/mod.ts").unwrap();
+    assert_eq!(
+      module_display_name(&specifier, &cwd),
+      "https://deno.land/x/mod.ts"
+    );
+  }
+}
@@ -8,15 +8,22 @@
 //! the same functions as ops available in JS runtime.
 
 use crate::colors;
+use crate::config_file::BracePosition;
 use crate::config_file::FmtConfig;
 use crate::config_file::FmtOptionsConfig;
+use crate::config_file::JsxQuoteStyle;
+use crate::config_file::OperatorPosition;
 use crate::config_file::ProseWrap;
+use crate::config_file::QuoteProps;
+use crate::config_file::TrailingCommas;
+use crate::deno_dir::DenoDir;
 use crate::diff::diff;
 use crate::file_watcher;
 use crate::file_watcher::ResolutionResult;
 use crate::flags::FmtFlags;
 use crate::fs_util::specifier_to_file_path;
 use crate::fs_util::{collect_files, get_extension, is_supported_ext_fmt};
+use crate::incremental_cache::IncrementalCache;
 use crate::text_encoding;
 use deno_ast::ParsedSource;
 use deno_core::anyhow::Context;
@@ -40,6 +47,7 @@ pub async fn format(
   fmt_flags: FmtFlags,
   watch: bool,
   maybe_fmt_config: Option<FmtConfig>,
+  deno_dir: &DenoDir,
 ) -> Result<(), AnyError> {
   let FmtFlags {
     files,
@@ -84,6 +92,11 @@ pub async fn format(
     &fmt_flags,
     maybe_fmt_config.map(|c| c.options).unwrap_or_default(),
   );
+  let incremental_cache = Arc::new(IncrementalCache::new(
+    &deno_dir.incremental_cache,
+    "fmt",
+    &fmt_options,
+  ));
 
   let resolver = |changed: Option<Vec<PathBuf>>| {
     let files_changed = changed.is_some();
@@ -125,13 +138,16 @@ pub async fn format(
       }
     }
   };
-  let operation = |(paths, fmt_options): (Vec<PathBuf>, FmtOptionsConfig)| async move {
-    if check {
-      check_source_files(paths, fmt_options).await?;
-    } else {
-      format_source_files(paths, fmt_options).await?;
+  let operation = |(paths, fmt_options): (Vec<PathBuf>, FmtOptionsConfig)| {
+    let incremental_cache = incremental_cache.clone();
+    async move {
+      if check {
+        check_source_files(paths, fmt_options, incremental_cache).await?;
+      } else {
+        format_source_files(paths, fmt_options, incremental_cache).await?;
+      }
+      Ok(())
     }
-    Ok(())
   };
 
   if watch {
@@ -253,6 +269,7 @@ pub fn format_parsed_source(
 async fn check_source_files(
   paths: Vec<PathBuf>,
   fmt_options: FmtOptionsConfig,
+  incremental_cache: Arc<IncrementalCache>,
 ) -> Result<(), AnyError> {
   let not_formatted_files_count = Arc::new(AtomicUsize::new(0));
   let checked_files_count = Arc::new(AtomicUsize::new(0));
@@ -266,10 +283,15 @@ async fn check_source_files(
     move |file_path| {
       checked_files_count.fetch_add(1, Ordering::Relaxed);
       let file_text = read_file_contents(&file_path)?.text;
+      if incremental_cache.is_file_same(&file_path, &file_text) {
+        return Ok(());
+      }
 
       match format_file(&file_path, &file_text, fmt_options.clone()) {
         Ok(formatted_text) => {
-          if formatted_text != file_text {
+          if formatted_text == file_text {
+            incremental_cache.update_file(&file_path, &file_text);
+          } else {
             not_formatted_files_count.fetch_add(1, Ordering::Relaxed);
             let _g = output_lock.lock().unwrap();
             let diff = diff(&file_text, &formatted_text);
@@ -309,6 +331,7 @@ async fn check_source_files(
 async fn format_source_files(
   paths: Vec<PathBuf>,
   fmt_options: FmtOptionsConfig,
+  incremental_cache: Arc<IncrementalCache>,
 ) -> Result<(), AnyError> {
   let formatted_files_count = Arc::new(AtomicUsize::new(0));
   let checked_files_count = Arc::new(AtomicUsize::new(0));
@@ -320,9 +343,13 @@ async fn format_source_files(
     move |file_path| {
       checked_files_count.fetch_add(1, Ordering::Relaxed);
       let file_contents = read_file_contents(&file_path)?;
+      if incremental_cache.is_file_same(&file_path, &file_contents.text) {
+        return Ok(());
+      }
 
       match format_file(&file_path, &file_contents.text, fmt_options.clone()) {
         Ok(formatted_text) => {
+          incremental_cache.update_file(&file_path, &formatted_text);
           if formatted_text != file_contents.text {
             write_file_contents(
               &file_path,
@@ -435,6 +462,54 @@ fn resolve_fmt_options(
     });
   }
 
+  if let Some(semi_colons) = fmt_flags.semi_colons {
+    options.semi_colons = Some(semi_colons);
+  }
+
+  if let Some(trailing_commas) = &fmt_flags.trailing_commas {
+    options.trailing_commas = Some(match trailing_commas.as_str() {
+      "always" => TrailingCommas::Always,
+      "never" => TrailingCommas::Never,
+      "only-multi-line" => TrailingCommas::OnlyMultiLine,
+      _ => unreachable!(),
+    });
+  }
+
+  if let Some(brace_position) = &fmt_flags.brace_position {
+    options.brace_position = Some(match brace_position.as_str() {
+      "maintain" => BracePosition::Maintain,
+      "same-line" => BracePosition::SameLine,
+      "next-line" => BracePosition::NextLine,
+      "same-line-unless-hanging" => BracePosition::SameLineUnlessHanging,
+      _ => unreachable!(),
+    });
+  }
+
+  if let Some(operator_position) = &fmt_flags.operator_position {
+    options.operator_position = Some(match operator_position.as_str() {
+      "maintain" => OperatorPosition::Maintain,
+      "same-line" => OperatorPosition::SameLine,
+      "next-line" => OperatorPosition::NextLine,
+      _ => unreachable!(),
+    });
+  }
+
+  if let Some(quote_props) = &fmt_flags.quote_props {
+    options.quote_props = Some(match quote_props.as_str() {
+      "as-needed" => QuoteProps::AsNeeded,
+      "preserve" => QuoteProps::Preserve,
+      _ => unreachable!(),
+    });
+  }
+
+  if let Some(jsx_quote_style) = &fmt_flags.jsx_quote_style {
+    options.jsx_quote_style = Some(match jsx_quote_style.as_str() {
+      "prefer-double" => JsxQuoteStyle::PreferDouble,
+      "prefer-single" => JsxQuoteStyle::PreferSingle,
+      _ => unreachable!(),
+    });
+  }
+
   options
 }
 
@@ -465,6 +540,81 @@ fn get_resolved_typescript_config(
     }
   }
 
+  if let Some(semi_colons) = options.semi_colons {
+    builder.semi_colons(if semi_colons {
+      dprint_plugin_typescript::configuration::SemiColons::Prefer
+    } else {
+      dprint_plugin_typescript::configuration::SemiColons::Asi
+    });
+  }
+
+  if let Some(trailing_commas) = options.trailing_commas {
+    builder.trailing_commas(match trailing_commas {
+      TrailingCommas::Always => {
+        dprint_plugin_typescript::configuration::TrailingCommas::Always
+      }
+      TrailingCommas::Never => {
+        dprint_plugin_typescript::configuration::TrailingCommas::Never
+      }
+      TrailingCommas::OnlyMultiLine => {
+        dprint_plugin_typescript::configuration::TrailingCommas::OnlyMultiLine
+      }
+    });
+  }
+
+  if let Some(brace_position) = options.brace_position {
+    builder.brace_position(match brace_position {
+      BracePosition::Maintain => {
+        dprint_plugin_typescript::configuration::BracePosition::Maintain
+      }
+      BracePosition::SameLine => {
+        dprint_plugin_typescript::configuration::BracePosition::SameLine
+      }
+      BracePosition::NextLine => {
+        dprint_plugin_typescript::configuration::BracePosition::NextLine
+      }
+      BracePosition::SameLineUnlessHanging => {
+        dprint_plugin_typescript::configuration::BracePosition::SameLineUnlessHanging
+      }
+    });
+  }
+
+  if let Some(operator_position) = options.operator_position {
+    builder.operator_position(match operator_position {
+      OperatorPosition::Maintain => {
+        dprint_plugin_typescript::configuration::OperatorPosition::Maintain
+      }
+      OperatorPosition::SameLine => {
+        dprint_plugin_typescript::configuration::OperatorPosition::SameLine
+      }
+      OperatorPosition::NextLine => {
+        dprint_plugin_typescript::configuration::OperatorPosition::NextLine
+      }
+    });
+  }
+
+  if let Some(quote_props) = options.quote_props {
+    builder.quote_props(match quote_props {
+      QuoteProps::AsNeeded => {
+        dprint_plugin_typescript::configuration::QuoteProps::AsNeeded
+      }
+      QuoteProps::Preserve => {
+        dprint_plugin_typescript::configuration::QuoteProps::Preserve
+      }
+    });
+  }
+
+  if let Some(jsx_quote_style) = options.jsx_quote_style {
+    builder.jsx_quote_style(match jsx_quote_style {
+      JsxQuoteStyle::PreferDouble => {
+        dprint_plugin_typescript::configuration::JsxQuoteStyle::PreferDouble
+      }
+      JsxQuoteStyle::PreferSingle => {
+        dprint_plugin_typescript::configuration::JsxQuoteStyle::PreferSingle
+      }
+    });
+  }
+
   builder.build()
 }
 
@@ -7,18 +7,29 @@
 //! the future it can be easily extended to provide
 //! the same functions as ops available in JS runtime.
 use crate::config_file::LintConfig;
+use crate::config_file::LintReporterKind;
+use crate::deno_dir::DenoDir;
 use crate::file_watcher::ResolutionResult;
 use crate::flags::LintFlags;
 use crate::fmt_errors;
 use crate::fs_util::{collect_files, is_supported_ext, specifier_to_file_path};
+use crate::incremental_cache::IncrementalCache;
 use crate::tools::fmt::run_parallelized;
+use crate::tools::lint_plugins::LintPluginHost;
+use crate::version;
 use crate::{colors, file_watcher};
+use deno_ast::swc::ast;
+use deno_ast::swc::visit::Visit;
+use deno_ast::swc::visit::VisitWith;
 use deno_ast::MediaType;
+use deno_ast::ParsedSource;
 use deno_core::anyhow::anyhow;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::error::JsStackFrame;
 use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::ModuleSpecifier;
 use deno_lint::diagnostic::LintDiagnostic;
 use deno_lint::linter::Linter;
 use deno_lint::linter::LinterBuilder;
@@ -27,24 +38,28 @@ use deno_lint::rules::LintRule;
 use log::debug;
 use log::info;
 use serde::Serialize;
+use std::collections::BTreeSet;
+use std::collections::HashSet;
 use std::fs;
 use std::io::{stdin, Read};
+use std::ops::Range;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
 static STDIN_FILE_NAME: &str = "_stdin.ts";
 
-#[derive(Clone, Debug)]
-pub enum LintReporterKind {
-  Pretty,
-  Json,
-}
+/// The maximum number of times fixes are applied to a file before giving up
+/// on reaching a stable result.
+const MAX_FIX_PASSES: usize = 10;
 
-fn create_reporter(kind: LintReporterKind) -> Box<dyn LintReporter + Send> {
+pub fn create_reporter(kind: LintReporterKind) -> Box<dyn LintReporter + Send> {
   match kind {
     LintReporterKind::Pretty => Box::new(PrettyLintReporter::new()),
     LintReporterKind::Json => Box::new(JsonLintReporter::new()),
+    LintReporterKind::Compact => Box::new(CompactLintReporter::new()),
+    LintReporterKind::Github => Box::new(GithubLintReporter::new()),
+    LintReporterKind::Sarif => Box::new(SarifLintReporter::new()),
   }
 }
 
@@ -52,6 +67,7 @@ pub async fn lint(
   maybe_lint_config: Option<LintConfig>,
   lint_flags: LintFlags,
   watch: bool,
+  deno_dir: &DenoDir,
 ) -> Result<(), AnyError> {
   let LintFlags {
     maybe_rules_tags,
@@ -60,6 +76,8 @@ pub async fn lint(
     files: args,
     ignore,
     json,
+    fix,
+    reporter,
     ..
   } = lint_flags;
   // First, prepare final configuration.
@@ -95,11 +113,35 @@ pub async fn lint(
 
   let reporter_kind = if json {
     LintReporterKind::Json
+  } else if let Some(reporter) = reporter {
+    match reporter.as_str() {
+      "pretty" => LintReporterKind::Pretty,
+      "json" => LintReporterKind::Json,
+      "compact" => LintReporterKind::Compact,
+      "github" => LintReporterKind::Github,
+      "sarif" => LintReporterKind::Sarif,
+      // validators in `flags.rs` makes other values unreachable
+      _ => unreachable!(),
+    }
   } else {
-    LintReporterKind::Pretty
+    maybe_lint_config
+      .as_ref()
+      .and_then(|lint_config| lint_config.report)
+      .unwrap_or(LintReporterKind::Pretty)
   };
 
   let has_error = Arc::new(AtomicBool::new(false));
+  // Plugin rules are always run, unless they are excluded.
+  let maybe_plugin_host = match maybe_lint_config.as_ref() {
+    Some(lint_config) if !lint_config.plugins.is_empty() => {
+      let exclude = maybe_rules_exclude
+        .clone()
+        .or_else(|| lint_config.rules.exclude.clone())
+        .unwrap_or_default();
+      Some(LintPluginHost::new(lint_config.plugins.clone(), exclude)?)
+    }
+    _ => None,
+  };
   // Try to get configured rules. CLI flags take precendence
   // over config file, ie. if there's `rules.include` in config file
   // and `--rules-include` CLI flag, only the flag value is taken into account.
@@ -109,6 +151,23 @@ pub async fn lint(
     maybe_rules_include,
     maybe_rules_exclude,
   )?;
+  let mut rule_codes = lint_rules
+    .iter()
+    .map(|rule| rule.code().to_string())
+    .collect::<Vec<_>>();
+  rule_codes.sort_unstable();
+  // Changing a plugin, or any module it imports, should invalidate the cached
+  // results, so the contents of every loaded plugin module are part of the
+  // cache state.
+  let plugin_hashes = maybe_plugin_host
+    .as_ref()
+    .map(|plugin_host| plugin_host.module_hashes().clone())
+    .unwrap_or_default();
+  let incremental_cache = Arc::new(IncrementalCache::new(
+    &deno_dir.incremental_cache,
+    "lint",
+    &(rule_codes, plugin_hashes),
+  ));
 
   let resolver = |changed: Option<Vec<PathBuf>>| {
     let files_changed = changed.is_some();
@@ -143,14 +202,21 @@ pub async fn lint(
 
   let operation = |paths: Vec<PathBuf>| async {
     let target_files_len = paths.len();
-    let reporter_kind = reporter_kind.clone();
     let reporter_lock = Arc::new(Mutex::new(create_reporter(reporter_kind)));
     run_parallelized(paths, {
       let has_error = has_error.clone();
       let lint_rules = lint_rules.clone();
       let reporter_lock = reporter_lock.clone();
+      let incremental_cache = incremental_cache.clone();
+      let maybe_plugin_host = maybe_plugin_host.clone();
       move |file_path| {
-        let r = lint_file(file_path.clone(), lint_rules.clone());
+        let r = lint_file(
+          file_path.clone(),
+          lint_rules.clone(),
+          maybe_plugin_host.as_ref(),
+          fix,
+          &incremental_cache,
+        );
         handle_lint_result(
           &file_path.to_string_lossy(),
           r,
@@ -175,9 +241,18 @@ pub async fn lint(
     file_watcher::watch_func(resolver, operation, "Lint").await?;
   } else {
     if args.len() == 1 && args[0].to_string_lossy() == "-" {
-      let reporter_lock =
-        Arc::new(Mutex::new(create_reporter(reporter_kind.clone())));
-      let r = lint_stdin(lint_rules);
+      if fix
+        && !matches!(
+          reporter_kind,
+          LintReporterKind::Pretty | LintReporterKind::Compact
+        )
+      {
+        return Err(generic_error(
+          "Lint fix on standard input can only be used with the pretty or compact reporter.",
+        ));
+      }
+      let reporter_lock = Arc::new(Mutex::new(create_reporter(reporter_kind)));
+      let r = lint_stdin(lint_rules, maybe_plugin_host.as_ref(), fix);
       handle_lint_result(
         STDIN_FILE_NAME,
         r,
@@ -250,37 +325,330 @@ pub fn create_linter(
 fn lint_file(
   file_path: PathBuf,
   lint_rules: Vec<Arc<dyn LintRule>>,
+  maybe_plugin_host: Option<&LintPluginHost>,
+  fix: bool,
+  incremental_cache: &IncrementalCache,
 ) -> Result<(Vec<LintDiagnostic>, String), AnyError> {
   let file_name = file_path.to_string_lossy().to_string();
   let source_code = fs::read_to_string(&file_path)?;
+  if incremental_cache.is_file_same(&file_path, &source_code) {
+    return Ok((Vec::new(), source_code));
+  }
   let media_type = MediaType::from(&file_path);
 
-  let linter = create_linter(media_type, lint_rules);
+  let (file_diagnostics, fixed_source_code) = lint_source(
+    file_name,
+    source_code.clone(),
+    media_type,
+    lint_rules,
+    maybe_plugin_host,
+    fix,
+  )?;
 
-  let (_, file_diagnostics) = linter.lint(file_name, source_code.clone())?;
+  if fixed_source_code != source_code {
+    fs::write(&file_path, &fixed_source_code)?;
+  }
+  if file_diagnostics.is_empty() {
+    incremental_cache.update_file(&file_path, &fixed_source_code);
+  }
 
-  Ok((file_diagnostics, source_code))
+  Ok((file_diagnostics, fixed_source_code))
 }
 
 /// Lint stdin and write result to stdout.
 /// Treats input as TypeScript.
-/// Compatible with `--json` flag.
+/// Compatible with `--json` flag, unless fixing, in which case the fixed
+/// source is written to stdout instead.
 fn lint_stdin(
   lint_rules: Vec<Arc<dyn LintRule>>,
+  maybe_plugin_host: Option<&LintPluginHost>,
+  fix: bool,
 ) -> Result<(Vec<LintDiagnostic>, String), AnyError> {
   let mut source_code = String::new();
   if stdin().read_to_string(&mut source_code).is_err() {
     return Err(generic_error("Failed to read from stdin"));
   }
 
-  let linter = create_linter(MediaType::TypeScript, lint_rules);
+  let (file_diagnostics, source_code) = lint_source(
+    STDIN_FILE_NAME.to_string(),
+    source_code,
+    MediaType::TypeScript,
+    lint_rules,
+    maybe_plugin_host,
+    fix,
+  )?;
+
+  if fix {
+    print!("{}", source_code);
+  }
+
+  Ok((file_diagnostics, source_code))
+}
 
-  let (_, file_diagnostics) =
-    linter.lint(STDIN_FILE_NAME.to_string(), source_code.clone())?;
+/// Lint the source code, and when `fix` is set, repeatedly apply the fixes
+/// for the reported diagnostics until no more fixes are available. Returns
+/// the remaining diagnostics along with the (possibly fixed) source code.
+fn lint_source(
+  file_name: String,
+  mut source_code: String,
+  media_type: MediaType,
+  lint_rules: Vec<Arc<dyn LintRule>>,
+  maybe_plugin_host: Option<&LintPluginHost>,
+  fix: bool,
+) -> Result<(Vec<LintDiagnostic>, String), AnyError> {
+  for _ in 0..MAX_FIX_PASSES {
+    let (parsed_source, file_diagnostics) = run_linter(
+      file_name.clone(),
+      source_code.clone(),
+      media_type,
+      lint_rules.clone(),
+      maybe_plugin_host,
+    )?;
+    if !fix {
+      return Ok((file_diagnostics, source_code));
+    }
+    let fixes = get_lint_fixes(&parsed_source, &file_diagnostics)
+      .into_iter()
+      .flatten()
+      .collect::<Vec<_>>();
+    if fixes.is_empty() {
+      return Ok((file_diagnostics, source_code));
+    }
+    source_code = apply_lint_fixes(&source_code, fixes);
+  }
 
+  debug!("{} did not stabilize after applying fixes", file_name);
+  let (_, file_diagnostics) = run_linter(
+    file_name,
+    source_code.clone(),
+    media_type,
+    lint_rules,
+    maybe_plugin_host,
+  )?;
   Ok((file_diagnostics, source_code))
 }
 
+/// Run the built-in rules followed by the rules of any plugins.
+fn run_linter(
+  file_name: String,
+  source_code: String,
+  media_type: MediaType,
+  lint_rules: Vec<Arc<dyn LintRule>>,
+  maybe_plugin_host: Option<&LintPluginHost>,
+) -> Result<(ParsedSource, Vec<LintDiagnostic>), AnyError> {
+  let linter = create_linter(media_type, lint_rules);
+  let (parsed_source, mut file_diagnostics) =
+    linter.lint(file_name, source_code)?;
+  if let Some(plugin_host) = maybe_plugin_host {
+    plugin_host.lint(&parsed_source, &mut file_diagnostics)?;
+  }
+  Ok((parsed_source, file_diagnostics))
+}
+
+/// A single text replacement, where the range is expressed as byte offsets
+/// into the linted source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintFixEdit {
+  pub range: Range<usize>,
+  pub new_text: String,
+}
+
+/// An automatic fix for a lint diagnostic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintFix {
+  pub description: String,
+  pub edits: Vec<LintFixEdit>,
+}
+
+/// Compute the available fixes for the diagnostics of a parsed source. The
+/// returned vector is parallel to `diagnostics`, with `None` for diagnostics
+/// which can't be fixed automatically.
+pub fn get_lint_fixes(
+  parsed_source: &ParsedSource,
+  diagnostics: &[LintDiagnostic],
+) -> Vec<Option<LintFix>> {
+  let source = parsed_source.source().text_str();
+  let mut prefer_const_fixes = None;
+
+  diagnostics
+    .iter()
+    .map(|d| match d.code.as_str() {
+      "no-extra-semi" => {
+        let range = d.range.start.byte_pos..d.range.end.byte_pos;
+        if source.get(range.clone()) == Some(";") {
+          Some(LintFix {
+            description: "Remove the extra semicolon".to_string(),
+            edits: vec![LintFixEdit {
+              range,
+              new_text: "".to_string(),
+            }],
+          })
+        } else {
+          None
+        }
+      }
+      "prefer-const" => {
+        let fixes = prefer_const_fixes.get_or_insert_with(|| {
+          PreferConstFixer::collect(parsed_source, diagnostics)
+        });
+        fixes
+          .iter()
+          .find(|(binding_positions, _)| {
+            binding_positions.contains(&d.range.start.byte_pos)
+          })
+          .map(|(_, edit)| LintFix {
+            description: "Use `const` instead of `let`".to_string(),
+            edits: vec![edit.clone()],
+          })
+      }
+      _ => None,
+    })
+    .collect()
+}
+
+/// Apply fixes to the source code. Fixes which overlap with an earlier fix
+/// are skipped, as they will be revisited on the next pass.
+fn apply_lint_fixes(source_code: &str, fixes: Vec<LintFix>) -> String {
+  let mut edits: Vec<LintFixEdit> = Vec::new();
+  for fix in fixes {
+    let overlaps = fix.edits.iter().any(|edit| {
+      edits.iter().any(|e| {
+        e == edit
+          || (edit.range.start < e.range.end && e.range.start < edit.range.end)
+      })
+    });
+    if !overlaps {
+      edits.extend(fix.edits);
+    }
+  }
+  edits.sort_by_key(|e| e.range.start);
+
+  let mut text = source_code.to_string();
+  for edit in edits.into_iter().rev() {
+    text.replace_range(edit.range, &edit.new_text);
+  }
+  text
+}
+
+/// Finds `let` declarations where every binding has been reported by the
+/// `prefer-const` rule, which means the whole declaration can be changed to
+/// a `const` declaration.
+struct PreferConstFixer<'a> {
+  source: &'a str,
+  reported: HashSet<usize>,
+  fixes: Vec<(Vec<usize>, LintFixEdit)>,
+}
+
+impl<'a> PreferConstFixer<'a> {
+  fn collect(
+    parsed_source: &'a ParsedSource,
+    diagnostics: &[LintDiagnostic],
+  ) -> Vec<(Vec<usize>, LintFixEdit)> {
+    let mut fixer = PreferConstFixer {
+      source: parsed_source.source().text_str(),
+      reported: diagnostics
+        .iter()
+        .filter(|d| d.code == "prefer-const")
+        .map(|d| d.range.start.byte_pos)
+        .collect(),
+      fixes: Vec::new(),
+    };
+    parsed_source.module().visit_with(&mut fixer);
+    fixer.fixes
+  }
+
+  fn check_var_decl(&mut self, node: &ast::VarDecl, is_loop_head: bool) {
+    if node.kind != ast::VarDeclKind::Let
+      || (!is_loop_head && node.decls.iter().any(|d| d.init.is_none()))
+    {
+      return;
+    }
+    let mut binding_positions = Vec::new();
+    for decl in &node.decls {
+      if !collect_binding_positions(&decl.name, &mut binding_positions) {
+        return;
+      }
+    }
+    if binding_positions.is_empty()
+      || !binding_positions.iter().all(|p| self.reported.contains(p))
+    {
+      return;
+    }
+    let start = node.span.lo.0 as usize;
+    let range = start..start + "let".len();
+    if self.source.get(range.clone()) == Some("let") {
+      self.fixes.push((
+        binding_positions,
+        LintFixEdit {
+          range,
+          new_text: "const".to_string(),
+        },
+      ));
+    }
+  }
+}
+
+impl Visit for PreferConstFixer<'_> {
+  fn visit_var_decl(&mut self, node: &ast::VarDecl) {
+    self.check_var_decl(node, false);
+    node.visit_children_with(self);
+  }
+
+  fn visit_for_in_stmt(&mut self, node: &ast::ForInStmt) {
+    if let ast::VarDeclOrPat::VarDecl(var_decl) = &node.left {
+      self.check_var_decl(var_decl, true);
+    }
+    node.visit_children_with(self);
+  }
+
+  fn visit_for_of_stmt(&mut self, node: &ast::ForOfStmt) {
+    if let ast::VarDeclOrPat::VarDecl(var_decl) = &node.left {
+      self.check_var_decl(var_decl, true);
+    }
+    node.visit_children_with(self);
+  }
+}
+
+/// Collect the start positions of all the identifiers bound by a pattern,
+/// returning `false` if the pattern contains something other than bindings.
+fn collect_binding_positions(
+  pat: &ast::Pat,
+  positions: &mut Vec<usize>,
+) -> bool {
+  match pat {
+    ast::Pat::Ident(binding_ident) => {
+      positions.push(binding_ident.id.span.lo.0 as usize);
+      true
+    }
+    ast::Pat::Array(array_pat) => array_pat
+      .elems
+      .iter()
+      .flatten()
+      .all(|elem| collect_binding_positions(elem, positions)),
+    ast::Pat::Object(object_pat) => {
+      object_pat.props.iter().all(|prop| match prop {
+        ast::ObjectPatProp::KeyValue(prop) => {
+          collect_binding_positions(&prop.value, positions)
+        }
+        ast::ObjectPatProp::Assign(prop) => {
+          positions.push(prop.key.span.lo.0 as usize);
+          true
+        }
+        ast::ObjectPatProp::Rest(rest_pat) => {
+          collect_binding_positions(&rest_pat.arg, positions)
+        }
+      })
+    }
+    ast::Pat::Rest(rest_pat) => {
+      collect_binding_positions(&rest_pat.arg, positions)
+    }
+    ast::Pat::Assign(assign_pat) => {
+      collect_binding_positions(&assign_pat.left, positions)
+    }
+    _ => false,
+  }
+}
+
 fn handle_lint_result(
   file_path: &str,
   result: Result<(Vec<LintDiagnostic>, String), AnyError>,
@@ -304,7 +672,7 @@ fn handle_lint_result(
   }
 }
 
-trait LintReporter {
+pub trait LintReporter {
   fn visit_diagnostic(&mut self, d: &LintDiagnostic, source_lines: Vec<&str>);
   fn visit_error(&mut self, file_path: &str, err: &AnyError);
   fn close(&mut self, check_count: usize);
@@ -355,17 +723,7 @@ impl LintReporter for PrettyLintReporter {
   }
 
   fn close(&mut self, check_count: usize) {
-    match self.lint_count {
-      1 => info!("Found 1 problem"),
-      n if n > 1 => info!("Found {} problems", self.lint_count),
-      _ => (),
-    }
-
-    match check_count {
-      n if n <= 1 => info!("Checked {} file", n),
-      n if n > 1 => info!("Checked {} files", n),
-      _ => unreachable!(),
-    }
+    report_summary(self.lint_count, check_count);
   }
 }
 
@@ -466,7 +824,226 @@ impl LintReporter for JsonLintReporter {
   }
 }
 
-fn sort_diagnostics(diagnostics: &mut Vec<LintDiagnostic>) {
+struct CompactLintReporter {
+  lint_count: u32,
+}
+
+impl CompactLintReporter {
+  fn new() -> CompactLintReporter {
+    CompactLintReporter { lint_count: 0 }
+  }
+}
+
+impl LintReporter for CompactLintReporter {
+  fn visit_diagnostic(&mut self, d: &LintDiagnostic, _source_lines: Vec<&str>) {
+    self.lint_count += 1;
+
+    eprintln!(
+      "{}: line {}, col {} - {} ({})",
+      d.filename,
+      d.range.start.line_index + 1,
+      d.range.start.column_index + 1,
+      d.message,
+      d.code
+    );
+  }
+
+  fn visit_error(&mut self, file_path: &str, err: &AnyError) {
+    eprintln!("Error linting: {}", file_path);
+    eprintln!("   {}", err);
+  }
+
+  fn close(&mut self, check_count: usize) {
+    report_summary(self.lint_count, check_count);
+  }
+}
+
+/// Reports diagnostics as workflow commands, which GitHub Actions turns into
+/// annotations on the changed lines of a pull request.
+struct GithubLintReporter {
+  lint_count: u32,
+}
+
+impl GithubLintReporter {
+  fn new() -> GithubLintReporter {
+    GithubLintReporter { lint_count: 0 }
+  }
+}
+
+/// Escape the message of a workflow command.
+fn escape_github_data(data: &str) -> String {
+  data
+    .replace('%', "%25")
+    .replace('\r', "%0D")
+    .replace('\n', "%0A")
+}
+
+/// Escape a property value of a workflow command.
+fn escape_github_property(property: &str) -> String {
+  escape_github_data(property)
+    .replace(':', "%3A")
+    .replace(',', "%2C")
+}
+
+impl LintReporter for GithubLintReporter {
+  fn visit_diagnostic(&mut self, d: &LintDiagnostic, _source_lines: Vec<&str>) {
+    self.lint_count += 1;
+
+    let mut message = d.message.clone();
+    if let Some(hint) = &d.hint {
+      message.push('\n');
+      message.push_str(hint);
+    }
+    println!(
+      "::error file={},line={},endLine={},col={},endColumn={},title={}::{}",
+      escape_github_property(&d.filename),
+      d.range.start.line_index + 1,
+      d.range.end.line_index + 1,
+      d.range.start.column_index + 1,
+      d.range.end.column_index + 1,
+      escape_github_property(&d.code),
+      escape_github_data(&message)
+    );
+  }
+
+  fn visit_error(&mut self, file_path: &str, err: &AnyError) {
+    println!(
+      "::error file={}::{}",
+      escape_github_property(file_path),
+      escape_github_data(&err.to_string())
+    );
+  }
+
+  fn close(&mut self, check_count: usize) {
+    report_summary(self.lint_count, check_count);
+  }
+}
+
+struct SarifLintReporter {
+  diagnostics: Vec<LintDiagnostic>,
+  errors: Vec<LintError>,
+}
+
+impl SarifLintReporter {
+  fn new() -> SarifLintReporter {
+    SarifLintReporter {
+      diagnostics: Vec::new(),
+      errors: Vec::new(),
+    }
+  }
+}
+
+fn to_sarif_uri(file_path: &str) -> String {
+  ModuleSpecifier::from_file_path(file_path)
+    .map(|specifier| specifier.to_string())
+    .unwrap_or_else(|_| file_path.to_string())
+}
+
+impl LintReporter for SarifLintReporter {
+  fn visit_diagnostic(&mut self, d: &LintDiagnostic, _source_lines: Vec<&str>) {
+    self.diagnostics.push(d.clone());
+  }
+
+  fn visit_error(&mut self, file_path: &str, err: &AnyError) {
+    self.errors.push(LintError {
+      file_path: file_path.to_string(),
+      message: err.to_string(),
+    });
+  }
+
+  fn close(&mut self, _check_count: usize) {
+    sort_diagnostics(&mut self.diagnostics);
+    let rule_codes = self
+      .diagnostics
+      .iter()
+      .map(|d| d.code.as_str())
+      .collect::<BTreeSet<_>>();
+    let results = self
+      .diagnostics
+      .iter()
+      .map(|d| {
+        let mut message = d.message.clone();
+        if let Some(hint) = &d.hint {
+          message.push('\n');
+          message.push_str(hint);
+        }
+        json!({
+          "ruleId": d.code,
+          "level": "error",
+          "message": { "text": message },
+          "locations": [{
+            "physicalLocation": {
+              "artifactLocation": { "uri": to_sarif_uri(&d.filename) },
+              "region": {
+                "startLine": d.range.start.line_index + 1,
+                "startColumn": d.range.start.column_index + 1,
+                "endLine": d.range.end.line_index + 1,
+                "endColumn": d.range.end.column_index + 1,
+              },
+            },
+          }],
+        })
+      })
+      .collect::<Vec<_>>();
+    let notifications = self
+      .errors
+      .iter()
+      .map(|e| {
+        json!({
+          "level": "error",
+          "message": { "text": e.message },
+          "locations": [{
+            "physicalLocation": {
+              "artifactLocation": { "uri": to_sarif_uri(&e.file_path) },
+            },
+          }],
+        })
+      })
+      .collect::<Vec<_>>();
+    let sarif = json!({
+      "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+      "version": "2.1.0",
+      "runs": [{
+        "tool": {
+          "driver": {
+            "name": "deno lint",
+            "version": version::deno(),
+            "informationUri": "https://lint.deno.land",
+            "rules": rule_codes
+              .into_iter()
+              .map(|code| json!({
+                "id": code,
+                "helpUri": format!("https://lint.deno.land/#{}", code),
+              }))
+              .collect::<Vec<_>>(),
+          },
+        },
+        "invocations": [{
+          "executionSuccessful": self.errors.is_empty(),
+          "toolExecutionNotifications": notifications,
+        }],
+        "results": results,
+      }],
+    });
+    println!("{}", serde_json::to_string_pretty(&sarif).unwrap());
+  }
+}
+
+fn report_summary(lint_count: u32, check_count: usize) {
+  match lint_count {
+    1 => info!("Found 1 problem"),
+    n if n > 1 => info!("Found {} problems", lint_count),
+    _ => (),
+  }
+
+  match check_count {
+    n if n <= 1 => info!("Checked {} file", n),
+    n if n > 1 => info!("Checked {} files", n),
+    _ => unreachable!(),
+  }
+}
+
+pub fn sort_diagnostics(diagnostics: &mut Vec<LintDiagnostic>) {
   // Sort so that we guarantee a deterministic output which is useful for tests
   diagnostics.sort_by(|a, b| {
     use std::cmp::Ordering;
@@ -574,4 +1151,36 @@ mod test {
     recommended_rule_names.sort();
     assert_eq!(rule_names, recommended_rule_names);
   }
+
+  fn lint_and_fix(source_code: &str) -> (Vec<String>, String) {
+    let (diagnostics, fixed_source_code) = lint_source(
+      "file:///a.ts".to_string(),
+      source_code.to_string(),
+      MediaType::TypeScript,
+      get_recommended_rules(),
+      None,
+      true,
+    )
+    .unwrap();
+    let codes = diagnostics.into_iter().map(|d| d.code).collect();
+    (codes, fixed_source_code)
+  }
+
+  #[test]
+  fn fix_prefer_const_and_no_extra_semi() {
+    let (codes, fixed) =
+      lint_and_fix("let a = 1;;\nfor (let b of [a]) {\n  console.log(b);\n}\n");
+    assert!(codes.is_empty());
+    assert_eq!(
+      fixed,
+      "const a = 1;\nfor (const b of [a]) {\n  console.log(b);\n}\n"
+    );
+  }
+
+  #[test]
+  fn fix_skips_partially_reassigned_declarations() {
+    let source_code = "let a = 1, b = 2;\nb = 3;\nconsole.log(a, b);\n";
+    let (_, fixed) = lint_and_fix(source_code);
+    assert_eq!(fixed, source_code);
+  }
 }
@@ -0,0 +1,483 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+//! This module provides support for project-local lint rules, which are
+//! written in JavaScript or TypeScript and listed in the `"lint.plugins"`
+//! section of the configuration file.
+//!
+//! A plugin module has a default export with a `rules` object, mapping rule
+//! codes to functions. For every linted file, each rule is called with a
+//! context containing the file name, the source text and the serialized swc
+//! AST of the module, and reports problems via `context.report()`:
+//!
+//! ```ts
+//! export default {
+//!   rules: {
+//!     "no-lodash": (context) => {
+//!       for (const item of context.ast.body) {
+//!         if (item.type === "ImportDeclaration" && item.source.value === "lodash") {
+//!           context.report({ span: item.span, message: "Use std instead." });
+//!         }
+//!       }
+//!     },
+//!   },
+//! };
+//! ```
+//!
+//! The plugins are run in a `JsRuntime` on a dedicated thread. The runtime
+//! only has the op used to respond to the host, so plugins have no access to
+//! the file system, the network or the environment, and may only import other
+//! local modules.
+
+use crate::ast::transpile;
+use crate::ast::EmitOptions;
+use crate::checksum;
+use crate::fs_util::specifier_to_file_path;
+use deno_ast::swc::common::BytePos;
+use deno_ast::MediaType;
+use deno_ast::ParsedSource;
+use deno_core::anyhow::Context;
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::futures::FutureExt;
+use deno_core::located_script_name;
+use deno_core::op_sync;
+use deno_core::resolve_import;
+use deno_core::serde::Deserialize;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::JsRuntime;
+use deno_core::ModuleLoader;
+use deno_core::ModuleSource;
+use deno_core::ModuleSourceFuture;
+use deno_core::ModuleSpecifier;
+use deno_core::OpState;
+use deno_core::RuntimeOptions;
+use deno_lint::diagnostic::LintDiagnostic;
+use deno_lint::diagnostic::Position;
+use deno_lint::diagnostic::Range;
+use deno_runtime::tokio_util::create_basic_runtime;
+use std::cell::RefCell;
+use std::fs;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// The rule codes of `deno_lint` which report on ignore directives, and so
+/// would otherwise complain about directives mentioning plugin rules.
+const IGNORE_DIRECTIVE_RULES: &[&str] =
+  &["ban-unused-ignore", "ban-unknown-rule-code"];
+
+static HOST_SOURCE: &str = r#"
+((window) => {
+  const rules = [];
+
+  function respond(response) {
+    Deno.core.opSync("op_lint_plugins_respond", response);
+  }
+
+  function errorMessage(error) {
+    return error instanceof Error ? (error.stack ?? error.message) : String(error);
+  }
+
+  async function load(specifiers, exclude) {
+    try {
+      for (const specifier of specifiers) {
+        const plugin = (await import(specifier)).default;
+        if (plugin === null || typeof plugin !== "object" ||
+          plugin.rules === null || typeof plugin.rules !== "object") {
+          throw new TypeError(
+            `The lint plugin "${specifier}" must have a default export with a "rules" object.`,
+          );
+        }
+        for (const [code, rule] of Object.entries(plugin.rules)) {
+          if (typeof rule !== "function") {
+            throw new TypeError(
+              `The lint rule "${code}" of the plugin "${specifier}" must be a function.`,
+            );
+          }
+          if (!exclude.includes(code)) {
+            rules.push({ code, rule });
+          }
+        }
+      }
+      respond({ codes: rules.map(({ code }) => code) });
+    } catch (error) {
+      respond({ error: errorMessage(error) });
+    }
+  }
+
+  function run({ filename, source, ast }) {
+    const diagnostics = [];
+    for (const { code, rule } of rules) {
+      const context = Object.freeze({
+        filename,
+        source,
+        ast,
+        report({ span, message, hint }) {
+          diagnostics.push({
+            code,
+            message: String(message),
+            hint: hint === undefined ? null : String(hint),
+            start: span.start,
+            end: span.end,
+          });
+        },
+      });
+      try {
+        rule(context);
+      } catch (error) {
+        respond({
+          error: `The lint rule "${code}" failed on "${filename}": ${errorMessage(error)}`,
+        });
+        return;
+      }
+    }
+    respond({ diagnostics });
+  }
+
+  window.__lintPlugins = { load, run };
+})(globalThis);
+"#;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PluginDiagnostic {
+  code: String,
+  message: String,
+  hint: Option<String>,
+  start: u32,
+  end: u32,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PluginResponse {
+  error: Option<String>,
+  codes: Option<Vec<String>>,
+  diagnostics: Option<Vec<PluginDiagnostic>>,
+}
+
+/// The checksums of the sources of the modules the plugins were loaded from,
+/// keyed by module specifier.
+pub type PluginModuleHashes = Vec<(String, String)>;
+
+type Request = (
+  String,
+  String,
+  serde_json::Value,
+  mpsc::Sender<Result<Vec<PluginDiagnostic>, AnyError>>,
+);
+
+/// A handle to the thread running the lint plugins, which can be cloned and
+/// shared between the threads linting files.
+#[derive(Clone)]
+pub struct LintPluginHost {
+  sender: mpsc::Sender<Request>,
+  codes: Arc<Vec<String>>,
+  module_hashes: Arc<PluginModuleHashes>,
+}
+
+impl LintPluginHost {
+  /// Load the plugin modules, skipping the rules which are in `exclude`.
+  pub fn new(
+    plugins: Vec<ModuleSpecifier>,
+    exclude: Vec<String>,
+  ) -> Result<Self, AnyError> {
+    let (sender, receiver) = mpsc::channel::<Request>();
+    let (ready_sender, ready_receiver) = mpsc::channel();
+    thread::spawn(move || {
+      let runtime = create_basic_runtime();
+      let mut js_runtime = match runtime.block_on(load(plugins, exclude)) {
+        Ok((js_runtime, codes, module_hashes)) => {
+          if ready_sender.send(Ok((codes, module_hashes))).is_err() {
+            return;
+          }
+          js_runtime
+        }
+        Err(err) => {
+          let _ = ready_sender.send(Err(err));
+          return;
+        }
+      };
+      while let Ok((file_name, source, ast, sender)) = receiver.recv() {
+        let _ = sender.send(run(&mut js_runtime, file_name, source, ast));
+      }
+    });
+
+    let (codes, module_hashes) = ready_receiver
+      .recv()
+      .map_err(|_| generic_error("The lint plugin thread exited."))??;
+    Ok(Self {
+      sender,
+      codes: Arc::new(codes),
+      module_hashes: Arc::new(module_hashes),
+    })
+  }
+
+  /// The checksums of every module loaded by the plugins, including the local
+  /// modules they import, so that changes to any of them can be detected.
+  pub fn module_hashes(&self) -> &PluginModuleHashes {
+    &self.module_hashes
+  }
+
+  /// Run the plugin rules on a parsed source, appending their diagnostics to
+  /// the ones reported by the built-in rules.
+  pub fn lint(
+    &self,
+    parsed_source: &ParsedSource,
+    diagnostics: &mut Vec<LintDiagnostic>,
+  ) -> Result<(), AnyError> {
+    if self.codes.is_empty() {
+      return Ok(());
+    }
+    let file_name = parsed_source.specifier().to_string();
+    let source = parsed_source.source().text_str().to_string();
+    let ast = serde_json::to_value(parsed_source.module())?;
+    let (sender, receiver) = mpsc::channel();
+    self
+      .sender
+      .send((file_name.clone(), source, ast, sender))
+      .map_err(|_| generic_error("The lint plugin thread exited."))?;
+    let plugin_diagnostics = receiver
+      .recv()
+      .map_err(|_| generic_error("The lint plugin thread exited."))??;
+
+    diagnostics.retain(|d| {
+      !IGNORE_DIRECTIVE_RULES.contains(&d.code.as_str())
+        || !self
+          .codes
+          .iter()
+          .any(|code| d.message.contains(&format!("\"{}\"", code)))
+    });
+    let ignores = IgnoreDirectives::new(parsed_source);
+    for d in plugin_diagnostics {
+      let start = position(parsed_source, d.start);
+      if ignores.is_ignored(&d.code, start.line_index) {
+        continue;
+      }
+      diagnostics.push(LintDiagnostic {
+        range: Range {
+          start,
+          end: position(parsed_source, d.end),
+        },
+        filename: file_name.clone(),
+        message: d.message,
+        code: d.code,
+        hint: d.hint,
+      });
+    }
+    Ok(())
+  }
+}
+
+fn position(parsed_source: &ParsedSource, pos: u32) -> Position {
+  let line_and_column =
+    parsed_source.source().line_and_column_index(BytePos(pos));
+  Position {
+    line_index: line_and_column.line_index,
+    column_index: line_and_column.column_index,
+    byte_pos: pos as usize,
+  }
+}
+
+/// The `deno-lint-ignore` and `deno-lint-ignore-file` directives of a module,
+/// which `deno_lint` only applies to its own rules.
+struct IgnoreDirectives {
+  /// `None` when the whole file is ignored.
+  file_codes: Option<Vec<String>>,
+  /// The line a directive applies to, along with its codes.
+  line_codes: Vec<(usize, Vec<String>)>,
+}
+
+impl IgnoreDirectives {
+  fn new(parsed_source: &ParsedSource) -> Self {
+    let mut directives = IgnoreDirectives {
+      file_codes: Some(Vec::new()),
+      line_codes: Vec::new(),
+    };
+    for comment in parsed_source.comments().get_vec() {
+      let mut words = comment.text.split_whitespace();
+      let codes = |words: std::str::SplitWhitespace| {
+        words.map(String::from).collect::<Vec<_>>()
+      };
+      match words.next() {
+        Some("deno-lint-ignore-file") => {
+          let codes = codes(words);
+          if codes.is_empty() {
+            directives.file_codes = None;
+          } else if let Some(file_codes) = directives.file_codes.as_mut() {
+            file_codes.extend(codes);
+          }
+        }
+        Some("deno-lint-ignore") => {
+          let line_index = parsed_source
+            .source()
+            .line_and_column_index(comment.span.hi)
+            .line_index;
+          directives.line_codes.push((line_index + 1, codes(words)));
+        }
+        _ => (),
+      }
+    }
+    directives
+  }
+
+  fn is_ignored(&self, code: &str, line_index: usize) -> bool {
+    match &self.file_codes {
+      None => true,
+      Some(file_codes) if file_codes.iter().any(|c| c == code) => true,
+      Some(_) => self.line_codes.iter().any(|(line, codes)| {
+        *line == line_index && codes.iter().any(|c| c == code)
+      }),
+    }
+  }
+}
+
+fn op_lint_plugins_respond(
+  state: &mut OpState,
+  response: PluginResponse,
+  _: (),
+) -> Result<(), AnyError> {
+  state.put(response);
+  Ok(())
+}
+
+fn take_response(
+  js_runtime: &mut JsRuntime,
+) -> Result<PluginResponse, AnyError> {
+  let op_state = js_runtime.op_state();
+  let response = op_state
+    .borrow_mut()
+    .try_take::<PluginResponse>()
+    .ok_or_else(|| generic_error("The lint plugins did not respond."))?;
+  match response.error {
+    Some(error) => Err(generic_error(error)),
+    None => Ok(response),
+  }
+}
+
+async fn load(
+  plugins: Vec<ModuleSpecifier>,
+  exclude: Vec<String>,
+) -> Result<(JsRuntime, Vec<String>, PluginModuleHashes), AnyError> {
+  let module_loader = Rc::new(PluginModuleLoader::default());
+  let mut js_runtime = JsRuntime::new(RuntimeOptions {
+    module_loader: Some(module_loader.clone()),
+    ..Default::default()
+  });
+  js_runtime
+    .register_op("op_lint_plugins_respond", op_sync(op_lint_plugins_respond));
+  js_runtime.sync_ops_cache();
+  js_runtime.execute_script(&located_script_name!(), HOST_SOURCE)?;
+
+  let specifiers = plugins.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+  let load_source = format!(
+    "globalThis.__lintPlugins.load({}, {})",
+    json!(specifiers),
+    json!(exclude)
+  );
+  js_runtime.execute_script(&located_script_name!(), &load_source)?;
+  js_runtime.run_event_loop(false).await?;
+  let codes = take_response(&mut js_runtime)?.codes.unwrap_or_default();
+  let mut module_hashes = module_loader.module_hashes.borrow().clone();
+  module_hashes.sort();
+
+  Ok((js_runtime, codes, module_hashes))
+}
+
+fn run(
+  js_runtime: &mut JsRuntime,
+  filename: String,
+  source: String,
+  ast: serde_json::Value,
+) -> Result<Vec<PluginDiagnostic>, AnyError> {
+  let request = json!({
+    "filename": filename,
+    "source": source,
+    "ast": ast,
+  });
+  let run_source = format!("globalThis.__lintPlugins.run({})", request);
+  js_runtime.execute_script(&located_script_name!(), &run_source)?;
+  Ok(take_response(js_runtime)?.diagnostics.unwrap_or_default())
+}
+
+/// Loads local modules for the plugins, transpiling TypeScript and JSX, and
+/// records the checksum of each module it loads.
+#[derive(Default)]
+struct PluginModuleLoader {
+  module_hashes: RefCell<PluginModuleHashes>,
+}
+
+impl ModuleLoader for PluginModuleLoader {
+  fn resolve(
+    &self,
+    specifier: &str,
+    referrer: &str,
+    _is_main: bool,
+  ) -> Result<ModuleSpecifier, AnyError> {
+    Ok(resolve_import(specifier, referrer)?)
+  }
+
+  fn load(
+    &self,
+    module_specifier: &ModuleSpecifier,
+    _maybe_referrer: Option<ModuleSpecifier>,
+    _is_dyn_import: bool,
+  ) -> Pin<Box<ModuleSourceFuture>> {
+    let module_specifier = module_specifier.clone();
+    let result = load_plugin_source(&module_specifier);
+    if let Ok((source, _)) = &result {
+      self
+        .module_hashes
+        .borrow_mut()
+        .push((module_specifier.to_string(), checksum::gen(&[source])));
+    }
+    async move {
+      let (_, code) = result?;
+      Ok(ModuleSource {
+        code,
+        module_url_specified: module_specifier.to_string(),
+        module_url_found: module_specifier.to_string(),
+      })
+    }
+    .boxed_local()
+  }
+}
+
+/// Returns the source of a plugin module along with the code to execute.
+fn load_plugin_source(
+  specifier: &ModuleSpecifier,
+) -> Result<(String, String), AnyError> {
+  if specifier.scheme() != "file" {
+    return Err(generic_error(format!(
+      "Lint plugins can only import local modules, but \"{}\" was imported.",
+      specifier
+    )));
+  }
+  let path = specifier_to_file_path(specifier)?;
+  let source = fs::read_to_string(&path).with_context(|| {
+    format!("Unable to load lint plugin \"{}\".", specifier)
+  })?;
+  let media_type = MediaType::from(specifier);
+  match media_type {
+    MediaType::JavaScript | MediaType::Mjs => Ok((source.clone(), source)),
+    MediaType::TypeScript | MediaType::Tsx | MediaType::Jsx => {
+      let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+        specifier: specifier.to_string(),
+        source: deno_ast::SourceTextInfo::new(Arc::new(source.clone())),
+        media_type,
+        capture_tokens: false,
+        maybe_syntax: None,
+        scope_analysis: false,
+      })?;
+      let (code, _) = transpile(&parsed_source, &EmitOptions::default())?;
+      Ok((source, code))
+    }
+    _ => Err(generic_error(format!(
+      "The lint plugin module \"{}\" has an unsupported media type.",
+      specifier
+    ))),
+  }
+}
@@ -1,10 +1,12 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
+pub mod bench;
 pub mod coverage;
 pub mod doc;
 pub mod fmt;
 pub mod installer;
 pub mod lint;
+pub mod lint_plugins;
 pub mod repl;
 pub mod standalone;
 pub mod test;
@@ -3,13 +3,18 @@
 use crate::ast::Location;
 use crate::cache;
 use crate::cache::CacherLoader;
+use crate::checksum;
 use crate::colors;
 use crate::create_main_worker;
+use crate::diff::diff;
 use crate::emit;
 use crate::file_fetcher::File;
 use crate::file_watcher;
 use crate::file_watcher::ResolutionResult;
 use crate::flags::Flags;
+use crate::flags::TestReporterKind;
+use crate::flags::TestShard;
+use crate::fs_util::atomic_write_file;
 use crate::fs_util::collect_specifiers;
 use crate::fs_util::is_supported_test_ext;
 use crate::fs_util::is_supported_test_path;
@@ -20,16 +25,20 @@ use crate::proc_state::ProcState;
 use crate::resolver::ImportMapResolver;
 use crate::resolver::JsxResolver;
 use crate::tools::coverage::CoverageCollector;
+use crate::xml_util;
 
 use deno_ast::swc::common::comments::CommentKind;
 use deno_ast::MediaType;
+use deno_core::anyhow::Context;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::futures::future;
 use deno_core::futures::stream;
 use deno_core::futures::FutureExt;
 use deno_core::futures::StreamExt;
+use deno_core::serde_json;
 use deno_core::serde_json::json;
+use deno_core::v8;
 use deno_core::JsRuntime;
 use deno_core::ModuleSpecifier;
 use deno_runtime::permissions::Permissions;
@@ -40,20 +49,35 @@ use rand::seq::SliceRandom;
 use rand::SeedableRng;
 use regex::Regex;
 use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::fs;
 use std::io::Write;
+use std::num::NonZeroU64;
 use std::num::NonZeroUsize;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::mpsc::channel;
 use std::sync::mpsc::Sender;
 use std::sync::Arc;
+use std::sync::Condvar;
+use std::sync::Mutex;
 use std::time::Duration;
 use std::time::Instant;
+use tokio::sync::Notify;
+
+lazy_static::lazy_static! {
+  static ref ANSI_ESCAPE_RE: Regex = Regex::new(
+    r"[\x1b\x9b][\[()#;?]*(?:[0-9]{1,4}(?:;[0-9]{0,4})*)?[0-9A-PRZcf-nqry=><]"
+  ).unwrap();
+}
 
 /// The test mode is used to determine how a specifier is to be tested.
 #[derive(Debug, Clone, PartialEq)]
-enum TestMode {
+pub(crate) enum TestMode {
   /// Test as documentation, type-checking fenced code blocks.
   Documentation,
   /// Test as an executable module, loading the module into the isolate and running each test it
@@ -63,29 +87,30 @@ enum TestMode {
   Both,
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 pub struct TestDescription {
   pub origin: String,
   pub name: String,
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum TestOutput {
   // TODO(caspervonb): add stdout and stderr redirection.
   Console(String),
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum TestResult {
   Ok,
   Ignored,
   Failed(String),
+  TimedOut(String),
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TestStepDescription {
   pub test: TestDescription,
@@ -93,13 +118,14 @@ pub struct TestStepDescription {
   pub name: String,
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum TestStepResult {
   Ok,
   Ignored,
   Failed(Option<String>),
   Pending(Option<String>),
+  TimedOut(String),
 }
 
 impl TestStepResult {
@@ -107,12 +133,13 @@ impl TestStepResult {
     match self {
       TestStepResult::Failed(Some(text)) => Some(text.as_str()),
       TestStepResult::Pending(Some(text)) => Some(text.as_str()),
+      TestStepResult::TimedOut(text) => Some(text.as_str()),
       _ => None,
     }
   }
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TestPlan {
   pub origin: String,
@@ -121,7 +148,7 @@ pub struct TestPlan {
   pub used_only: bool,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum TestEvent {
   Plan(TestPlan),
@@ -130,6 +157,7 @@ pub enum TestEvent {
   Result(TestDescription, TestResult, u64),
   StepWait(TestStepDescription),
   StepResult(TestStepDescription, TestStepResult, u64),
+  Snapshots(TestSnapshotSummary),
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -145,6 +173,10 @@ pub struct TestSummary {
   pub filtered_out: usize,
   pub measured: usize,
   pub failures: Vec<(TestDescription, String)>,
+  pub snapshots_written: usize,
+  pub snapshots_updated: usize,
+  pub snapshots_removed: usize,
+  pub obsolete_snapshots: Vec<(String, String)>,
 }
 
 impl TestSummary {
@@ -161,6 +193,10 @@ impl TestSummary {
       filtered_out: 0,
       measured: 0,
       failures: Vec::new(),
+      snapshots_written: 0,
+      snapshots_updated: 0,
+      snapshots_removed: 0,
+      obsolete_snapshots: Vec::new(),
     }
   }
 
@@ -247,6 +283,7 @@ impl PrettyTestReporter {
       TestStepResult::Ignored => colors::yellow("ignored").to_string(),
       TestStepResult::Pending(_) => colors::gray("pending").to_string(),
       TestStepResult::Failed(_) => colors::red("FAILED").to_string(),
+      TestStepResult::TimedOut(_) => colors::red("TIMED OUT").to_string(),
     };
 
     if self.last_wait_output_level == description.level {
@@ -271,7 +308,7 @@ impl PrettyTestReporter {
 
 /// A function that converts a milisecond elapsed time to a string that
 /// represents a human readable version of that time.
-fn human_elapsed(elapsed: u128) -> String {
+pub fn human_elapsed(elapsed: u128) -> String {
   if elapsed < 1_000 {
     return format!("({}ms)", elapsed);
   }
@@ -339,6 +376,7 @@ impl TestReporter for PrettyTestReporter {
       TestResult::Ok => colors::green("ok").to_string(),
       TestResult::Ignored => colors::yellow("ignored").to_string(),
       TestResult::Failed(_) => colors::red("FAILED").to_string(),
+      TestResult::TimedOut(_) => colors::red("TIMED OUT").to_string(),
     };
 
     if self.last_wait_output_level == 0 {
@@ -428,38 +466,959 @@ impl TestReporter for PrettyTestReporter {
       summary.filtered_out,
       colors::gray(human_elapsed(elapsed.as_millis())),
     );
+
+    if summary.snapshots_written
+      + summary.snapshots_updated
+      + summary.snapshots_removed
+      > 0
+    {
+      println!(
+        "snapshots: {} written; {} updated; {} removed\n",
+        summary.snapshots_written,
+        summary.snapshots_updated,
+        summary.snapshots_removed,
+      );
+    }
+
+    if !summary.obsolete_snapshots.is_empty() {
+      println!("{}", colors::yellow("obsolete snapshots:"));
+      for (origin, name) in &summary.obsolete_snapshots {
+        println!("\t{} ({})", name, origin);
+      }
+      println!("\nRun with --update-snapshots to remove them.\n");
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum TestRecordStatus {
+  Ok,
+  Ignored,
+  Failed,
+  Pending,
+  TimedOut,
+}
+
+/// A finished test or test step as kept by `TestRecorder`. Steps are nested
+/// under the test or step that declared them.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TestRecord {
+  name: String,
+  status: TestRecordStatus,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  error: Option<String>,
+  duration: u64,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  output: Vec<String>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  steps: Vec<TestRecord>,
+}
+
+impl TestRecord {
+  /// The message used by reporters that require one for every failure, even
+  /// when the runtime did not provide an error (ie. a step that failed because
+  /// one of its own steps failed).
+  fn failure_message(&self) -> Option<String> {
+    match self.status {
+      TestRecordStatus::Ok | TestRecordStatus::Ignored => None,
+      TestRecordStatus::Failed => Some(
+        self
+          .error
+          .clone()
+          .unwrap_or_else(|| "Test step failed.".to_string()),
+      ),
+      TestRecordStatus::Pending => {
+        Some(self.error.clone().unwrap_or_else(|| {
+          "Test step did not complete before its parent finished.".to_string()
+        }))
+      }
+      TestRecordStatus::TimedOut => Some(
+        self
+          .error
+          .clone()
+          .unwrap_or_else(|| "Test timed out.".to_string()),
+      ),
+    }
   }
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TestModuleRecord {
+  origin: String,
+  filtered_out: usize,
+  tests: Vec<TestRecord>,
+}
+
+fn strip_ansi_codes(text: &str) -> String {
+  ANSI_ESCAPE_RE.replace_all(text, "").to_string()
+}
+
+/// Accumulates the event stream into a tree of modules, tests and steps for
+/// reporters that can only render once the whole run is known.
+#[derive(Default)]
+struct TestRecorder {
+  modules: Vec<TestModuleRecord>,
+  /// Finished steps of running tests, indexed by step level. Step results are
+  /// reported after the results of their own steps, so the steps one level
+  /// down are always complete by the time their parent is recorded.
+  pending_steps: HashMap<TestDescription, Vec<Vec<TestRecord>>>,
+  pending_output: HashMap<TestDescription, Vec<String>>,
+  last_wait: Option<TestDescription>,
+}
+
+impl TestRecorder {
+  fn record_plan(&mut self, plan: &TestPlan) {
+    self.modules.push(TestModuleRecord {
+      origin: plan.origin.clone(),
+      filtered_out: plan.filtered_out,
+      tests: Vec::new(),
+    });
+  }
+
+  fn record_wait(&mut self, description: &TestDescription) {
+    self.last_wait = Some(description.clone());
+  }
+
+  fn record_output(&mut self, output: &TestOutput) {
+    // Console output does not identify the test that produced it, so it is
+    // attributed to the test that started last. This is exact when modules
+    // run one at a time, but output of concurrent jobs may be misattributed.
+    if let Some(description) = &self.last_wait {
+      match output {
+        TestOutput::Console(line) => self
+          .pending_output
+          .entry(description.clone())
+          .or_insert_with(Vec::new)
+          .push(strip_ansi_codes(line)),
+      }
+    }
+  }
+
+  fn record_step_result(
+    &mut self,
+    description: &TestStepDescription,
+    result: &TestStepResult,
+    elapsed: u64,
+  ) {
+    let levels = self
+      .pending_steps
+      .entry(description.test.clone())
+      .or_insert_with(Vec::new);
+    if levels.len() < description.level + 2 {
+      levels.resize_with(description.level + 2, Vec::new);
+    }
+    let steps = std::mem::take(&mut levels[description.level + 1]);
+    let (status, error) = match result {
+      TestStepResult::Ok => (TestRecordStatus::Ok, None),
+      TestStepResult::Ignored => (TestRecordStatus::Ignored, None),
+      TestStepResult::Failed(error) => {
+        (TestRecordStatus::Failed, error.clone())
+      }
+      TestStepResult::Pending(error) => {
+        (TestRecordStatus::Pending, error.clone())
+      }
+      TestStepResult::TimedOut(error) => {
+        (TestRecordStatus::TimedOut, Some(error.clone()))
+      }
+    };
+
+    levels[description.level].push(TestRecord {
+      name: description.name.clone(),
+      status,
+      error: error.map(|error| strip_ansi_codes(&error)),
+      duration: elapsed,
+      output: Vec::new(),
+      steps,
+    });
+  }
+
+  fn record_result(
+    &mut self,
+    description: &TestDescription,
+    result: &TestResult,
+    elapsed: u64,
+  ) {
+    if self.last_wait.as_ref() == Some(description) {
+      self.last_wait = None;
+    }
+
+    let steps = self
+      .pending_steps
+      .remove(description)
+      .and_then(|mut levels| levels.get_mut(1).map(std::mem::take))
+      .unwrap_or_default();
+    let output = self.pending_output.remove(description).unwrap_or_default();
+    let (status, error) = match result {
+      TestResult::Ok => (TestRecordStatus::Ok, None),
+      TestResult::Ignored => (TestRecordStatus::Ignored, None),
+      TestResult::Failed(error) => {
+        (TestRecordStatus::Failed, Some(strip_ansi_codes(error)))
+      }
+      TestResult::TimedOut(error) => {
+        (TestRecordStatus::TimedOut, Some(strip_ansi_codes(error)))
+      }
+    };
+    let record = TestRecord {
+      name: description.name.clone(),
+      status,
+      error,
+      duration: elapsed,
+      output,
+      steps,
+    };
+
+    match self
+      .modules
+      .iter()
+      .rposition(|module| module.origin == description.origin)
+    {
+      Some(index) => self.modules[index].tests.push(record),
+      None => self.modules.push(TestModuleRecord {
+        origin: description.origin.clone(),
+        filtered_out: 0,
+        tests: vec![record],
+      }),
+    }
+  }
+}
+
+type RenderTestRecords =
+  fn(&[TestModuleRecord], &TestSummary, &Duration) -> String;
+
+/// A reporter that records the whole run and writes a single rendered report
+/// once the summary is known.
+struct RecordingTestReporter {
+  recorder: TestRecorder,
+  render: RenderTestRecords,
+  writer: Box<dyn Write + Send>,
+}
+
+impl RecordingTestReporter {
+  fn new(
+    render: RenderTestRecords,
+    writer: Box<dyn Write + Send>,
+  ) -> RecordingTestReporter {
+    RecordingTestReporter {
+      recorder: TestRecorder::default(),
+      render,
+      writer,
+    }
+  }
+}
+
+impl TestReporter for RecordingTestReporter {
+  fn report_plan(&mut self, plan: &TestPlan) {
+    self.recorder.record_plan(plan);
+  }
+
+  fn report_wait(&mut self, description: &TestDescription) {
+    self.recorder.record_wait(description);
+  }
+
+  fn report_output(&mut self, output: &TestOutput) {
+    self.recorder.record_output(output);
+  }
+
+  fn report_result(
+    &mut self,
+    description: &TestDescription,
+    result: &TestResult,
+    elapsed: u64,
+  ) {
+    self.recorder.record_result(description, result, elapsed);
+  }
+
+  fn report_step_wait(&mut self, _description: &TestStepDescription) {}
+
+  fn report_step_result(
+    &mut self,
+    description: &TestStepDescription,
+    result: &TestStepResult,
+    elapsed: u64,
+  ) {
+    self
+      .recorder
+      .record_step_result(description, result, elapsed);
+  }
+
+  fn report_summary(&mut self, summary: &TestSummary, elapsed: &Duration) {
+    let report = (self.render)(&self.recorder.modules, summary, elapsed);
+    if let Err(err) = self
+      .writer
+      .write_all(report.as_bytes())
+      .and_then(|_| self.writer.flush())
+    {
+      eprintln!("Failed to write test report: {}", err);
+    }
+  }
+}
+
+/// Forwards every event to each of the wrapped reporters in order.
+struct CompoundTestReporter {
+  reporters: Vec<Box<dyn TestReporter + Send>>,
+}
+
+impl TestReporter for CompoundTestReporter {
+  fn report_plan(&mut self, plan: &TestPlan) {
+    for reporter in &mut self.reporters {
+      reporter.report_plan(plan);
+    }
+  }
+
+  fn report_wait(&mut self, description: &TestDescription) {
+    for reporter in &mut self.reporters {
+      reporter.report_wait(description);
+    }
+  }
+
+  fn report_output(&mut self, output: &TestOutput) {
+    for reporter in &mut self.reporters {
+      reporter.report_output(output);
+    }
+  }
+
+  fn report_result(
+    &mut self,
+    description: &TestDescription,
+    result: &TestResult,
+    elapsed: u64,
+  ) {
+    for reporter in &mut self.reporters {
+      reporter.report_result(description, result, elapsed);
+    }
+  }
+
+  fn report_step_wait(&mut self, description: &TestStepDescription) {
+    for reporter in &mut self.reporters {
+      reporter.report_step_wait(description);
+    }
+  }
+
+  fn report_step_result(
+    &mut self,
+    description: &TestStepDescription,
+    result: &TestStepResult,
+    elapsed: u64,
+  ) {
+    for reporter in &mut self.reporters {
+      reporter.report_step_result(description, result, elapsed);
+    }
+  }
+
+  fn report_summary(&mut self, summary: &TestSummary, elapsed: &Duration) {
+    for reporter in &mut self.reporters {
+      reporter.report_summary(summary, elapsed);
+    }
+  }
+}
+
+fn format_seconds(milliseconds: u128) -> String {
+  format!("{:.3}", milliseconds as f64 / 1000.0)
+}
+
+/// Flattens a test and its steps into `(name, record)` pairs where step names
+/// are prefixed with the names of their ancestors, ie. `test > step > step`.
+fn flatten_test_records<'a>(
+  prefix: Option<&str>,
+  record: &'a TestRecord,
+  output: &mut Vec<(String, &'a TestRecord)>,
+) {
+  let name = match prefix {
+    Some(prefix) => format!("{} > {}", prefix, record.name),
+    None => record.name.clone(),
+  };
+  output.push((name.clone(), record));
+  for step in &record.steps {
+    flatten_test_records(Some(&name), step, output);
+  }
+}
+
+fn render_junit(
+  modules: &[TestModuleRecord],
+  _summary: &TestSummary,
+  elapsed: &Duration,
+) -> String {
+  let mut suites = Vec::new();
+  let mut total_tests = 0;
+  let mut total_failures = 0;
+  let mut total_skipped = 0;
+
+  for module in modules {
+    let mut cases = Vec::new();
+    for test in &module.tests {
+      flatten_test_records(None, test, &mut cases);
+    }
+
+    let failures = cases
+      .iter()
+      .filter(|(_, record)| record.failure_message().is_some())
+      .count();
+    let skipped = cases
+      .iter()
+      .filter(|(_, record)| record.status == TestRecordStatus::Ignored)
+      .count();
+    let duration: u64 = module.tests.iter().map(|test| test.duration).sum();
+    total_tests += cases.len();
+    total_failures += failures;
+    total_skipped += skipped;
+
+    let mut suite = format!(
+      "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\">\n",
+      xml_util::escape(&module.origin),
+      cases.len(),
+      failures,
+      skipped,
+      format_seconds(duration.into()),
+    );
+    for (name, record) in cases {
+      suite.push_str(&format!(
+        "    <testcase name=\"{}\" classname=\"{}\" time=\"{}\"",
+        xml_util::escape(&name),
+        xml_util::escape(&module.origin),
+        format_seconds(record.duration.into()),
+      ));
+
+      let failure_message = record.failure_message();
+      if failure_message.is_none()
+        && record.status != TestRecordStatus::Ignored
+        && record.output.is_empty()
+      {
+        suite.push_str("/>\n");
+        continue;
+      }
+
+      suite.push_str(">\n");
+      if let Some(message) = failure_message {
+        suite.push_str(&format!(
+          "      <failure message=\"{}\">{}</failure>\n",
+          xml_util::escape(message.lines().next().unwrap_or_default()),
+          xml_util::escape(&message),
+        ));
+      }
+      if record.status == TestRecordStatus::Ignored {
+        suite.push_str("      <skipped/>\n");
+      }
+      if !record.output.is_empty() {
+        suite.push_str(&format!(
+          "      <system-out>{}</system-out>\n",
+          xml_util::escape(&record.output.join("\n")),
+        ));
+      }
+      suite.push_str("    </testcase>\n");
+    }
+    suite.push_str("  </testsuite>\n");
+    suites.push(suite);
+  }
+
+  format!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"deno test\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\">\n{}</testsuites>\n",
+    total_tests,
+    total_failures,
+    total_skipped,
+    format_seconds(elapsed.as_millis()),
+    suites.join(""),
+  )
+}
+
+/// Escapes the characters that have a meaning in the description of a TAP
+/// test point.
+fn escape_tap_description(text: &str) -> String {
+  text
+    .replace('\\', "\\\\")
+    .replace('#', "\\#")
+    .replace('\n', " ")
+}
+
+fn push_tap_yaml_block(lines: &mut Vec<String>, indent: &str, text: &str) {
+  for line in text.lines() {
+    lines.push(format!("{}    {}", indent, line));
+  }
+}
+
+fn push_tap_test_point(
+  lines: &mut Vec<String>,
+  record: &TestRecord,
+  index: usize,
+  depth: usize,
+) {
+  let indent = "    ".repeat(depth);
+
+  if !record.steps.is_empty() {
+    lines.push(format!(
+      "{}# Subtest: {}",
+      indent,
+      escape_tap_description(&record.name)
+    ));
+    for (step_index, step) in record.steps.iter().enumerate() {
+      push_tap_test_point(lines, step, step_index + 1, depth + 1);
+    }
+    lines.push(format!("{}    1..{}", indent, record.steps.len()));
+  }
+
+  let (status, directive) = match record.status {
+    TestRecordStatus::Ok => ("ok", ""),
+    TestRecordStatus::Ignored => ("ok", " # SKIP"),
+    TestRecordStatus::Failed
+    | TestRecordStatus::Pending
+    | TestRecordStatus::TimedOut => ("not ok", ""),
+  };
+  lines.push(format!(
+    "{}{} {} - {}{}",
+    indent,
+    status,
+    index,
+    escape_tap_description(&record.name),
+    directive,
+  ));
+
+  lines.push(format!("{}  ---", indent));
+  lines.push(format!("{}  duration_ms: {}", indent, record.duration));
+  if let Some(message) = record.failure_message() {
+    lines.push(format!("{}  error: |-", indent));
+    push_tap_yaml_block(lines, &indent, &message);
+  }
+  if !record.output.is_empty() {
+    lines.push(format!("{}  output: |-", indent));
+    push_tap_yaml_block(lines, &indent, &record.output.join("\n"));
+  }
+  lines.push(format!("{}  ...", indent));
+}
+
+fn render_tap(
+  modules: &[TestModuleRecord],
+  _summary: &TestSummary,
+  _elapsed: &Duration,
+) -> String {
+  let total: usize = modules.iter().map(|module| module.tests.len()).sum();
+  let mut lines = vec!["TAP version 14".to_string(), format!("1..{}", total)];
+
+  let mut index = 0;
+  for module in modules {
+    lines.push(format!("# {}", module.origin));
+    for test in &module.tests {
+      index += 1;
+      push_tap_test_point(&mut lines, test, index, 0);
+    }
+  }
+
+  lines.push(String::new());
+  lines.join("\n")
+}
+
+fn render_json(
+  modules: &[TestModuleRecord],
+  summary: &TestSummary,
+  elapsed: &Duration,
+) -> String {
+  let report = json!({
+    "modules": modules,
+    "summary": {
+      "total": summary.total,
+      "passed": summary.passed,
+      "failed": summary.failed,
+      "ignored": summary.ignored,
+      "passedSteps": summary.passed_steps,
+      "failedSteps": summary.failed_steps,
+      "pendingSteps": summary.pending_steps,
+      "ignoredSteps": summary.ignored_steps,
+      "filteredOut": summary.filtered_out,
+      "measured": summary.measured,
+      "duration": elapsed.as_millis() as u64,
+    },
+  });
+
+  format!("{}\n", serde_json::to_string_pretty(&report).unwrap())
+}
+
+/// Creates the reporter for the run. Reports that are written to a file are
+/// accompanied by the pretty reporter, so the console output stays the same.
 fn create_reporter(
+  kind: TestReporterKind,
+  maybe_output: Option<PathBuf>,
   concurrent: bool,
   echo_output: bool,
-) -> Box<dyn TestReporter + Send> {
-  Box::new(PrettyTestReporter::new(concurrent, echo_output))
+) -> Result<Box<dyn TestReporter + Send>, AnyError> {
+  let render: RenderTestRecords = match kind {
+    TestReporterKind::Pretty => {
+      if maybe_output.is_some() {
+        return Err(generic_error(
+          "--reporter-output requires a reporter other than 'pretty'",
+        ));
+      }
+
+      return Ok(Box::new(PrettyTestReporter::new(concurrent, echo_output)));
+    }
+    TestReporterKind::Junit => render_junit,
+    TestReporterKind::Tap => render_tap,
+    TestReporterKind::Json => render_json,
+  };
+
+  if let Some(output) = maybe_output {
+    if let Some(parent) = output.parent() {
+      fs::create_dir_all(parent)?;
+    }
+    let file = fs::File::create(&output).with_context(|| {
+      format!("Failed to create test report: {}", output.display())
+    })?;
+
+    Ok(Box::new(CompoundTestReporter {
+      reporters: vec![
+        Box::new(PrettyTestReporter::new(concurrent, echo_output)),
+        Box::new(RecordingTestReporter::new(render, Box::new(file))),
+      ],
+    }))
+  } else {
+    Ok(Box::new(RecordingTestReporter::new(
+      render,
+      Box::new(std::io::stdout()),
+    )))
+  }
+}
+
+#[derive(Default)]
+struct TestTimeoutsState {
+  next_id: u32,
+  deadlines: HashMap<u32, Instant>,
+  expired: Option<u32>,
+  shutdown: bool,
+}
+
+/// The deadlines of the tests and steps running in a worker, registered by the
+/// runtime when a test or step with a timeout starts.
+#[derive(Clone, Default)]
+pub struct TestTimeouts {
+  state: Arc<(Mutex<TestTimeoutsState>, Condvar)>,
+  expired_notify: Arc<Notify>,
+}
+
+impl TestTimeouts {
+  pub fn start(&self, timeout: Duration) -> u32 {
+    let (lock, condvar) = &*self.state;
+    let mut state = lock.lock().unwrap();
+    let id = state.next_id;
+    state.next_id += 1;
+    state.deadlines.insert(id, Instant::now() + timeout);
+    condvar.notify_all();
+    id
+  }
+
+  pub fn clear(&self, id: u32) {
+    let (lock, _) = &*self.state;
+    lock.lock().unwrap().deadlines.remove(&id);
+  }
+
+  fn expired(&self) -> Option<u32> {
+    let (lock, _) = &*self.state;
+    lock.lock().unwrap().expired
+  }
+
+  /// Resolves once a deadline has passed.
+  async fn wait_expired(&self) {
+    self.expired_notify.notified().await
+  }
+
+  /// Spawns a thread that terminates the isolate when the earliest deadline
+  /// passes. Terminating execution stops tests that are stuck in synchronous
+  /// code, while `wait_expired` covers tests waiting on ops that never
+  /// resolve.
+  fn spawn_watchdog(&self, isolate_handle: v8::IsolateHandle) -> TestWatchdog {
+    let timeouts = self.clone();
+    let join_handle = std::thread::spawn(move || {
+      let (lock, condvar) = &*timeouts.state;
+      let mut state = lock.lock().unwrap();
+      loop {
+        if state.shutdown {
+          return;
+        }
+
+        let now = Instant::now();
+        let earliest = state
+          .deadlines
+          .iter()
+          .min_by_key(|(_, deadline)| **deadline)
+          .map(|(id, deadline)| (*id, *deadline));
+        match earliest {
+          Some((id, deadline)) if deadline <= now => {
+            state.expired = Some(id);
+            isolate_handle.terminate_execution();
+            timeouts.expired_notify.notify_one();
+            return;
+          }
+          Some((_, deadline)) => {
+            state = condvar.wait_timeout(state, deadline - now).unwrap().0;
+          }
+          None => {
+            state = condvar.wait(state).unwrap();
+          }
+        }
+      }
+    });
+
+    TestWatchdog {
+      timeouts: self.clone(),
+      join_handle: Some(join_handle),
+    }
+  }
+}
+
+/// Stops the watchdog thread when the worker is done.
+struct TestWatchdog {
+  timeouts: TestTimeouts,
+  join_handle: Option<std::thread::JoinHandle<()>>,
+}
+
+impl Drop for TestWatchdog {
+  fn drop(&mut self) {
+    let (lock, condvar) = &*self.timeouts.state;
+    lock.lock().unwrap().shutdown = true;
+    condvar.notify_all();
+    if let Some(join_handle) = self.join_handle.take() {
+      join_handle.join().ok();
+    }
+  }
+}
+
+/// The tests registered by a test module and the ones that passed, sent by the
+/// runtime once all of the module's tests have run.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CompletedTests {
+  pub registered: Vec<String>,
+  pub passed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestSnapshotSummary {
+  pub origin: String,
+  pub written: usize,
+  pub updated: usize,
+  pub removed: usize,
+  pub obsolete: Vec<String>,
+}
+
+/// The snapshots of a test module, stored as JSON in a `__snapshots__`
+/// directory next to the module. Every test or step maps its full name to the
+/// values it asserted, in the order they were asserted.
+pub struct TestSnapshots {
+  origin: String,
+  path: Option<PathBuf>,
+  update: bool,
+  stored: BTreeMap<String, Vec<String>>,
+  asserted: BTreeMap<String, Vec<String>>,
+  completed: Option<CompletedTests>,
+}
+
+fn snapshot_path(specifier: &ModuleSpecifier) -> Option<PathBuf> {
+  if specifier.scheme() != "file" {
+    return None;
+  }
+
+  let path = specifier.to_file_path().ok()?;
+  let file_name = path.file_name()?.to_string_lossy().to_string();
+  Some(
+    path
+      .with_file_name("__snapshots__")
+      .join(format!("{}.snap", file_name)),
+  )
+}
+
+impl TestSnapshots {
+  fn load(specifier: &ModuleSpecifier, update: bool) -> Result<Self, AnyError> {
+    let path = snapshot_path(specifier);
+    let stored = match &path {
+      Some(path) if path.exists() => {
+        let text = fs::read_to_string(path)?;
+        serde_json::from_str(&text).with_context(|| {
+          format!("Failed to parse snapshot file: {}", path.display())
+        })?
+      }
+      _ => BTreeMap::new(),
+    };
+
+    Ok(TestSnapshots {
+      origin: specifier.to_string(),
+      path,
+      update,
+      stored,
+      asserted: BTreeMap::new(),
+      completed: None,
+    })
+  }
+
+  /// Compares a value asserted by the named test against its snapshot and
+  /// returns a message describing the mismatch, if any. When updating, the
+  /// asserted value always matches as it replaces the snapshot.
+  pub fn assert(
+    &mut self,
+    name: String,
+    actual: String,
+  ) -> Result<Option<String>, AnyError> {
+    if self.path.is_none() {
+      return Err(generic_error(
+        "Snapshots are only supported in local test modules",
+      ));
+    }
+
+    let values = self.asserted.entry(name.clone()).or_default();
+    values.push(actual);
+    let index = values.len() - 1;
+    let actual = &values[index];
+    let snapshot_name = format!("{} {}", name, index + 1);
+
+    match self.stored.get(&name).and_then(|values| values.get(index)) {
+      Some(expected) if expected == actual => Ok(None),
+      _ if self.update => Ok(None),
+      Some(expected) => Ok(Some(format!(
+        "Snapshot \"{}\" does not match:\n\n{}\nRun with --update-snapshots to update it.",
+        snapshot_name,
+        diff(expected, actual),
+      ))),
+      None => Ok(Some(format!(
+        "Snapshot \"{}\" does not exist. Run with --update-snapshots to write it.",
+        snapshot_name,
+      ))),
+    }
+  }
+
+  pub fn complete(&mut self, completed: CompletedTests) {
+    self.completed = Some(completed);
+  }
+
+  /// Whether all the snapshots the named test or step still uses have been
+  /// asserted, which is the case when its root test passed or no longer
+  /// exists. Snapshots of tests that were filtered out, ignored or failed are
+  /// never considered obsolete.
+  fn is_checked(&self, name: &str) -> bool {
+    let completed = match &self.completed {
+      Some(completed) => completed,
+      None => return false,
+    };
+
+    let root = completed
+      .registered
+      .iter()
+      .filter(|test| {
+        name == test.as_str() || name.starts_with(&format!("{} > ", test))
+      })
+      .max_by_key(|test| test.len());
+
+    match root {
+      Some(root) => completed.passed.contains(root),
+      None => true,
+    }
+  }
+
+  /// Reports the snapshots that are no longer asserted by any test. When
+  /// updating, the asserted values are written instead and the obsolete
+  /// snapshots removed.
+  fn finish(self) -> Result<TestSnapshotSummary, AnyError> {
+    let mut snapshots = BTreeMap::new();
+    let mut written = 0;
+    let mut updated = 0;
+    let mut obsolete = Vec::new();
+
+    let names: BTreeSet<&String> =
+      self.stored.keys().chain(self.asserted.keys()).collect();
+    for name in names {
+      let stored = self.stored.get(name).map(Vec::as_slice).unwrap_or(&[]);
+      let asserted = self.asserted.get(name).map(Vec::as_slice).unwrap_or(&[]);
+      let checked = self.is_checked(name);
+
+      let mut values = Vec::new();
+      for index in 0..stored.len().max(asserted.len()) {
+        match (stored.get(index), asserted.get(index)) {
+          (expected, Some(actual)) => {
+            match expected {
+              None => written += 1,
+              Some(expected) if expected != actual => updated += 1,
+              Some(_) => {}
+            }
+            values.push(actual.clone());
+          }
+          (Some(_), None) if checked => {
+            obsolete.push(format!("{} {}", name, index + 1));
+          }
+          (Some(expected), None) => values.push(expected.clone()),
+          (None, None) => unreachable!(),
+        }
+      }
+
+      if !values.is_empty() {
+        snapshots.insert(name.clone(), values);
+      }
+    }
+
+    if !self.update {
+      return Ok(TestSnapshotSummary {
+        origin: self.origin,
+        obsolete,
+        ..Default::default()
+      });
+    }
+
+    let removed = obsolete.len();
+    if written + updated + removed > 0 {
+      if let Some(path) = &self.path {
+        if snapshots.is_empty() {
+          fs::remove_file(path)?;
+        } else {
+          fs::create_dir_all(path.parent().unwrap())?;
+          fs::write(
+            path,
+            format!("{}\n", serde_json::to_string_pretty(&snapshots)?),
+          )?;
+        }
+      }
+    }
+
+    Ok(TestSnapshotSummary {
+      origin: self.origin,
+      written,
+      updated,
+      removed,
+      obsolete: Vec::new(),
+    })
+  }
 }
 
 /// Test a single specifier as documentation containing test programs, an executable test module or
 /// both.
-async fn test_specifier(
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn test_specifier(
   ps: ProcState,
   permissions: Permissions,
   specifier: ModuleSpecifier,
   mode: TestMode,
   filter: Option<String>,
+  names: Option<Vec<String>>,
   shuffle: Option<u64>,
+  timeout: Option<NonZeroU64>,
+  update_snapshots: bool,
   channel: Sender<TestEvent>,
 ) -> Result<(), AnyError> {
+  let timeouts = TestTimeouts::default();
   let init_ops = |js_runtime: &mut JsRuntime| {
     ops::testing::init(js_runtime);
 
-    js_runtime
-      .op_state()
-      .borrow_mut()
-      .put::<Sender<TestEvent>>(channel.clone());
+    let op_state = js_runtime.op_state();
+    let mut op_state = op_state.borrow_mut();
+    op_state.put::<Sender<TestEvent>>(channel.clone());
+    op_state.put::<TestTimeouts>(timeouts.clone());
   };
 
+  let snapshots = TestSnapshots::load(&specifier, update_snapshots)?;
   let mut worker =
     create_main_worker(&ps, specifier.clone(), permissions, Some(&init_ops));
+  worker
+    .js_runtime
+    .op_state()
+    .borrow_mut()
+    .put::<TestSnapshots>(snapshots);
+  let _watchdog = timeouts
+    .spawn_watchdog(worker.js_runtime.v8_isolate().thread_safe_handle());
 
   let mut maybe_coverage_collector = if let Some(ref coverage_dir) =
     ps.coverage_dir
@@ -494,17 +1453,43 @@ async fn test_specifier(
       r#"Deno[Deno.internal].runTests({})"#,
       json!({
         "filter": filter,
+        "names": names,
         "shuffle": shuffle,
+        "timeout": timeout,
       }),
     ),
   )?;
 
-  worker.js_runtime.resolve_value(test_result).await?;
+  let maybe_result = tokio::select! {
+    result = worker.js_runtime.resolve_value(test_result) => Some(result),
+    _ = timeouts.wait_expired() => None,
+  };
 
-  worker.js_runtime.execute_script(
-    &located_script_name!(),
-    "window.dispatchEvent(new Event('unload'));",
-  )?;
+  if let Some(id) = timeouts.expired() {
+    // The module is abandoned once a test times out; the runtime reports the
+    // timed out test along with the ops and resources it left pending.
+    worker.js_runtime.v8_isolate().cancel_terminate_execution();
+    worker.js_runtime.execute_script(
+      &located_script_name!(),
+      &format!("Deno[Deno.internal].reportTestTimeout({})", id),
+    )?;
+  } else {
+    if let Some(result) = maybe_result {
+      result?;
+    }
+
+    worker.js_runtime.execute_script(
+      &located_script_name!(),
+      "window.dispatchEvent(new Event('unload'));",
+    )?;
+  }
+
+  let snapshots = worker
+    .js_runtime
+    .op_state()
+    .borrow_mut()
+    .take::<TestSnapshots>();
+  channel.send(TestEvent::Snapshots(snapshots.finish()?)).ok();
 
   if let Some(coverage_collector) = maybe_coverage_collector.as_mut() {
     worker
@@ -695,7 +1680,7 @@ async fn fetch_inline_files(
 }
 
 /// Type check a collection of module and document specifiers.
-async fn check_specifiers(
+pub(crate) async fn check_specifiers(
   ps: ProcState,
   permissions: Permissions,
   specifiers: Vec<(ModuleSpecifier, TestMode)>,
@@ -761,17 +1746,194 @@ async fn check_specifiers(
   Ok(())
 }
 
+/// Wall clock durations of previous test runs in milliseconds, keyed by module
+/// specifier.
+type TestDurations = BTreeMap<String, u64>;
+
+fn test_durations_path(ps: &ProcState) -> PathBuf {
+  ps.dir.root.join("test_durations.json")
+}
+
+fn load_test_durations(ps: &ProcState) -> TestDurations {
+  fs::read_to_string(test_durations_path(ps))
+    .ok()
+    .and_then(|source| serde_json::from_str(&source).ok())
+    .unwrap_or_default()
+}
+
+fn save_test_durations(
+  ps: &ProcState,
+  durations: TestDurations,
+) -> Result<(), AnyError> {
+  let mut stored = load_test_durations(ps);
+  stored.extend(durations);
+  let path = test_durations_path(ps);
+  atomic_write_file(&path, serde_json::to_string_pretty(&stored)?, 0o644)
+    .with_context(|| format!("Failed writing {}", path.display()))?;
+  Ok(())
+}
+
+/// The tests that failed the last time they ran, keyed by the module
+/// specifier.
+type TestFailures = BTreeMap<String, BTreeSet<String>>;
+
+const NO_RECORDED_FAILURES: &str =
+  "No test failures were recorded, so there are no tests to rerun.";
+
+/// Failures are recorded per project, which is the directory of the
+/// configuration file or else the current working directory.
+fn test_failures_path(ps: &ProcState) -> PathBuf {
+  let project_root = ps
+    .maybe_config_file
+    .as_ref()
+    .and_then(|config_file| config_file.specifier.to_file_path().ok())
+    .and_then(|path| path.parent().map(Path::to_path_buf))
+    .or_else(|| std::env::current_dir().ok())
+    .unwrap_or_default();
+  let key = checksum::gen(&[project_root.to_string_lossy().as_bytes()]);
+  ps.dir
+    .root
+    .join("test_failures")
+    .join(format!("{}.json", key))
+}
+
+fn load_test_failures(path: &Path) -> TestFailures {
+  fs::read_to_string(path)
+    .ok()
+    .and_then(|source| serde_json::from_str(&source).ok())
+    .unwrap_or_default()
+}
+
+/// Records the failures of this run. The recorded failures of a module are
+/// replaced as a whole when all of its tests ran. Otherwise only the ones of
+/// the tests that ran are replaced, so the failures of the tests that were
+/// filtered out are kept for `--rerun-failed`.
+fn save_test_failures(
+  path: &Path,
+  ran: &BTreeMap<String, BTreeSet<String>>,
+  complete: &BTreeSet<String>,
+  failures: TestFailures,
+) -> Result<(), AnyError> {
+  let mut stored = load_test_failures(path);
+  stored.retain(|origin, names| {
+    if complete.contains(origin) {
+      return false;
+    }
+    if let Some(ran) = ran.get(origin) {
+      names.retain(|name| !ran.contains(name));
+    }
+    !names.is_empty()
+  });
+  for (origin, names) in failures {
+    stored.entry(origin).or_default().extend(names);
+  }
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent)?;
+  }
+  atomic_write_file(path, serde_json::to_string_pretty(&stored)?, 0o644)
+    .with_context(|| format!("Failed writing {}", path.display()))?;
+  Ok(())
+}
+
+/// Narrows the test modules down to the ones with recorded failures, which are
+/// then run as executable modules only. Returns `None` when none of the
+/// modules have recorded failures, in which case there is nothing to rerun.
+fn select_failed(
+  specifiers_with_mode: &[(ModuleSpecifier, TestMode)],
+  failures: &TestFailures,
+) -> Option<Vec<(ModuleSpecifier, TestMode)>> {
+  let selected = specifiers_with_mode
+    .iter()
+    .filter(|(specifier, mode)| {
+      *mode != TestMode::Documentation
+        && failures.contains_key(specifier.as_str())
+    })
+    .map(|(specifier, _)| (specifier.clone(), TestMode::Executable))
+    .collect::<Vec<_>>();
+
+  if selected.is_empty() {
+    None
+  } else {
+    Some(selected)
+  }
+}
+
+/// Deterministically partitions the test modules into `shard.total` shards and
+/// returns the modules of the selected one, keeping their original order.
+///
+/// Modules are assigned greedily, slowest first, to the shard with the least
+/// accumulated duration. Modules without a recorded duration are weighted with
+/// the average of the known ones, so without any durations, which is the case
+/// unless balancing by duration was opted into, the modules are simply dealt
+/// out by specifier.
+fn select_shard(
+  specifiers_with_mode: Vec<(ModuleSpecifier, TestMode)>,
+  shard: TestShard,
+  durations: &TestDurations,
+) -> Vec<(ModuleSpecifier, TestMode)> {
+  let known = specifiers_with_mode
+    .iter()
+    .filter_map(|(specifier, _)| durations.get(specifier.as_str()))
+    .collect::<Vec<_>>();
+  let fallback = if known.is_empty() {
+    1
+  } else {
+    known.iter().copied().sum::<u64>() / known.len() as u64
+  };
+
+  let mut weighted = specifiers_with_mode
+    .iter()
+    .enumerate()
+    .map(|(index, (specifier, _))| {
+      let weight = durations
+        .get(specifier.as_str())
+        .copied()
+        .unwrap_or(fallback)
+        .max(1);
+      (weight, specifier, index)
+    })
+    .collect::<Vec<_>>();
+  weighted.sort_by(|(a_weight, a_specifier, _), (b_weight, b_specifier, _)| {
+    b_weight
+      .cmp(a_weight)
+      .then_with(|| a_specifier.cmp(b_specifier))
+  });
+
+  let mut loads = vec![0u64; shard.total];
+  let mut selected = vec![false; specifiers_with_mode.len()];
+  for (weight, _, index) in weighted {
+    let target = (0..shard.total)
+      .min_by_key(|target| (loads[*target], *target))
+      .unwrap();
+    loads[target] += weight;
+    selected[index] = target == shard.index - 1;
+  }
+
+  specifiers_with_mode
+    .into_iter()
+    .zip(selected)
+    .filter_map(|(entry, selected)| selected.then(|| entry))
+    .collect()
+}
+
 /// Test a collection of specifiers with test modes concurrently.
+#[allow(clippy::too_many_arguments)]
 async fn test_specifiers(
   ps: ProcState,
   permissions: Permissions,
   specifiers_with_mode: Vec<(ModuleSpecifier, TestMode)>,
   fail_fast: Option<NonZeroUsize>,
   filter: Option<String>,
+  failed_only: Option<TestFailures>,
   shuffle: Option<u64>,
+  timeout: Option<NonZeroU64>,
+  update_snapshots: bool,
   concurrent_jobs: NonZeroUsize,
+  reporter_kind: TestReporterKind,
+  reporter_output: Option<PathBuf>,
 ) -> Result<(), AnyError> {
   let log_level = ps.flags.log_level;
+  let failures_path = test_failures_path(&ps);
   let specifiers_with_mode = if let Some(seed) = shuffle {
     let mut rng = SmallRng::seed_from_u64(seed);
     let mut specifiers_with_mode = specifiers_with_mode.clone();
@@ -784,50 +1946,86 @@ async fn test_specifiers(
 
   let (sender, receiver) = channel::<TestEvent>();
 
-  let join_handles =
-    specifiers_with_mode.iter().map(move |(specifier, mode)| {
+  // Durations are only meaningful for shard balancing when whole modules ran.
+  let record_durations = filter.is_none() && failed_only.is_none();
+  let durations = Arc::new(Mutex::new(TestDurations::new()));
+
+  let join_handles = specifiers_with_mode.iter().map({
+    let ps = ps.clone();
+    let durations = durations.clone();
+    move |(specifier, mode)| {
       let ps = ps.clone();
       let permissions = permissions.clone();
       let specifier = specifier.clone();
       let mode = mode.clone();
       let filter = filter.clone();
+      let names = failed_only.as_ref().map(|failures| {
+        failures
+          .get(specifier.as_str())
+          .into_iter()
+          .flatten()
+          .cloned()
+          .collect::<Vec<_>>()
+      });
       let sender = sender.clone();
+      let durations = durations.clone();
 
       tokio::task::spawn_blocking(move || {
         let join_handle = std::thread::spawn(move || {
+          let started = Instant::now();
           let future = test_specifier(
             ps,
             permissions,
-            specifier,
+            specifier.clone(),
             mode,
             filter,
+            names,
             shuffle,
+            timeout,
+            update_snapshots,
             sender,
           );
 
-          run_basic(future)
+          let result = run_basic(future);
+          let elapsed = started.elapsed().as_millis() as u64;
+          *durations
+            .lock()
+            .unwrap()
+            .entry(specifier.to_string())
+            .or_default() += elapsed;
+          result
         });
 
         join_handle.join().unwrap()
       })
-    });
+    }
+  });
 
   let join_stream = stream::iter(join_handles)
     .buffer_unordered(concurrent_jobs.get())
     .collect::<Vec<Result<Result<(), AnyError>, tokio::task::JoinError>>>();
 
-  let mut reporter =
-    create_reporter(concurrent_jobs.get() > 1, log_level != Some(Level::Error));
+  let mut reporter = create_reporter(
+    reporter_kind,
+    reporter_output,
+    concurrent_jobs.get() > 1,
+    log_level != Some(Level::Error),
+  )?;
 
   let handler = {
     tokio::task::spawn_blocking(move || {
       let earlier = Instant::now();
       let mut summary = TestSummary::new();
       let mut used_only = false;
+      let mut ran = BTreeMap::<String, BTreeSet<String>>::new();
+      let mut complete = BTreeSet::new();
 
       for event in receiver.iter() {
         match event {
           TestEvent::Plan(plan) => {
+            if plan.filtered_out == 0 {
+              complete.insert(plan.origin.clone());
+            }
             summary.total += plan.total;
             summary.filtered_out += plan.filtered_out;
 
@@ -854,12 +2052,19 @@ async fn test_specifiers(
               TestResult::Ignored => {
                 summary.ignored += 1;
               }
-              TestResult::Failed(error) => {
+              TestResult::Failed(error) | TestResult::TimedOut(error) => {
                 summary.failed += 1;
                 summary.failures.push((description.clone(), error.clone()));
               }
             }
 
+            if !matches!(result, TestResult::Ignored) {
+              ran
+                .entry(description.origin.clone())
+                .or_default()
+                .insert(description.name.clone());
+            }
+
             reporter.report_result(&description, &result, elapsed);
           }
 
@@ -875,7 +2080,7 @@ async fn test_specifiers(
               TestStepResult::Ignored => {
                 summary.ignored_steps += 1;
               }
-              TestStepResult::Failed(_) => {
+              TestStepResult::Failed(_) | TestStepResult::TimedOut(_) => {
                 summary.failed_steps += 1;
               }
               TestStepResult::Pending(_) => {
@@ -885,6 +2090,19 @@ async fn test_specifiers(
 
             reporter.report_step_result(&description, &result, duration);
           }
+
+          TestEvent::Snapshots(snapshots) => {
+            summary.snapshots_written += snapshots.written;
+            summary.snapshots_updated += snapshots.updated;
+            summary.snapshots_removed += snapshots.removed;
+            let origin = snapshots.origin;
+            summary.obsolete_snapshots.extend(
+              snapshots
+                .obsolete
+                .into_iter()
+                .map(|name| (origin.clone(), name)),
+            );
+          }
         }
 
         if let Some(x) = fail_fast {
@@ -897,6 +2115,19 @@ async fn test_specifiers(
       let elapsed = Instant::now().duration_since(earlier);
       reporter.report_summary(&summary, &elapsed);
 
+      let mut failures = TestFailures::new();
+      for (description, _) in &summary.failures {
+        failures
+          .entry(description.origin.clone())
+          .or_default()
+          .insert(description.name.clone());
+      }
+      if let Err(err) =
+        save_test_failures(&failures_path, &ran, &complete, failures)
+      {
+        log::warn!("{}", err);
+      }
+
       if used_only {
         return Err(generic_error(
           "Test failed because the \"only\" option was used",
@@ -913,6 +2144,13 @@ async fn test_specifiers(
 
   let (join_results, result) = future::join(join_stream, handler).await;
 
+  if record_durations {
+    let durations = std::mem::take(&mut *durations.lock().unwrap());
+    if let Err(err) = save_test_durations(&ps, durations) {
+      log::warn!("{}", err);
+    }
+  }
+
   // propagate any errors
   for join_result in join_results {
     join_result??;
@@ -1008,7 +2246,14 @@ pub async fn run_tests(
   allow_none: bool,
   filter: Option<String>,
   shuffle: Option<u64>,
+  shard: Option<TestShard>,
+  shard_by_duration: bool,
+  rerun_failed: bool,
+  timeout: Option<NonZeroU64>,
+  update_snapshots: bool,
   concurrent_jobs: NonZeroUsize,
+  reporter_kind: TestReporterKind,
+  reporter_output: Option<PathBuf>,
 ) -> Result<(), AnyError> {
   let ps = ProcState::build(flags.clone()).await?;
   let permissions = Permissions::from_options(&flags.clone().into());
@@ -1024,6 +2269,32 @@ pub async fn run_tests(
     return Err(generic_error("No test modules found"));
   }
 
+  let specifiers_with_mode = if let Some(shard) = shard {
+    // recorded durations differ between DENO_DIRs, so they are only used when
+    // asked for, as every shard needs to compute the same partition
+    let durations = if shard_by_duration {
+      load_test_durations(&ps)
+    } else {
+      TestDurations::new()
+    };
+    select_shard(specifiers_with_mode, shard, &durations)
+  } else {
+    specifiers_with_mode
+  };
+
+  let (specifiers_with_mode, failed_only) = if rerun_failed {
+    let failures = load_test_failures(&test_failures_path(&ps));
+    match select_failed(&specifiers_with_mode, &failures) {
+      Some(failed) => (failed, Some(failures)),
+      None => {
+        log::info!("{}", NO_RECORDED_FAILURES);
+        return Ok(());
+      }
+    }
+  } else {
+    (specifiers_with_mode, None)
+  };
+
   let lib = if flags.unstable {
     emit::TypeLib::UnstableDenoWindow
   } else {
@@ -1048,8 +2319,13 @@ pub async fn run_tests(
     specifiers_with_mode,
     fail_fast,
     filter,
+    failed_only,
     shuffle,
+    timeout,
+    update_snapshots,
     concurrent_jobs,
+    reporter_kind,
+    reporter_output,
   )
   .await?;
 
@@ -1066,7 +2342,12 @@ pub async fn run_tests_with_watch(
   fail_fast: Option<NonZeroUsize>,
   filter: Option<String>,
   shuffle: Option<u64>,
+  rerun_failed: bool,
+  timeout: Option<NonZeroU64>,
+  update_snapshots: bool,
   concurrent_jobs: NonZeroUsize,
+  reporter_kind: TestReporterKind,
+  reporter_output: Option<PathBuf>,
 ) -> Result<(), AnyError> {
   let ps = ProcState::build(flags.clone()).await?;
   let permissions = Permissions::from_options(&flags.clone().into());
@@ -1228,6 +2509,8 @@ pub async fn run_tests_with_watch(
 
   let operation = |modules_to_reload: Vec<ModuleSpecifier>| {
     let filter = filter.clone();
+    let reporter_kind = reporter_kind.clone();
+    let reporter_output = reporter_output.clone();
     let include = include.clone();
     let ignore = ignore.clone();
     let lib = lib.clone();
@@ -1235,7 +2518,7 @@ pub async fn run_tests_with_watch(
     let ps = ps.clone();
 
     async move {
-      let specifiers_with_mode = fetch_specifiers_with_test_mode(
+      let mut specifiers_with_mode = fetch_specifiers_with_test_mode(
         ps.clone(),
         include.clone(),
         ignore.clone(),
@@ -1247,6 +2530,25 @@ pub async fn run_tests_with_watch(
       .cloned()
       .collect::<Vec<(ModuleSpecifier, TestMode)>>();
 
+      // Modules that failed in their previous run are run first, so a fix is
+      // confirmed before the rest of the affected modules run.
+      let failures = load_test_failures(&test_failures_path(&ps));
+      specifiers_with_mode.sort_by_key(|(specifier, _)| {
+        !failures.contains_key(specifier.as_str())
+      });
+
+      let (specifiers_with_mode, failed_only) = if rerun_failed {
+        match select_failed(&specifiers_with_mode, &failures) {
+          Some(failed) => (failed, Some(failures)),
+          None => {
+            log::info!("{}", NO_RECORDED_FAILURES);
+            return Ok(());
+          }
+        }
+      } else {
+        (specifiers_with_mode, None)
+      };
+
       check_specifiers(
         ps.clone(),
         permissions.clone(),
@@ -1265,8 +2567,13 @@ pub async fn run_tests_with_watch(
         specifiers_with_mode,
         fail_fast,
         filter.clone(),
+        failed_only,
         shuffle,
+        timeout,
+        update_snapshots,
         concurrent_jobs,
+        reporter_kind,
+        reporter_output,
       )
       .await?;
 
@@ -1293,4 +2600,281 @@ mod tests {
     assert_eq!(human_elapsed(70 * 1000), "(1m10s)");
     assert_eq!(human_elapsed(86 * 1000 + 100), "(1m26s)");
   }
+
+  fn record_sample_run() -> TestRecorder {
+    let test = TestDescription {
+      origin: "file:///a_test.ts".to_string(),
+      name: "outer".to_string(),
+    };
+    let step = |name: &str, level: usize| TestStepDescription {
+      test: test.clone(),
+      level,
+      name: name.to_string(),
+    };
+
+    let mut recorder = TestRecorder::default();
+    recorder.record_plan(&TestPlan {
+      origin: test.origin.clone(),
+      total: 2,
+      filtered_out: 0,
+      used_only: false,
+    });
+    recorder.record_wait(&test);
+    recorder.record_output(&TestOutput::Console("hello".to_string()));
+    recorder.record_step_result(&step("inner", 2), &TestStepResult::Ok, 1);
+    recorder.record_step_result(
+      &step("first", 1),
+      &TestStepResult::Failed(None),
+      2,
+    );
+    recorder.record_step_result(
+      &step("second", 1),
+      &TestStepResult::Ignored,
+      0,
+    );
+    recorder.record_result(
+      &test,
+      &TestResult::Failed("Error: <1 test step failed>".to_string()),
+      5,
+    );
+
+    let ignored = TestDescription {
+      origin: test.origin.clone(),
+      name: "ignored # too slow".to_string(),
+    };
+    recorder.record_wait(&ignored);
+    recorder.record_result(&ignored, &TestResult::Ignored, 0);
+
+    recorder
+  }
+
+  fn completed_tests(registered: &[&str], passed: &[&str]) -> CompletedTests {
+    CompletedTests {
+      registered: registered.iter().map(|name| name.to_string()).collect(),
+      passed: passed.iter().map(|name| name.to_string()).collect(),
+    }
+  }
+
+  #[test]
+  fn test_snapshots() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let specifier =
+      ModuleSpecifier::from_file_path(temp_dir.path().join("foo_test.ts"))
+        .unwrap();
+    let snapshot_path = temp_dir.path().join("__snapshots__/foo_test.ts.snap");
+
+    let mut snapshots = TestSnapshots::load(&specifier, false).unwrap();
+    let message = snapshots.assert("a".to_string(), "1".to_string()).unwrap();
+    assert_eq!(
+      message.unwrap(),
+      "Snapshot \"a 1\" does not exist. Run with --update-snapshots to write it."
+    );
+
+    let mut snapshots = TestSnapshots::load(&specifier, true).unwrap();
+    for (name, actual) in [("a", "1"), ("a", "2"), ("b > c", "3")] {
+      let message = snapshots.assert(name.to_string(), actual.to_string());
+      assert_eq!(message.unwrap(), None);
+    }
+    snapshots.complete(completed_tests(&["a", "b"], &["a", "b"]));
+    let summary = snapshots.finish().unwrap();
+    assert_eq!(
+      (summary.written, summary.updated, summary.removed),
+      (3, 0, 0)
+    );
+    assert_eq!(
+      fs::read_to_string(&snapshot_path).unwrap(),
+      "{\n  \"a\": [\n    \"1\",\n    \"2\"\n  ],\n  \"b > c\": [\n    \"3\"\n  ]\n}\n"
+    );
+
+    // Snapshots of failed tests are not obsolete, as they may not have been
+    // asserted yet.
+    let mut snapshots = TestSnapshots::load(&specifier, false).unwrap();
+    assert_eq!(
+      snapshots.assert("a".to_string(), "1".to_string()).unwrap(),
+      None
+    );
+    let message = snapshots.assert("a".to_string(), "3".to_string()).unwrap();
+    assert!(message
+      .unwrap()
+      .starts_with("Snapshot \"a 2\" does not match:"));
+    snapshots.complete(completed_tests(&["a", "b"], &["a"]));
+    assert_eq!(snapshots.finish().unwrap().obsolete, Vec::<String>::new());
+
+    let mut snapshots = TestSnapshots::load(&specifier, false).unwrap();
+    assert_eq!(
+      snapshots.assert("a".to_string(), "1".to_string()).unwrap(),
+      None
+    );
+    snapshots.complete(completed_tests(&["a"], &["a"]));
+    assert_eq!(snapshots.finish().unwrap().obsolete, vec!["a 2", "b > c 1"]);
+
+    // Without the completed tests, such as when a test timed out, nothing is
+    // considered obsolete either.
+    let mut snapshots = TestSnapshots::load(&specifier, true).unwrap();
+    assert_eq!(
+      snapshots.assert("a".to_string(), "4".to_string()).unwrap(),
+      None
+    );
+    let summary = snapshots.finish().unwrap();
+    assert_eq!(
+      (summary.written, summary.updated, summary.removed),
+      (0, 1, 0)
+    );
+
+    let mut snapshots = TestSnapshots::load(&specifier, true).unwrap();
+    snapshots.complete(completed_tests(&[], &[]));
+    let summary = snapshots.finish().unwrap();
+    assert_eq!(summary.removed, 3);
+    assert!(!snapshot_path.exists());
+
+    let remote = ModuleSpecifier::parse("https://deno.land/x/foo_test.ts");
+    let mut snapshots = TestSnapshots::load(&remote.unwrap(), false).unwrap();
+    assert!(snapshots.assert("a".to_string(), "1".to_string()).is_err());
+  }
+
+  #[test]
+  fn test_recorder_nests_steps() {
+    let recorder = record_sample_run();
+    assert_eq!(recorder.modules.len(), 1);
+
+    let tests = &recorder.modules[0].tests;
+    assert_eq!(tests.len(), 2);
+    assert_eq!(tests[0].output, vec!["hello".to_string()]);
+    assert_eq!(tests[0].steps.len(), 2);
+    assert_eq!(tests[0].steps[0].name, "first");
+    assert_eq!(tests[0].steps[0].status, TestRecordStatus::Failed);
+    assert_eq!(tests[0].steps[0].steps.len(), 1);
+    assert_eq!(tests[0].steps[0].steps[0].name, "inner");
+    assert_eq!(tests[0].steps[1].status, TestRecordStatus::Ignored);
+    assert!(tests[1].output.is_empty());
+    assert!(recorder.pending_steps.is_empty());
+  }
+
+  #[test]
+  fn test_render_junit() {
+    let recorder = record_sample_run();
+    let report = render_junit(
+      &recorder.modules,
+      &TestSummary::new(),
+      &Duration::from_millis(1500),
+    );
+    assert_eq!(
+      report,
+      r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="deno test" tests="5" failures="2" skipped="2" time="1.500">
+  <testsuite name="file:///a_test.ts" tests="5" failures="2" skipped="2" time="0.005">
+    <testcase name="outer" classname="file:///a_test.ts" time="0.005">
+      <failure message="Error: &lt;1 test step failed&gt;">Error: &lt;1 test step failed&gt;</failure>
+      <system-out>hello</system-out>
+    </testcase>
+    <testcase name="outer &gt; first" classname="file:///a_test.ts" time="0.002">
+      <failure message="Test step failed.">Test step failed.</failure>
+    </testcase>
+    <testcase name="outer &gt; first &gt; inner" classname="file:///a_test.ts" time="0.001"/>
+    <testcase name="outer &gt; second" classname="file:///a_test.ts" time="0.000">
+      <skipped/>
+    </testcase>
+    <testcase name="ignored # too slow" classname="file:///a_test.ts" time="0.000">
+      <skipped/>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+    );
+  }
+
+  #[test]
+  fn test_render_tap() {
+    let recorder = record_sample_run();
+    let report = render_tap(
+      &recorder.modules,
+      &TestSummary::new(),
+      &Duration::from_millis(1500),
+    );
+    assert_eq!(
+      report,
+      r#"TAP version 14
+1..2
+# file:///a_test.ts
+# Subtest: outer
+    # Subtest: first
+        ok 1 - inner
+          ---
+          duration_ms: 1
+          ...
+        1..1
+    not ok 1 - first
+      ---
+      duration_ms: 2
+      error: |-
+        Test step failed.
+      ...
+    ok 2 - second # SKIP
+      ---
+      duration_ms: 0
+      ...
+    1..2
+not ok 1 - outer
+  ---
+  duration_ms: 5
+  error: |-
+    Error: <1 test step failed>
+  output: |-
+    hello
+  ...
+ok 2 - ignored \# too slow # SKIP
+  ---
+  duration_ms: 0
+  ...
+"#
+    );
+  }
+
+  #[test]
+  fn test_select_shard() {
+    let specifiers_with_mode = ["a", "b", "c", "d", "e"]
+      .iter()
+      .map(|name| {
+        let specifier =
+          ModuleSpecifier::parse(&format!("file:///{}_test.ts", name)).unwrap();
+        (specifier, TestMode::Executable)
+      })
+      .collect::<Vec<_>>();
+    let names = |shard: Vec<(ModuleSpecifier, TestMode)>| {
+      shard
+        .into_iter()
+        .map(|(specifier, _)| specifier.path()[1..2].to_string())
+        .collect::<Vec<_>>()
+    };
+    let select = |index: usize, durations: &TestDurations| {
+      names(select_shard(
+        specifiers_with_mode.clone(),
+        TestShard { index, total: 2 },
+        durations,
+      ))
+    };
+
+    let durations = TestDurations::new();
+    assert_eq!(select(1, &durations), vec!["a", "c", "e"]);
+    assert_eq!(select(2, &durations), vec!["b", "d"]);
+
+    let mut durations = TestDurations::new();
+    durations.insert("file:///c_test.ts".to_string(), 1000);
+    durations.insert("file:///e_test.ts".to_string(), 200);
+    durations.insert("file:///b_test.ts".to_string(), 300);
+    // "a" and "d" are weighted with the average of 500ms.
+    assert_eq!(select(1, &durations), vec!["b", "c"]);
+    assert_eq!(select(2, &durations), vec!["a", "d", "e"]);
+
+    let all = (1..=3)
+      .flat_map(|index| {
+        names(select_shard(
+          specifiers_with_mode.clone(),
+          TestShard { index, total: 3 },
+          &durations,
+        ))
+      })
+      .collect::<BTreeSet<_>>();
+    assert_eq!(all.len(), specifiers_with_mode.len());
+  }
 }
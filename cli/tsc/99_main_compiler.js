@@ -819,6 +819,26 @@ delete Object.prototype.__proto__;
           ),
         );
       }
+      case "organizeImports": {
+        return respond(
+          id,
+          languageService.organizeImports(
+            {
+              type: "file",
+              fileName: request.specifier,
+              skipDestructiveCodeActions: request.skipDestructiveCodeActions,
+            },
+            {
+              indentSize: 2,
+              indentStyle: ts.IndentStyle.Block,
+              semicolons: ts.SemicolonPreference.Insert,
+            },
+            {
+              quotePreference: "double",
+            },
+          ),
+        );
+      }
       case "prepareCallHierarchy": {
         return respond(
           id,
@@ -846,6 +866,16 @@ delete Object.prototype.__proto__;
           ),
         );
       }
+      case "provideInlayHints": {
+        return respond(
+          id,
+          languageService.provideInlayHints(
+            request.specifier,
+            request.span,
+            request.preferences,
+          ),
+        );
+      }
       default:
         throw new TypeError(
           // @ts-ignore exhausted case statement sets type to never
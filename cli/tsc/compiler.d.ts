@@ -67,9 +67,11 @@ declare global {
     | GetSmartSelectionRange
     | GetSupportedCodeFixes
     | GetTypeDefinitionRequest
+    | OrganizeImports
     | PrepareCallHierarchy
     | ProvideCallHierarchyIncomingCalls
-    | ProvideCallHierarchyOutgoingCalls;
+    | ProvideCallHierarchyOutgoingCalls
+    | ProvideInlayHints;
 
   interface BaseLanguageServerRequest {
     id: number;
@@ -227,6 +229,12 @@ declare global {
     position: number;
   }
 
+  interface OrganizeImports extends BaseLanguageServerRequest {
+    method: "organizeImports";
+    specifier: string;
+    skipDestructiveCodeActions: boolean;
+  }
+
   interface PrepareCallHierarchy extends BaseLanguageServerRequest {
     method: "prepareCallHierarchy";
     specifier: string;
@@ -246,4 +254,11 @@ declare global {
     specifier: string;
     position: number;
   }
+
+  interface ProvideInlayHints extends BaseLanguageServerRequest {
+    method: "provideInlayHints";
+    specifier: string;
+    span: ts.TextSpan;
+    preferences?: ts.InlayHintsOptions;
+  }
 }
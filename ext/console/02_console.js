@@ -279,6 +279,7 @@
     trailingComma: false,
     compact: true,
     iterableLimit: 100,
+    strAbbreviateSize: 100,
     showProxy: false,
     colors: false,
     getters: false,
@@ -289,7 +290,6 @@
 
   const LINE_BREAKING_LENGTH = 80;
   const MIN_GROUP_LENGTH = 6;
-  const STR_ABBREVIATE_SIZE = 100;
 
   const PROMISE_STRING_BASE_LENGTH = 12;
 
@@ -748,8 +748,9 @@
     const green = maybeColor(colors.green, inspectOptions);
     switch (typeof value) {
       case "string": {
-        const trunc = value.length > STR_ABBREVIATE_SIZE
-          ? StringPrototypeSlice(value, 0, STR_ABBREVIATE_SIZE) + "..."
+        const { strAbbreviateSize } = inspectOptions;
+        const trunc = value.length > strAbbreviateSize
+          ? StringPrototypeSlice(value, 0, strAbbreviateSize) + "..."
           : value;
         return green(quoteString(trunc)); // Quoted strings are green
       }
@@ -6,16 +6,25 @@
   const {
     ArrayPrototypePush,
     ArrayPrototypeShift,
+    Date,
+    DateNow,
+    DateParse,
+    DatePrototypeToString,
+    DateUTC,
     Error,
     FunctionPrototypeCall,
     Map,
     MapPrototypeDelete,
+    MapPrototypeForEach,
     MapPrototypeGet,
     MapPrototypeHas,
     MapPrototypeSet,
+    MathFloor,
+    NumberIsFinite,
     // deno-lint-ignore camelcase
     NumberPOSITIVE_INFINITY,
     PromisePrototypeThen,
+    ReflectConstruct,
     SymbolFor,
     TypeError,
   } = window.__bootstrap.primordials;
@@ -88,7 +97,7 @@
    * The keys in this map correspond to the key ID's in the spec's map of active
    * timers. The values are the timeout's cancel rid.
    *
-   * @type {Map<number, { cancelRid: number, isRef: boolean, promiseId: number }>}
+   * @type {Map<number, { cancelRid: number, isRef: boolean, promiseId: number, isVirtual: boolean }>}
    */
   const activeTimers = new Map();
 
@@ -124,7 +133,12 @@
       // https://github.com/whatwg/html/issues/7358
       id = nextId++;
       const cancelRid = core.opSync("op_timer_handle");
-      timerInfo = { cancelRid, isRef: true, promiseId: -1 };
+      timerInfo = {
+        cancelRid,
+        isRef: true,
+        promiseId: -1,
+        isVirtual: virtualClock !== null,
+      };
 
       // Step 4 in "run steps after a timeout".
       MapPrototypeSet(activeTimers, id, timerInfo);
@@ -356,6 +370,149 @@
     clearTimeout(id);
   }
 
+  // ---------------------------------------------------------------------------
+
+  /**
+   * The installed virtual clock, if any: the time `Date.now()` reported when it
+   * was installed and the `virtualClockNow()` time at that point.
+   *
+   * @type { {epoch: number, start: number} | null }
+   */
+  let virtualClock = null;
+
+  /**
+   * Guards `runVirtualTimers` against intervals and timers that keep
+   * rescheduling themselves.
+   */
+  const MAX_VIRTUAL_TIMER_RUNS = 10000;
+
+  /**
+   * The time of the virtual clock at full precision, unlike `opNow()`, which
+   * reduces it without the hrtime permission. The virtual clock ops are only
+   * registered by the test runner.
+   */
+  function virtualClockNow() {
+    return core.opSync("op_virtual_clock_now");
+  }
+
+  function virtualDateNow() {
+    return MathFloor(
+      virtualClock.epoch + (virtualClockNow() - virtualClock.start),
+    );
+  }
+
+  // Stands in for the global `Date` while a virtual clock is installed, so that
+  // both `Date.now()` and `new Date()` report the virtual time.
+  function VirtualDate(...args) {
+    if (new.target === undefined) {
+      return DatePrototypeToString(new Date(virtualDateNow()));
+    }
+    return ReflectConstruct(
+      Date,
+      args.length === 0 ? [virtualDateNow()] : args,
+      new.target,
+    );
+  }
+  VirtualDate.prototype = Date.prototype;
+  VirtualDate.now = virtualDateNow;
+  VirtualDate.parse = DateParse;
+  VirtualDate.UTC = DateUTC;
+
+  /**
+   * Stops time until the clock is advanced with `tickVirtualClock` or
+   * `runVirtualTimers`. Timers scheduled in the meantime only fire once the
+   * clock reaches their deadline, and are discarded when it is uninstalled.
+   *
+   * @param {number} epoch The time reported by `Date.now()`.
+   */
+  function installVirtualClock(epoch = DateNow()) {
+    core.opSync("op_virtual_clock_install");
+    virtualClock = { epoch, start: virtualClockNow() };
+    globalThis.Date = VirtualDate;
+  }
+
+  function uninstallVirtualClock() {
+    const virtualTimers = [];
+    MapPrototypeForEach(activeTimers, (timerInfo, id) => {
+      if (timerInfo.isVirtual) {
+        ArrayPrototypePush(virtualTimers, id);
+      }
+    });
+    for (const id of virtualTimers) {
+      clearTimeout(id);
+    }
+
+    core.opSync("op_virtual_clock_uninstall");
+    globalThis.Date = Date;
+    virtualClock = null;
+  }
+
+  function isVirtualClockInstalled() {
+    return virtualClock !== null;
+  }
+
+  function nextEventLoopTurn() {
+    return core.opAsync("op_virtual_clock_yield");
+  }
+
+  /**
+   * Advances the virtual clock one deadline at a time, running the timers that
+   * are due along with the ones they schedule, until the clock reaches
+   * `target`.
+   *
+   * @param {number} target
+   */
+  async function advanceVirtualClock(target) {
+    for (let runs = 0;; runs++) {
+      // The callbacks of the woken timers run as macrotasks at the end of the
+      // turn in which their sleeps resolve, after this function resumes, so it
+      // takes a second turn before the timers they schedule are known.
+      await nextEventLoopTurn();
+      await nextEventLoopTurn();
+
+      const next = core.opSync("op_virtual_clock_next");
+      if (next === null || next > target) {
+        break;
+      }
+      if (runs === MAX_VIRTUAL_TIMER_RUNS) {
+        throw new Error(
+          `Aborting after running ${MAX_VIRTUAL_TIMER_RUNS} timers, assuming an infinite loop.`,
+        );
+      }
+      core.opSync("op_virtual_clock_advance", next);
+    }
+
+    if (target !== NumberPOSITIVE_INFINITY) {
+      core.opSync("op_virtual_clock_advance", target);
+    }
+  }
+
+  function assertVirtualClockInstalled() {
+    if (virtualClock === null) {
+      throw new Error("No virtual clock is installed.");
+    }
+  }
+
+  /**
+   * Moves the virtual clock forward by `millis`, running the timers that are
+   * due on the way.
+   *
+   * @param {number} millis
+   */
+  function tickVirtualClock(millis) {
+    assertVirtualClockInstalled();
+    if (typeof millis !== "number" || !NumberIsFinite(millis) || millis < 0) {
+      throw new TypeError("The ticked time must be a non-negative number of ms.");
+    }
+    return advanceVirtualClock(virtualClockNow() + millis);
+  }
+
+  /** Moves the virtual clock forward until no timers are pending. */
+  function runVirtualTimers() {
+    assertVirtualClockInstalled();
+    return advanceVirtualClock(NumberPOSITIVE_INFINITY);
+  }
+
   function refTimer(id) {
     const timerInfo = MapPrototypeGet(activeTimers, id);
     if (timerInfo === undefined || timerInfo.isRef) {
@@ -384,5 +541,14 @@
     sleepSync,
     refTimer,
     unrefTimer,
+    virtualClock: {
+      install: installVirtualClock,
+      uninstall: uninstallVirtualClock,
+      isInstalled: isVirtualClockInstalled,
+      now: virtualDateNow,
+      tick: tickVirtualClock,
+      runAll: runVirtualTimers,
+      nextEventLoopTurn,
+    },
   };
 })(this);
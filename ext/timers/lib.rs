@@ -17,6 +17,7 @@ use std::cell::RefCell;
 use std::rc::Rc;
 use std::time::Duration;
 use std::time::Instant;
+use tokio::sync::oneshot;
 
 pub trait TimersPermission {
   fn allow_hrtime(&mut self) -> bool;
@@ -57,9 +58,14 @@ pub fn op_now<TP>(
 where
   TP: TimersPermission + 'static,
 {
-  let start_time = state.borrow::<StartTime>();
-  let seconds = start_time.elapsed().as_secs();
-  let mut subsec_nanos = start_time.elapsed().subsec_nanos() as f64;
+  // While a virtual clock is installed, its time stands in for the time since
+  // the start time, with the same reduced precision.
+  let elapsed = match state.try_borrow::<VirtualClock>() {
+    Some(clock) => clock.elapsed(),
+    None => state.borrow::<StartTime>().elapsed(),
+  };
+  let seconds = elapsed.as_secs();
+  let mut subsec_nanos = elapsed.subsec_nanos() as f64;
   let reduced_time_precision = 2_000_000.0; // 2ms in nanoseconds
 
   // If the permission is not enabled
@@ -100,13 +106,26 @@ pub fn op_timer_handle(
 }
 
 /// Waits asynchronously until either `millis` milliseconds have passed or the
-/// [`TimerHandle`] resource given by `rid` has been canceled.
+/// [`TimerHandle`] resource given by `rid` has been canceled. While a
+/// [`VirtualClock`] is installed, the milliseconds are measured on it instead.
 pub async fn op_sleep(
   state: Rc<RefCell<OpState>>,
   millis: u64,
   rid: ResourceId,
 ) -> Result<(), AnyError> {
   let handle = state.borrow().resource_table.get::<TimerHandle>(rid)?;
+  let maybe_receiver = state
+    .borrow_mut()
+    .try_borrow_mut::<VirtualClock>()
+    .map(|clock| clock.sleep(millis));
+  if let Some(receiver) = maybe_receiver {
+    // The sender is dropped when the clock is uninstalled, after which the
+    // sleep continues in real time.
+    if receiver.or_cancel(handle.0.clone()).await?.is_ok() {
+      return Ok(());
+    }
+  }
+
   tokio::time::sleep(Duration::from_millis(millis))
     .or_cancel(handle.0.clone())
     .await?;
@@ -125,3 +144,89 @@ where
   std::thread::sleep(Duration::from_millis(millis));
   Ok(())
 }
+
+/// A clock that only moves when it is advanced, used by the test runner to
+/// control time in tests. While one is in the [`OpState`], `op_now` reports its
+/// time and `op_sleep` resolves once it has been advanced past the deadline.
+/// The ops which install and advance it are only registered by the test
+/// runner.
+pub struct VirtualClock {
+  /// Milliseconds since the [`StartTime`].
+  now: f64,
+  sleepers: Vec<(f64, oneshot::Sender<()>)>,
+}
+
+impl VirtualClock {
+  pub fn new(now: f64) -> Self {
+    Self {
+      now,
+      sleepers: Vec::new(),
+    }
+  }
+
+  /// Milliseconds since the [`StartTime`], at full precision.
+  pub fn now(&self) -> f64 {
+    self.now
+  }
+
+  fn elapsed(&self) -> Duration {
+    Duration::from_nanos((self.now * 1_000_000.0).round() as u64)
+  }
+
+  fn sleep(&mut self, millis: u64) -> oneshot::Receiver<()> {
+    let (sender, receiver) = oneshot::channel();
+    self.sleepers.push((self.now + millis as f64, sender));
+    receiver
+  }
+
+  /// The earliest deadline of the sleeps that are still waiting.
+  pub fn next_deadline(&mut self) -> Option<f64> {
+    self.sleepers.retain(|(_, sender)| !sender.is_closed());
+    self
+      .sleepers
+      .iter()
+      .map(|(deadline, _)| *deadline)
+      .reduce(f64::min)
+  }
+
+  /// Moves the clock forward to `time`, waking the sleeps whose deadline has
+  /// been reached. The clock never moves backwards.
+  pub fn advance_to(&mut self, time: f64) {
+    self.now = self.now.max(time);
+    let now = self.now;
+    let (expired, pending) = std::mem::take(&mut self.sleepers)
+      .into_iter()
+      .partition::<Vec<_>, _>(|(deadline, _)| *deadline <= now);
+    self.sleepers = pending;
+    for (_, sender) in expired {
+      sender.send(()).ok();
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_virtual_clock() {
+    let mut clock = VirtualClock::new(10.0);
+    let mut first = clock.sleep(100);
+    let mut second = clock.sleep(50);
+    let third = clock.sleep(50);
+    drop(third);
+    assert_eq!(clock.next_deadline(), Some(60.0));
+
+    clock.advance_to(60.0);
+    assert!(second.try_recv().is_ok());
+    assert!(first.try_recv().is_err());
+    assert_eq!(clock.next_deadline(), Some(110.0));
+
+    clock.advance_to(20.0);
+    assert_eq!(clock.now, 60.0);
+
+    clock.advance_to(200.0);
+    assert!(first.try_recv().is_ok());
+    assert_eq!(clock.next_deadline(), None);
+  }
+}
@@ -4,16 +4,21 @@
 ((window) => {
   const core = window.Deno.core;
   const { setExitHandler } = window.__bootstrap.os;
-  const { Console, inspectArgs } = window.__bootstrap.console;
+  const { Console, inspect, inspectArgs } = window.__bootstrap.console;
   const { metrics } = core;
   const { serializePermissions } = window.__bootstrap.permissions;
   const { assert } = window.__bootstrap.util;
+  const { virtualClock } = window.__bootstrap.timers;
   const {
     AggregateError,
     ArrayPrototypeFilter,
+    ArrayPrototypeIncludes,
+    ArrayPrototypeJoin,
+    ArrayPrototypeMap,
     ArrayPrototypePush,
     ArrayPrototypeShift,
     ArrayPrototypeSome,
+    Date,
     DateNow,
     Error,
     Function,
@@ -26,11 +31,25 @@
     StringPrototypeSlice,
     RegExp,
     Number,
+    NumberIsFinite,
+    NumberIsInteger,
+    ObjectFreeze,
+    PromisePrototypeThen,
     RegExpPrototypeTest,
+    SafeMap,
     SymbolToStringTag,
   } = window.__bootstrap.primordials;
   let testStepsEnabled = false;
 
+  /** @type {number | null} */
+  let defaultTestTimeout = null;
+  /** @type {{ description: { origin: string; name: string }, step: TestStep, start: number } | null} */
+  let runningTest = null;
+  /** @type {{ origin: string; name: string }[]} */
+  let pendingTests = [];
+  /** @type {Map<number, { step: TestStep, timeout: number, start: number, metrics: object, resources: object }>} */
+  const activeTestTimeouts = new SafeMap();
+
   const opSanitizerDelayResolveQueue = [];
 
   // Even if every resource is closed by the end of a test, there can be a delay
@@ -42,11 +61,18 @@
   // will have an associated op. An additional `setTimeout` of 0 is needed
   // before that, though, in order to give time for worker message ops to finish
   // (since timeouts of 0 don't queue tasks in the timer queue immediately).
+  // Under a virtual clock the timeout would never fire, so waiting for the next
+  // turn of the event loop has to do.
   function opSanitizerDelay() {
     return new Promise((resolve) => {
-      setTimeout(() => {
+      const delay = () => {
         ArrayPrototypePush(opSanitizerDelayResolveQueue, resolve);
-      }, 0);
+      };
+      if (virtualClock.isInstalled()) {
+        PromisePrototypeThen(virtualClock.nextEventLoopTurn(), delay);
+      } else {
+        setTimeout(delay, 0);
+      }
     });
   }
 
@@ -252,6 +278,133 @@ finishing test case.`;
     };
   }
 
+  function assertValidTimeout(timeout) {
+    if (
+      timeout != null &&
+      (typeof timeout !== "number" || !NumberIsFinite(timeout) || timeout <= 0)
+    ) {
+      throw new TypeError("The timeout must be a positive number of ms.");
+    }
+  }
+
+  // Deadlines are enforced by the test runner, which terminates the isolate
+  // once one passes and then calls `reportTestTimeout` with its id. Snapshots
+  // of the ops metrics and resources are kept to describe what the test was
+  // waiting for.
+  function startTestTimeout(step, timeout) {
+    if (timeout == null) {
+      return null;
+    }
+
+    const id = core.opSync("op_start_test_timeout", timeout);
+    activeTestTimeouts.set(id, {
+      step,
+      timeout,
+      start: DateNow(),
+      metrics: metrics(),
+      resources: core.resources(),
+    });
+    return id;
+  }
+
+  function clearTestTimeout(id) {
+    if (id === null) {
+      return;
+    }
+
+    activeTestTimeouts.delete(id);
+    core.opSync("op_clear_test_timeout", id);
+  }
+
+  function formatTestTimeout({ step, timeout, metrics: pre, resources }) {
+    const post = metrics();
+    const pendingOps = [];
+    for (const key in post.ops) {
+      const pending = Number(
+        (post.ops[key]?.opsDispatchedAsync ?? 0) -
+          (post.ops[key]?.opsCompletedAsync ?? 0) -
+          ((pre.ops[key]?.opsDispatchedAsync ?? 0) -
+            (pre.ops[key]?.opsCompletedAsync ?? 0)),
+      );
+      if (pending > 0) {
+        ArrayPrototypePush(pendingOps, `  ${key}: ${pending}`);
+      }
+    }
+
+    const postResources = core.resources();
+    const openedResources = [];
+    for (const rid in postResources) {
+      if (!(rid in resources)) {
+        ArrayPrototypePush(openedResources, `  ${rid}: ${postResources[rid]}`);
+      }
+    }
+
+    const kind = step.parent ? "Test step" : "Test case";
+    let message = `${kind} timed out after ${timeout}ms.`;
+    if (pendingOps.length > 0) {
+      message += `\n\nPending async ops:\n${
+        ArrayPrototypeJoin(pendingOps, "\n")
+      }`;
+    }
+    if (openedResources.length > 0) {
+      message += `\n\nResources opened during the test:\n${
+        ArrayPrototypeJoin(openedResources, "\n")
+      }`;
+    }
+    if (pendingOps.length === 0 && openedResources.length === 0) {
+      message += "\n\nNo async ops are pending, " +
+        "the test is likely stuck in synchronous code.";
+    }
+    return message;
+  }
+
+  // Called by the test runner after it terminated a test that ran past its
+  // deadline. The module is abandoned afterwards, so this reports the results
+  // of the running test and its steps in place of `runTests`.
+  function reportTestTimeout(id) {
+    const timeout = activeTestTimeouts.get(id);
+    if (!timeout || !runningTest) {
+      return;
+    }
+
+    const { step } = timeout;
+    const message = formatTestTimeout(timeout);
+    if (step.parent) {
+      step.status = "timedOut";
+      step.error = message;
+      step.elapsed = DateNow() - timeout.start;
+      step.finalized = true;
+      for (const ancestor of step.ancestors()) {
+        if (ancestor.parent) {
+          ancestor.status = "failed";
+          ancestor.finalized = true;
+        }
+      }
+    }
+
+    const { description, step: testStep, start } = runningTest;
+    testStep.finalized = true;
+    for (const child of testStep.children) {
+      child.reportResult();
+    }
+
+    const result = step.parent
+      ? `Test step "${step.getFullName()}" timed out after ${timeout.timeout}ms.`
+      : message;
+    reportTestResult(description, { timedOut: result }, DateNow() - start);
+    runningTest = null;
+
+    // the tests after the one which timed out never get to run, but every
+    // planned test still needs a result
+    for (const pending of pendingTests) {
+      reportTestWait(pending);
+      reportTestResult(pending, {
+        failed: "Not run because an earlier test in the module timed out.",
+      }, 0);
+    }
+    pendingTests = [];
+  }
+
   function withPermissions(fn, permissions) {
     function pledgePermissions(permissions) {
       return core.opSync(
@@ -275,6 +428,45 @@ finishing test case.`;
     };
   }
 
+  function assertValidFakeTime(fakeTime) {
+    if (
+      fakeTime != null && typeof fakeTime !== "boolean" &&
+      !(typeof fakeTime === "number" && NumberIsFinite(fakeTime)) &&
+      !(fakeTime instanceof Date)
+    ) {
+      throw new TypeError(
+        "The fakeTime option must be a boolean, a number of ms since the epoch or a Date.",
+      );
+    }
+  }
+
+  // Runs the test against a virtual clock that only moves when the test
+  // advances it through `t.clock`.
+  function withFakeTime(fn, fakeTime) {
+    return async function applyFakeTime(...params) {
+      virtualClock.install(
+        typeof fakeTime === "boolean" ? DateNow() : Number(fakeTime),
+      );
+      try {
+        await fn(...params);
+      } finally {
+        virtualClock.uninstall();
+      }
+    };
+  }
+
+  const testClock = ObjectFreeze({
+    get now() {
+      return virtualClock.now();
+    },
+    tick(millis) {
+      return virtualClock.tick(millis);
+    },
+    runAll() {
+      return virtualClock.runAll();
+    },
+  });
+
   const tests = [];
 
   // Main test function provided by Deno.
@@ -291,6 +483,8 @@ finishing test case.`;
       sanitizeResources: true,
       sanitizeExit: true,
       permissions: null,
+      timeout: null,
+      fakeTime: false,
     };
 
     if (typeof nameOrFnOrOptions === "string") {
@@ -363,6 +557,15 @@ finishing test case.`;
       testDef = { ...defaults, ...nameOrFnOrOptions, fn, name };
     }
 
+    assertValidTimeout(testDef.timeout);
+    assertValidFakeTime(testDef.fakeTime);
+
+    // `0` is a valid epoch, so only the absence of the option or `false`
+    // leaves the real clock in place
+    if (testDef.fakeTime != null && testDef.fakeTime !== false) {
+      testDef.fn = withFakeTime(testDef.fn, testDef.fakeTime);
+    }
+
     testDef.fn = wrapTestFnWithSanitizers(testDef.fn, testDef);
 
     if (testDef.permissions) {
@@ -423,6 +626,11 @@ finishing test case.`;
       sanitizeResources: test.sanitizeResources,
       sanitizeExit: test.sanitizeExit,
     });
+    runningTest = { description, step, start: DateNow() };
+    const timeoutId = startTestTimeout(
+      step,
+      test.timeout ?? defaultTestTimeout,
+    );
 
     try {
       await test.fn(step);
@@ -439,6 +647,8 @@ finishing test case.`;
         "failed": formatError(error),
       };
     } finally {
+      clearTestTimeout(timeoutId);
+      runningTest = null;
       step.finalized = true;
       // ensure the children report their result
       for (const child of step.children) {
@@ -489,9 +699,12 @@ finishing test case.`;
 
   async function runTests({
     filter = null,
+    names = null,
     shuffle = null,
+    timeout = null,
   } = {}) {
     core.setMacrotaskCallback(handleOpSanitizerDelayMacrotask);
+    defaultTestTimeout = timeout;
 
     const origin = getTestOrigin();
     const originalConsole = globalThis.console;
@@ -499,9 +712,12 @@ finishing test case.`;
     globalThis.console = new Console(reportTestConsoleOutput);
 
     const only = ArrayPrototypeFilter(tests, (test) => test.only);
+    const testFilter = createTestFilter(filter);
     const filtered = ArrayPrototypeFilter(
       only.length > 0 ? only : tests,
-      createTestFilter(filter),
+      (test) =>
+        testFilter(test) &&
+        (names === null || ArrayPrototypeIncludes(names, test.name)),
     );
 
     reportTestPlan({
@@ -529,11 +745,13 @@ finishing test case.`;
       }
     }
 
+    pendingTests = ArrayPrototypeMap(filtered, (test) => ({
+      origin,
+      name: test.name,
+    }));
+    const passed = [];
     for (const test of filtered) {
-      const description = {
-        origin,
-        name: test.name,
-      };
+      const description = ArrayPrototypeShift(pendingTests);
       const earlier = DateNow();
 
       reportTestWait(description);
@@ -542,8 +760,17 @@ finishing test case.`;
       const elapsed = DateNow() - earlier;
 
       reportTestResult(description, result, elapsed);
+
+      if (result === "ok") {
+        ArrayPrototypePush(passed, test.name);
+      }
     }
 
+    core.opSync("op_complete_test_snapshots", {
+      registered: ArrayPrototypeMap(tests, (test) => test.name),
+      passed,
+    });
+
     globalThis.console = originalConsole;
   }
 
@@ -555,6 +782,7 @@ finishing test case.`;
    *   sanitizeOps?: boolean,
    *   sanitizeResources?: boolean,
    *   sanitizeExit?: boolean,
+   *   timeout?: number,
    * }} TestStepDefinition
    *
    * @typedef {{
@@ -574,7 +802,7 @@ finishing test case.`;
     #reportedResult = false;
     finalized = false;
     elapsed = 0;
-    /** @type "ok" | "ignored" | "pending" | "failed" */
+    /** @type "ok" | "ignored" | "pending" | "failed" | "timedOut" */
     status = "pending";
     error = undefined;
     /** @type {TestStep[]} */
@@ -715,6 +943,10 @@ finishing test case.`;
           return {
             "failed": this.error && formatError(this.error),
           };
+        case "timedOut":
+          return {
+            "timedOut": this.error,
+          };
         default:
           throw new Error(`Unhandled status: ${this.status}`);
       }
@@ -737,10 +969,52 @@ finishing test case.`;
     }
   }
 
+  const snapshotInspectOptions = {
+    depth: Infinity,
+    iterableLimit: Infinity,
+    strAbbreviateSize: Infinity,
+    sorted: true,
+    trailingComma: true,
+    compact: false,
+    colors: false,
+  };
+
+  // Strings are stored as they are, so that multi-line text such as the output
+  // of a program is diffed line by line.
+  function serializeSnapshot(value) {
+    return typeof value === "string"
+      ? value
+      : inspect(value, snapshotInspectOptions);
+  }
+
   /** @param parentStep {TestStep} */
   function createTestContext(parentStep) {
     return {
       [SymbolToStringTag]: "TestContext",
+      /**
+       * The virtual clock of a test run with the `fakeTime` option.
+       */
+      get clock() {
+        if (!virtualClock.isInstalled()) {
+          throw new Error(
+            'The clock is only available to tests with the "fakeTime" option.',
+          );
+        }
+        return testClock;
+      },
+      /**
+       * Compares the value against the next snapshot of this test or step.
+       * @param actual {unknown}
+       */
+      assertSnapshot(actual) {
+        const message = core.opSync("op_assert_test_snapshot", {
+          name: parentStep.getFullName(),
+          actual: serializeSnapshot(actual),
+        });
+        if (message !== null) {
+          throw new Error(message);
+        }
+      },
       /**
        * @param nameOrTestDefinition {string | TestStepDefinition}
        * @param fn {(t: TestContext) => void | Promise<void>}
@@ -760,6 +1034,7 @@ finishing test case.`;
         }
 
         const definition = getDefinition();
+        assertValidTimeout(definition.timeout);
         const subStep = new TestStep({
           name: definition.name,
           parent: parentStep,
@@ -795,6 +1070,7 @@ finishing test case.`;
             subStep.sanitizerOptions,
           );
           const start = DateNow();
+          const timeoutId = startTestTimeout(subStep, definition.timeout);
 
           try {
             await testFn(subStep);
@@ -807,6 +1083,8 @@ finishing test case.`;
           } catch (error) {
             subStep.error = formatError(error);
             subStep.status = "failed";
+          } finally {
+            clearTestTimeout(timeoutId);
           }
 
           subStep.elapsed = DateNow() - start;
@@ -880,6 +1158,212 @@ finishing test case.`;
     return testFn;
   }
 
+  const benches = [];
+
+  function assertValidIterations(value, name, min) {
+    if (typeof value !== "number" || !NumberIsInteger(value) || value < min) {
+      throw new TypeError(
+        `The '${name}' option must be an integer greater than or equal to ${min}.`,
+      );
+    }
+  }
+
+  // Main bench function provided by Deno.
+  function bench(
+    nameOrFnOrOptions,
+    optionsOrFn,
+    maybeFn,
+  ) {
+    let benchDef;
+    const defaults = {
+      ignore: false,
+      only: false,
+      permissions: null,
+      warmup: 50,
+      n: 1000,
+    };
+
+    if (typeof nameOrFnOrOptions === "string") {
+      if (!nameOrFnOrOptions) {
+        throw new TypeError("The bench name can't be empty");
+      }
+      if (typeof optionsOrFn === "function") {
+        benchDef = { fn: optionsOrFn, name: nameOrFnOrOptions, ...defaults };
+      } else {
+        if (!maybeFn || typeof maybeFn !== "function") {
+          throw new TypeError("Missing bench function");
+        }
+        if (optionsOrFn.fn != undefined) {
+          throw new TypeError(
+            "Unexpected 'fn' field in options, bench function is already provided as the third argument.",
+          );
+        }
+        if (optionsOrFn.name != undefined) {
+          throw new TypeError(
+            "Unexpected 'name' field in options, bench name is already provided as the first argument.",
+          );
+        }
+        benchDef = {
+          ...defaults,
+          ...optionsOrFn,
+          fn: maybeFn,
+          name: nameOrFnOrOptions,
+        };
+      }
+    } else if (typeof nameOrFnOrOptions === "function") {
+      if (!nameOrFnOrOptions.name) {
+        throw new TypeError("The bench function must have a name");
+      }
+      if (optionsOrFn != undefined) {
+        throw new TypeError("Unexpected second argument to Deno.bench()");
+      }
+      if (maybeFn != undefined) {
+        throw new TypeError("Unexpected third argument to Deno.bench()");
+      }
+      benchDef = {
+        ...defaults,
+        fn: nameOrFnOrOptions,
+        name: nameOrFnOrOptions.name,
+      };
+    } else {
+      let fn;
+      if (typeof optionsOrFn === "function") {
+        fn = optionsOrFn;
+        if (nameOrFnOrOptions.fn != undefined) {
+          throw new TypeError(
+            "Unexpected 'fn' field in options, bench function is already provided as the second argument.",
+          );
+        }
+      } else {
+        if (
+          !nameOrFnOrOptions.fn || typeof nameOrFnOrOptions.fn !== "function"
+        ) {
+          throw new TypeError(
+            "Expected 'fn' field in the first argument to be a bench function.",
+          );
+        }
+        fn = nameOrFnOrOptions.fn;
+      }
+      const name = nameOrFnOrOptions.name ?? fn.name;
+      if (!name) {
+        throw new TypeError("The bench name can't be empty");
+      }
+      benchDef = { ...defaults, ...nameOrFnOrOptions, fn, name };
+    }
+
+    assertValidIterations(benchDef.warmup, "warmup", 0);
+    assertValidIterations(benchDef.n, "n", 1);
+
+    ArrayPrototypePush(benches, benchDef);
+  }
+
+  function benchNow() {
+    return core.opSync("op_bench_now");
+  }
+
+  // Every iteration is timed on its own so the runner can compute the
+  // distribution of the samples, not just their mean. Warmup iterations give
+  // the JIT a chance to optimize the function and are not recorded.
+  async function runBench(bench) {
+    if (bench.ignore) {
+      return "ignored";
+    }
+
+    const samples = [];
+    let measure = async function measure() {
+      for (let i = 0; i < bench.warmup + bench.n; i++) {
+        const start = benchNow();
+        const result = bench.fn();
+        if (result instanceof Promise) {
+          await result;
+        }
+        const elapsed = benchNow() - start;
+
+        if (i >= bench.warmup) {
+          ArrayPrototypePush(samples, elapsed);
+        }
+      }
+    };
+
+    if (bench.permissions) {
+      measure = withPermissions(measure, bench.permissions);
+    }
+
+    try {
+      await measure();
+      return { "ok": samples };
+    } catch (error) {
+      return { "failed": formatError(error) };
+    }
+  }
+
+  function getBenchOrigin() {
+    return core.opSync("op_get_bench_origin");
+  }
+
+  function reportBenchPlan(plan) {
+    core.opSync("op_dispatch_bench_event", {
+      plan,
+    });
+  }
+
+  function reportBenchConsoleOutput(console) {
+    core.opSync("op_dispatch_bench_event", {
+      output: { console },
+    });
+  }
+
+  function reportBenchWait(description) {
+    core.opSync("op_dispatch_bench_event", {
+      wait: description,
+    });
+  }
+
+  function reportBenchResult(description, result, elapsed) {
+    core.opSync("op_dispatch_bench_event", {
+      result: [description, result, elapsed],
+    });
+  }
+
+  async function runBenchmarks({
+    filter = null,
+  } = {}) {
+    const origin = getBenchOrigin();
+    const originalConsole = globalThis.console;
+
+    globalThis.console = new Console(reportBenchConsoleOutput);
+
+    const only = ArrayPrototypeFilter(benches, (bench) => bench.only);
+    const filtered = ArrayPrototypeFilter(
+      only.length > 0 ? only : benches,
+      createTestFilter(filter),
+    );
+
+    reportBenchPlan({
+      origin,
+      total: filtered.length,
+      filteredOut: benches.length - filtered.length,
+      usedOnly: only.length > 0,
+    });
+
+    for (const bench of filtered) {
+      const description = {
+        origin,
+        name: bench.name,
+      };
+      const earlier = DateNow();
+
+      reportBenchWait(description);
+
+      const result = await runBench(bench);
+      const elapsed = DateNow() - earlier;
+
+      reportBenchResult(description, result, elapsed);
+    }
+
+    globalThis.console = originalConsole;
+  }
+
   /**
    * @template T
    * @param value {T | undefined}
@@ -897,10 +1381,13 @@ finishing test case.`;
   window.__bootstrap.internals = {
     ...window.__bootstrap.internals ?? {},
     runTests,
+    runBenchmarks,
+    reportTestTimeout,
     enableTestSteps,
   };
 
   window.__bootstrap.testing = {
     test,
+    bench,
   };
 })(this);
@@ -141,5 +141,6 @@
     funlockSync: __bootstrap.fs.funlockSync,
     refTimer: __bootstrap.timers.refTimer,
     unrefTimer: __bootstrap.timers.unrefTimer,
+    bench: __bootstrap.testing.bench,
   };
 })(this);